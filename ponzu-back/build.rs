@@ -0,0 +1,41 @@
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Embeds the commit and the time of the build, served by `/version`.
+///
+/// The commit is read from `GIT_SHA` when set, for builds outside of a checkout, and from git
+/// otherwise.
+fn main() {
+    let sha = env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| git(&["rev-parse", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    let built_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    println!("cargo:rustc-env=PONZU_GIT_SHA={}", sha);
+    println!("cargo:rustc-env=PONZU_BUILD_TIME={}", built_at);
+
+    // Rebuild when the sources change, and when a commit moves the checked out branch
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    if let Some(dir) = git(&["rev-parse", "--git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", dir);
+        println!("cargo:rerun-if-changed={}/refs", dir);
+    }
+}
+
+/// Runs a git command, returning its trimmed output, or `None` if it failed.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8(output.stdout).ok()?;
+    Some(out.trim().to_string()).filter(|out| !out.is_empty())
+}
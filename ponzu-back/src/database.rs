@@ -1,4 +1,5 @@
 use colored::Colorize;
+use mongodb::bson::doc;
 use mongodb::error::Error;
 use mongodb::{Client, Database};
 
@@ -16,7 +17,10 @@ pub async fn init_database(conn_str: String, name: String)
     let client = Client::with_uri_str(conn_str).await?;
     let db = client.database(name.as_str());
 
-    println!("{} {}!", "Connected to database", name.bright_green());
+    // Ping the server, as the client connects lazily and would not notice it being unreachable
+    db.run_command(doc! { "ping": 1 }).await?;
+
+    println!("Connected to database {}!", name.bright_green());
 
     // Return the database client
     Ok(db)
@@ -0,0 +1,62 @@
+use crate::models::activity::{Activity, ActivityKind};
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ActivityDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub user: String,
+    pub kind: ActivityKind,
+    pub target: String,
+    pub detail: Option<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+/// The activity kinds a user publishes to their followers.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ActivitySettingsDto {
+    pub published: Vec<ActivityKind>,
+}
+
+/// Query parameters selecting a slice of an activity stream, newest first
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedQuery {
+    /// The cursor returned with the previous slice.
+    pub before: Option<String>,
+    pub limit: Option<u64>,
+}
+
+impl FeedQuery {
+    /// The requested slice size, capped at 100.
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+impl From<Activity> for ActivityDto {
+    fn from(activity: Activity) -> Self {
+        Self {
+            id: activity.id,
+            user: activity.user,
+            kind: activity.kind,
+            target: activity.target,
+            detail: activity.detail,
+            created_at: activity.created_at,
+        }
+    }
+}
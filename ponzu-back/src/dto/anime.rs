@@ -1,17 +1,27 @@
 use crate::models::anime::{Aired, AiredProp, AiredPropFromTo, Anime, Broadcast};
 use crate::models::genre::Genre;
 use crate::models::producer::Producer;
+use crate::types::jikan::{
+    deserialize_option_duration, AgeRating, AnimeSource, AnimeStatus, AnimeType, BroadcastDay,
+    EpisodeDuration,
+};
 use crate::types::links::{ExternalLink, Images, Trailer};
+use crate::types::openapi::ObjectIdJson;
 use crate::types::title_meta::{MalEntity, Relation, Theme, Title};
+use crate::types::validation::period;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
 };
+use chrono::NaiveTime;
+use chrono_tz::Tz;
 use mongodb::bson::{doc, to_bson, DateTime, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct AnimeDto {
     #[serde(
         rename = "_id",
@@ -19,6 +29,7 @@ pub struct AnimeDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub images: Images,
@@ -29,14 +40,16 @@ pub struct AnimeDto {
     pub title_english: String,
     pub title_japanese: String,
     pub title_synonyms: Vec<String>,
-    pub r#type: String,
-    pub source: String,
+    pub r#type: Option<AnimeType>,
+    pub source: AnimeSource,
     pub episodes: Option<u32>,
-    pub status: String,
+    pub status: AnimeStatus,
     pub airing: bool,
     pub aired: Option<AiredDto>,
-    pub duration: String,
-    pub rating: String,
+    /// The length of an episode, in seconds.
+    #[serde(default, deserialize_with = "deserialize_option_duration")]
+    pub duration: Option<EpisodeDuration>,
+    pub rating: Option<AgeRating>,
     pub scored_by: u64,
     pub members: u64,
     pub favorites: u64,
@@ -58,15 +71,17 @@ pub struct AnimeDto {
     pub streaming: Vec<ExternalLink>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct BroadcastDto {
-    pub day: String,
-    pub time: String,
-    pub timezone: String,
+    pub day: Option<BroadcastDay>,
+    pub time: Option<NaiveTime>,
+    #[schema(value_type = Option<String>, example = "Asia/Tokyo")]
+    pub timezone: Option<Tz>,
     pub string: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+#[validate(schema(function = "ordered_aired"))]
 pub struct AiredDto {
     #[serde(
         skip_serializing_if = "Option::is_none",
@@ -81,45 +96,56 @@ pub struct AiredDto {
     pub prop: AiredPropDto,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Checks that the airing period does not end before it starts.
+fn ordered_aired(aired: &AiredDto) -> Result<(), ValidationError> {
+    period(aired.from, aired.to)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct AiredPropDto {
     pub from: AiredPropFromToDto,
     pub to: AiredPropFromToDto,
     pub string: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct AiredPropFromToDto {
     pub day: i32,
     pub month: i32,
     pub year: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateAnimeDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
     pub images: Images,
     pub trailer: Trailer,
     pub approved: bool,
     pub titles: Vec<Title>,
+    #[validate(length(min = 1, max = 500))]
     pub title: String,
     pub title_english: String,
     pub title_japanese: String,
     pub title_synonyms: Vec<String>,
-    pub r#type: String,
-    pub source: String,
+    pub r#type: Option<AnimeType>,
+    pub source: AnimeSource,
     pub episodes: Option<u32>,
-    pub status: String,
+    pub status: AnimeStatus,
     pub airing: bool,
+    #[validate(nested)]
     pub aired: Option<AiredDto>,
-    pub duration: String,
-    pub rating: String,
+    /// The length of an episode, in seconds.
+    #[serde(default, deserialize_with = "deserialize_option_duration")]
+    pub duration: Option<EpisodeDuration>,
+    pub rating: Option<AgeRating>,
     pub scored_by: u64,
     pub members: u64,
     pub favorites: u64,
     pub synopsis: String,
     pub background: String,
     pub season: String,
+    #[validate(range(min = 1900, max = 2100))]
     pub year: Option<u32>,
     pub broadcast: Option<BroadcastDto>,
     pub producers: Vec<Producer>,
@@ -131,35 +157,42 @@ pub struct CreateAnimeDto {
     pub demographics: Vec<MalEntity>,
     pub relations: Vec<Relation>,
     pub theme: Option<Theme>,
+    #[validate(nested)]
     pub external: Vec<ExternalLink>,
+    #[validate(nested)]
     pub streaming: Vec<ExternalLink>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Validate, ToSchema)]
 pub struct UpdateAnimeDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
     pub images: Option<Images>,
     pub trailer: Option<Trailer>,
     pub approved: Option<bool>,
     pub titles: Option<Vec<Title>>,
+    #[validate(length(min = 1, max = 500))]
     pub title: Option<String>,
     pub title_english: Option<String>,
     pub title_japanese: Option<String>,
     pub title_synonyms: Option<Vec<String>>,
-    pub r#type: Option<String>,
-    pub source: Option<String>,
+    pub r#type: Option<AnimeType>,
+    pub source: Option<AnimeSource>,
     pub episodes: Option<u32>,
-    pub status: Option<String>,
+    pub status: Option<AnimeStatus>,
     pub airing: Option<bool>,
+    #[validate(nested)]
     pub aired: Option<AiredDto>,
-    pub duration: Option<String>,
-    pub rating: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_duration")]
+    pub duration: Option<EpisodeDuration>,
+    pub rating: Option<AgeRating>,
     pub scored_by: Option<u64>,
     pub members: Option<u64>,
     pub favorites: Option<u64>,
     pub synopsis: Option<String>,
     pub background: Option<String>,
     pub season: Option<String>,
+    #[validate(range(min = 1900, max = 2100))]
     pub year: Option<u32>,
     pub broadcast: Option<BroadcastDto>,
     pub producers: Option<Vec<Producer>>,
@@ -171,7 +204,9 @@ pub struct UpdateAnimeDto {
     pub demographics: Option<Vec<MalEntity>>,
     pub relations: Option<Vec<Relation>>,
     pub theme: Option<Theme>,
+    #[validate(nested)]
     pub external: Option<Vec<ExternalLink>>,
+    #[validate(nested)]
     pub streaming: Option<Vec<ExternalLink>>,
 }
 
@@ -0,0 +1,220 @@
+use crate::models::board::{Post, Topic};
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::{to_bson, DateTime, Document};
+use mongodb::options::UpdateModifications;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TopicDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub club: String,
+    pub user: String,
+    pub title: String,
+    pub pinned: bool,
+    pub locked: bool,
+    pub post_count: u64,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub last_post_at: DateTime,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+/// A new topic and its first post. The club and the author come from the request.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreateTopicDto {
+    #[serde(skip_deserializing, default)]
+    pub club: String,
+    #[serde(skip_deserializing, default)]
+    pub user: String,
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(min = 1, max = 20000))]
+    pub body: String,
+}
+
+/// Changes to a topic. Pinning and locking are reserved to officers.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct UpdateTopicDto {
+    #[validate(length(min = 1, max = 200))]
+    pub title: Option<String>,
+    pub pinned: Option<bool>,
+    pub locked: Option<bool>,
+    #[serde(skip_deserializing, default)]
+    pub last_post_at: Option<DateTime>,
+}
+
+/// A topic as listed to a reader.
+///
+/// `unread` is only set for members, and tells whether the topic has posts they have not read yet.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TopicViewDto {
+    #[serde(flatten)]
+    pub topic: TopicDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unread: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PostDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub topic: String,
+    pub user: String,
+    pub body: String,
+    pub mentions: Vec<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    pub edit_count: u32,
+}
+
+/// The body of a new post. The topic, the author and the mentions come from the request.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreatePostDto {
+    #[serde(skip_deserializing, default)]
+    pub topic: String,
+    #[serde(skip_deserializing, default)]
+    pub user: String,
+    #[validate(length(min = 1, max = 20000))]
+    pub body: String,
+    #[serde(skip_deserializing, default)]
+    pub mentions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct UpdatePostDto {
+    #[validate(length(min = 1, max = 20000))]
+    pub body: Option<String>,
+    #[serde(skip_deserializing, default)]
+    pub mentions: Option<Vec<String>>,
+}
+
+impl From<Topic> for TopicDto {
+    fn from(topic: Topic) -> Self {
+        Self {
+            id: topic.id,
+            club: topic.club,
+            user: topic.user,
+            title: topic.title,
+            pinned: topic.pinned,
+            locked: topic.locked,
+            post_count: topic.post_count,
+            last_post_at: topic.last_post_at,
+            created_at: topic.created_at,
+        }
+    }
+}
+
+impl From<CreateTopicDto> for Topic {
+    fn from(dto: CreateTopicDto) -> Self {
+        let now = DateTime::now();
+        Self {
+            id: None,
+            club: dto.club,
+            user: dto.user,
+            title: dto.title,
+            pinned: false,
+            locked: false,
+            post_count: 0,
+            last_post_at: now,
+            created_at: now,
+        }
+    }
+}
+
+impl From<UpdateTopicDto> for UpdateModifications {
+    fn from(dto: UpdateTopicDto) -> Self {
+        let mut doc = Document::new();
+
+        if let Some(title) = dto.title {
+            doc.insert("title", title);
+        }
+        if let Some(pinned) = dto.pinned {
+            doc.insert("pinned", pinned);
+        }
+        if let Some(locked) = dto.locked {
+            doc.insert("locked", locked);
+        }
+        if let Some(last_post_at) = dto.last_post_at {
+            doc.insert(
+                "last_post_at",
+                to_bson(&last_post_at.try_to_rfc3339_string().unwrap_or_default())
+                    .expect("Failed to convert last_post_at to bson"),
+            );
+        }
+
+        UpdateModifications::Document(doc)
+    }
+}
+
+impl From<Post> for PostDto {
+    fn from(post: Post) -> Self {
+        Self {
+            id: post.id,
+            topic: post.topic,
+            user: post.user,
+            body: post.body,
+            mentions: post.mentions,
+            created_at: post.created_at,
+            edited_at: post.edited_at,
+            edit_count: post.edit_count,
+        }
+    }
+}
+
+impl From<CreatePostDto> for Post {
+    fn from(dto: CreatePostDto) -> Self {
+        Self {
+            id: None,
+            topic: dto.topic,
+            user: dto.user,
+            body: dto.body,
+            mentions: dto.mentions,
+            created_at: DateTime::now(),
+            edited_at: None,
+            edit_count: 0,
+        }
+    }
+}
+
+impl From<UpdatePostDto> for UpdateModifications {
+    fn from(dto: UpdatePostDto) -> Self {
+        let mut doc = Document::new();
+
+        if let Some(body) = dto.body {
+            doc.insert("body", body);
+        }
+        if let Some(mentions) = dto.mentions {
+            doc.insert(
+                "mentions",
+                to_bson(&mentions).expect("Failed to convert mentions to bson"),
+            );
+        }
+
+        UpdateModifications::Document(doc)
+    }
+}
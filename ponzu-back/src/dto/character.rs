@@ -1,14 +1,19 @@
 use crate::models::character::{Character, CharacterMedia, CharacterVoice};
 use crate::types::links::Images;
+use crate::types::openapi::ObjectIdJson;
+use crate::types::validation::url_or_empty;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
 };
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
 /// A simplified character object.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CharacterReference {
     pub mal_id: u64,
     pub url: String,
@@ -16,7 +21,7 @@ pub struct CharacterReference {
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CharacterDto {
     #[serde(
         rename = "_id",
@@ -24,6 +29,7 @@ pub struct CharacterDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub url: String,
@@ -38,23 +44,26 @@ pub struct CharacterDto {
     pub voices: Vec<CharacterVoiceDto>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CharacterMediaDto {
     pub role: String,
     pub media: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CharacterVoiceDto {
     pub language: String,
     pub person: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateCharacterDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: String,
     pub images: Images,
+    #[validate(length(min = 1, max = 200))]
     pub name: String,
     pub name_kanji: String,
     pub nicknames: Vec<String>,
@@ -65,11 +74,14 @@ pub struct CreateCharacterDto {
     pub voices: Vec<CharacterVoiceDto>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateCharacterDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: Option<String>,
     pub images: Option<Images>,
+    #[validate(length(min = 1, max = 200))]
     pub name: Option<String>,
     pub name_kanji: Option<String>,
     pub nicknames: Option<Vec<String>>,
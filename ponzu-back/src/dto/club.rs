@@ -1,4 +1,10 @@
-use crate::models::club::Club;
+use crate::dto::anime::AnimeDto;
+use crate::dto::manga::MangaDto;
+use crate::models::club::{
+    Club, ClubAccess, ClubBan, ClubMember, ClubRequest, ClubRequestKind, ClubRole,
+};
+use crate::types::links::Images;
+use crate::types::openapi::ObjectIdJson;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
 };
@@ -8,8 +14,10 @@ use serde::{Deserialize, Serialize};
 
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ClubDto {
     #[serde(
         rename = "_id",
@@ -17,34 +25,142 @@ pub struct ClubDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub name: String,
     pub description: Option<String>,
-    pub members: Vec<String>,
-    pub access: String,
+    pub access: ClubAccess,
     pub category: String,
+    pub anime: Vec<String>,
+    pub manga: Vec<String>,
+    pub characters: Vec<String>,
+    pub people: Vec<String>,
+    /// The linked anime and manga the caller may read, filled in when the club is served.
+    #[serde(default)]
+    pub titles: Vec<LinkedTitleDto>,
+    pub member_count: u64,
     #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
     pub created_at: DateTime,
     #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
     pub updated_at: DateTime,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// A summary of an anime or manga linked to a club.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct LinkedTitleDto {
+    pub id: String,
+    /// Either `anime` or `manga`.
+    pub r#type: String,
+    pub title: String,
+    pub images: Images,
+}
+
+impl From<AnimeDto> for LinkedTitleDto {
+    fn from(anime: AnimeDto) -> Self {
+        Self {
+            id: anime.id.unwrap_or_default(),
+            r#type: "anime".to_string(),
+            title: anime.title,
+            images: anime.images,
+        }
+    }
+}
+
+impl From<MangaDto> for LinkedTitleDto {
+    fn from(manga: MangaDto) -> Self {
+        Self {
+            id: manga.id.unwrap_or_default(),
+            r#type: "manga".to_string(),
+            title: manga.title,
+            images: manga.images,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateClubDto {
+    #[validate(length(min = 1, max = 100))]
     pub name: String,
+    #[validate(length(max = 5000))]
     pub description: Option<String>,
-    pub members: Vec<String>,
-    pub access: String,
+    pub access: ClubAccess,
+    #[validate(length(min = 1, max = 50))]
     pub category: String,
+    #[serde(default)]
+    pub anime: Vec<String>,
+    #[serde(default)]
+    pub manga: Vec<String>,
+    #[serde(default)]
+    pub characters: Vec<String>,
+    #[serde(default)]
+    pub people: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateClubDto {
+    #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
+    #[validate(length(max = 5000))]
     pub description: Option<String>,
-    pub members: Option<Vec<String>>,
-    pub access: Option<String>,
+    pub access: Option<ClubAccess>,
+    #[validate(length(min = 1, max = 50))]
     pub category: Option<String>,
+    pub anime: Option<Vec<String>>,
+    pub manga: Option<Vec<String>>,
+    pub characters: Option<Vec<String>>,
+    pub people: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ClubMemberDto {
+    pub user: String,
+    pub role: ClubRole,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub joined_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ClubRequestDto {
+    pub user: String,
+    pub kind: ClubRequestKind,
+    pub invited_by: Option<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ClubBanDto {
+    pub user: String,
+    pub banned_by: String,
+    pub reason: Option<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct InviteMemberDto {
+    pub user: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BanMemberDto {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SetMemberRoleDto {
+    pub role: ClubRole,
+}
+
+/// The outcome of a join or an invitation.
+///
+/// Private clubs record a join request instead of adding the member right away, and invited
+/// users are only added once they join.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum JoinClubDto {
+    Joined { member: ClubMemberDto },
+    Pending { request: ClubRequestDto },
 }
 
 impl From<Club> for ClubDto {
@@ -53,9 +169,14 @@ impl From<Club> for ClubDto {
             id: club.id,
             name: club.name,
             description: club.description,
-            members: club.members,
             access: club.access,
             category: club.category,
+            anime: club.anime,
+            manga: club.manga,
+            characters: club.characters,
+            people: club.people,
+            titles: Vec::new(),
+            member_count: club.member_count,
             created_at: club.created_at,
             updated_at: club.updated_at,
         }
@@ -69,9 +190,13 @@ impl From<CreateClubDto> for Club {
             id: None,
             name: dto.name,
             description: dto.description,
-            members: dto.members,
             access: dto.access,
             category: dto.category,
+            anime: dto.anime,
+            manga: dto.manga,
+            characters: dto.characters,
+            people: dto.people,
+            member_count: 0,
             created_at: now,
             updated_at: now,
         }
@@ -94,12 +219,6 @@ impl From<UpdateClubDto> for UpdateModifications {
                 to_bson(&description).expect("Failed to convert description to bson"),
             );
         }
-        if let Some(members) = dto.members {
-            doc.insert(
-                "members",
-                to_bson(&members).expect("Failed to convert members to bson"),
-            );
-        }
         if let Some(access) = dto.access {
             doc.insert(
                 "access",
@@ -112,7 +231,70 @@ impl From<UpdateClubDto> for UpdateModifications {
                 to_bson(&category).expect("Failed to convert category to bson"),
             );
         }
+        if let Some(anime) = dto.anime {
+            doc.insert(
+                "anime",
+                to_bson(&anime).expect("Failed to convert anime to bson"),
+            );
+        }
+        if let Some(manga) = dto.manga {
+            doc.insert(
+                "manga",
+                to_bson(&manga).expect("Failed to convert manga to bson"),
+            );
+        }
+        if let Some(characters) = dto.characters {
+            doc.insert(
+                "characters",
+                to_bson(&characters).expect("Failed to convert characters to bson"),
+            );
+        }
+        if let Some(people) = dto.people {
+            doc.insert(
+                "people",
+                to_bson(&people).expect("Failed to convert people to bson"),
+            );
+        }
+        if !doc.is_empty() {
+            doc.insert(
+                "updated_at",
+                to_bson(&DateTime::now().try_to_rfc3339_string().unwrap_or_default())
+                    .expect("Failed to convert updated_at to bson"),
+            );
+        }
 
         UpdateModifications::Document(doc)
     }
 }
+
+impl From<ClubMember> for ClubMemberDto {
+    fn from(member: ClubMember) -> Self {
+        Self {
+            user: member.user,
+            role: member.role,
+            joined_at: member.joined_at,
+        }
+    }
+}
+
+impl From<ClubRequest> for ClubRequestDto {
+    fn from(request: ClubRequest) -> Self {
+        Self {
+            user: request.user,
+            kind: request.kind,
+            invited_by: request.invited_by,
+            created_at: request.created_at,
+        }
+    }
+}
+
+impl From<ClubBan> for ClubBanDto {
+    fn from(ban: ClubBan) -> Self {
+        Self {
+            user: ban.user,
+            banned_by: ban.banned_by,
+            reason: ban.reason,
+            created_at: ban.created_at,
+        }
+    }
+}
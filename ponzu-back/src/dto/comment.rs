@@ -0,0 +1,147 @@
+use crate::dto::review::ReactionsDto;
+use crate::models::comment::Comment;
+use crate::models::review::Reactions;
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::{DateTime, Document};
+use mongodb::options::UpdateModifications;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CommentDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub review: String,
+    pub parent: Option<String>,
+    pub depth: u32,
+    pub user: String,
+    pub body: String,
+    pub reactions: ReactionsDto,
+    pub reply_count: u64,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    pub edit_count: u32,
+}
+
+/// The body of a new comment. The review, the author and the depth come from the request.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreateCommentDto {
+    #[serde(skip_deserializing, default)]
+    pub review: String,
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(skip_deserializing, default)]
+    pub depth: u32,
+    #[serde(skip_deserializing, default)]
+    pub user: String,
+    #[validate(length(min = 1, max = 10000))]
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct UpdateCommentDto {
+    #[validate(length(min = 1, max = 10000))]
+    pub body: Option<String>,
+}
+
+/// Query parameters selecting a slice of a comment thread
+///
+/// Without `parent`, the top-level comments are listed. `after` is the `next` cursor of the
+/// previous slice.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommentQuery {
+    pub parent: Option<String>,
+    pub after: Option<String>,
+    pub limit: Option<u64>,
+}
+
+impl CommentQuery {
+    /// The requested slice size, capped at 100.
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+impl From<Comment> for CommentDto {
+    fn from(comment: Comment) -> Self {
+        Self {
+            id: comment.id,
+            review: comment.review,
+            parent: comment.parent,
+            depth: comment.depth,
+            user: comment.user,
+            body: comment.body,
+            reactions: comment.reactions.into(),
+            reply_count: comment.reply_count,
+            created_at: comment.created_at,
+            edited_at: comment.edited_at,
+            edit_count: comment.edit_count,
+        }
+    }
+}
+
+impl From<CommentDto> for Comment {
+    fn from(dto: CommentDto) -> Self {
+        Self {
+            id: dto.id,
+            review: dto.review,
+            parent: dto.parent,
+            depth: dto.depth,
+            user: dto.user,
+            body: dto.body,
+            reactions: dto.reactions.into(),
+            reply_count: dto.reply_count,
+            created_at: dto.created_at,
+            edited_at: dto.edited_at,
+            edit_count: dto.edit_count,
+        }
+    }
+}
+
+impl From<CreateCommentDto> for Comment {
+    fn from(dto: CreateCommentDto) -> Self {
+        Self {
+            id: None,
+            review: dto.review,
+            parent: dto.parent,
+            depth: dto.depth,
+            user: dto.user,
+            body: dto.body,
+            reactions: Reactions::default(),
+            reply_count: 0,
+            created_at: DateTime::now(),
+            edited_at: None,
+            edit_count: 0,
+        }
+    }
+}
+
+impl From<UpdateCommentDto> for UpdateModifications {
+    fn from(dto: UpdateCommentDto) -> Self {
+        let mut doc = Document::new();
+
+        if let Some(body) = dto.body {
+            doc.insert("body", body);
+        }
+
+        UpdateModifications::Document(doc)
+    }
+}
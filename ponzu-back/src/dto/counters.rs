@@ -0,0 +1,34 @@
+use mongodb::bson::Document;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A denormalized counter whose stored value differs from the recomputed one.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct CounterDriftDto {
+    pub collection: String,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub field: String,
+    pub stored: u64,
+    pub expected: u64,
+}
+
+impl CounterDriftDto {
+    /// Creates a drift entry for a counter of the given document.
+    pub fn new(
+        collection: &str,
+        document: &Document,
+        field: &str,
+        stored: u64,
+        expected: u64,
+    ) -> Self {
+        Self {
+            collection: collection.to_string(),
+            id: document.get_object_id("_id").ok().map(|id| id.to_hex()),
+            name: document.get_str("name").ok().map(str::to_string),
+            field: field.to_string(),
+            stored,
+            expected,
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use crate::types::links::Images;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// A lightweight representation of either an anime or manga entry.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct EntryDto {
     pub id: String,
     pub mal_id: u64,
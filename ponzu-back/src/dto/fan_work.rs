@@ -0,0 +1,443 @@
+use crate::models::fan_work::{
+    Chapter, ContentRating, ContentWarning, FanWork, FanWorkAction, FanWorkActionType, FanWorkKind,
+    FanWorkReport, FanWorkStatus,
+};
+use crate::models::moderation::ReportReason;
+use crate::types::links::Images;
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::{doc, to_bson, DateTime, Document};
+use mongodb::options::UpdateModifications;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FanWorkDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub user: String,
+    pub kind: FanWorkKind,
+    pub title: String,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+    pub rating: ContentRating,
+    pub warnings: Vec<ContentWarning>,
+    pub anime: Vec<String>,
+    pub manga: Vec<String>,
+    pub characters: Vec<String>,
+    pub image: Option<Images>,
+    pub chapter_count: u64,
+    pub word_count: u64,
+    pub like_count: u64,
+    pub status: FanWorkStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hold_reason: Option<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    pub edit_count: u32,
+}
+
+/// The body of a new fan work. The author comes from the request, the image is uploaded afterwards.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreateFanWorkDto {
+    #[serde(skip_deserializing, default)]
+    pub user: String,
+    pub kind: FanWorkKind,
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(max = 2000))]
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[validate(length(max = 20))]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub rating: ContentRating,
+    #[serde(default)]
+    pub warnings: Vec<ContentWarning>,
+    #[serde(default)]
+    pub anime: Vec<String>,
+    #[serde(default)]
+    pub manga: Vec<String>,
+    #[serde(default)]
+    pub characters: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct UpdateFanWorkDto {
+    #[validate(length(min = 1, max = 200))]
+    pub title: Option<String>,
+    #[validate(length(max = 2000))]
+    pub summary: Option<String>,
+    #[validate(length(max = 20))]
+    pub tags: Option<Vec<String>>,
+    pub rating: Option<ContentRating>,
+    pub warnings: Option<Vec<ContentWarning>>,
+    pub anime: Option<Vec<String>>,
+    pub manga: Option<Vec<String>>,
+    pub characters: Option<Vec<String>>,
+}
+
+/// Query parameters filtering the fan work listing
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FanWorkQuery {
+    pub kind: Option<FanWorkKind>,
+    pub tag: Option<String>,
+    /// The highest content rating listed.
+    pub max_rating: Option<ContentRating>,
+    pub anime: Option<String>,
+    pub manga: Option<String>,
+    pub character: Option<String>,
+    pub user: Option<String>,
+}
+
+impl FanWorkQuery {
+    /// Builds the filter selecting the fan works of the listing. Held and hidden works are never listed.
+    pub fn filter(&self) -> Document {
+        let mut filter = doc! { "status": "published" };
+        if let Some(kind) = self.kind {
+            filter.insert(
+                "kind",
+                to_bson(&kind).expect("Failed to convert kind to bson"),
+            );
+        }
+        if let Some(max) = self.max_rating {
+            let ratings = ContentRating::ALL
+                .into_iter()
+                .filter(|r| *r <= max)
+                .collect::<Vec<_>>();
+            filter.insert(
+                "rating",
+                doc! { "$in": to_bson(&ratings).expect("Failed to convert ratings to bson") },
+            );
+        }
+        for (field, value) in [
+            ("tags", &self.tag),
+            ("anime", &self.anime),
+            ("manga", &self.manga),
+            ("characters", &self.character),
+            ("user", &self.user),
+        ] {
+            if let Some(value) = value {
+                filter.insert(field, value);
+            }
+        }
+        filter
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ChapterDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub work: String,
+    pub number: u32,
+    pub title: Option<String>,
+    pub body: String,
+    pub word_count: u64,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    pub edit_count: u32,
+}
+
+/// The body of a new chapter. The work and the number come from the request.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreateChapterDto {
+    #[serde(skip_deserializing, default)]
+    pub work: String,
+    #[serde(skip_deserializing, default)]
+    pub number: u32,
+    #[validate(length(max = 200))]
+    #[serde(default)]
+    pub title: Option<String>,
+    #[validate(length(min = 1))]
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct UpdateChapterDto {
+    #[validate(length(max = 200))]
+    pub title: Option<String>,
+    #[validate(length(min = 1))]
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FanWorkReportDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub work: String,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub resolved: bool,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+/// A staff action on a fan work. `rating` is required by `rerate`.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ModerateFanWorkDto {
+    pub action: FanWorkActionType,
+    pub rating: Option<ContentRating>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FanWorkActionDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub work: String,
+    pub user: String,
+    pub actor: String,
+    pub action: FanWorkActionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<ContentRating>,
+    pub note: Option<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+/// A fan work together with whether the caller likes it.
+///
+/// `liked` is left out for anonymous callers.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct LikedFanWorkDto {
+    #[serde(flatten)]
+    pub work: FanWorkDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liked: Option<bool>,
+}
+
+/// A fan work waiting for moderation, with its open reports.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct FanWorkQueueItemDto {
+    pub work: FanWorkDto,
+    pub weight: u64,
+    pub reports: u64,
+    pub reasons: Vec<ReportReason>,
+}
+
+impl From<FanWork> for FanWorkDto {
+    fn from(work: FanWork) -> Self {
+        Self {
+            id: work.id,
+            user: work.user,
+            kind: work.kind,
+            title: work.title,
+            summary: work.summary,
+            tags: work.tags,
+            rating: work.rating,
+            warnings: work.warnings,
+            anime: work.anime,
+            manga: work.manga,
+            characters: work.characters,
+            image: work.image,
+            chapter_count: work.chapter_count,
+            word_count: work.word_count,
+            like_count: work.like_count,
+            status: work.status,
+            hold_reason: work.hold_reason,
+            created_at: work.created_at,
+            edited_at: work.edited_at,
+            edit_count: work.edit_count,
+        }
+    }
+}
+
+impl From<CreateFanWorkDto> for FanWork {
+    fn from(dto: CreateFanWorkDto) -> Self {
+        Self {
+            id: None,
+            user: dto.user,
+            kind: dto.kind,
+            title: dto.title,
+            summary: dto.summary,
+            tags: dto.tags,
+            rating: dto.rating,
+            warnings: dto.warnings,
+            anime: dto.anime,
+            manga: dto.manga,
+            characters: dto.characters,
+            image: None,
+            chapter_count: 0,
+            word_count: 0,
+            like_count: 0,
+            status: FanWorkStatus::Published,
+            hold_reason: None,
+            created_at: DateTime::now(),
+            edited_at: None,
+            edit_count: 0,
+        }
+    }
+}
+
+impl From<UpdateFanWorkDto> for UpdateModifications {
+    fn from(dto: UpdateFanWorkDto) -> Self {
+        let mut doc = Document::new();
+
+        if let Some(title) = dto.title {
+            doc.insert("title", title);
+        }
+        if let Some(summary) = dto.summary {
+            doc.insert("summary", summary);
+        }
+        if let Some(tags) = dto.tags {
+            doc.insert(
+                "tags",
+                to_bson(&tags).expect("Failed to convert tags to bson"),
+            );
+        }
+        if let Some(rating) = dto.rating {
+            doc.insert(
+                "rating",
+                to_bson(&rating).expect("Failed to convert rating to bson"),
+            );
+        }
+        if let Some(warnings) = dto.warnings {
+            doc.insert(
+                "warnings",
+                to_bson(&warnings).expect("Failed to convert warnings to bson"),
+            );
+        }
+        if let Some(anime) = dto.anime {
+            doc.insert(
+                "anime",
+                to_bson(&anime).expect("Failed to convert anime to bson"),
+            );
+        }
+        if let Some(manga) = dto.manga {
+            doc.insert(
+                "manga",
+                to_bson(&manga).expect("Failed to convert manga to bson"),
+            );
+        }
+        if let Some(characters) = dto.characters {
+            doc.insert(
+                "characters",
+                to_bson(&characters).expect("Failed to convert characters to bson"),
+            );
+        }
+
+        UpdateModifications::Document(doc)
+    }
+}
+
+impl From<Chapter> for ChapterDto {
+    fn from(chapter: Chapter) -> Self {
+        Self {
+            id: chapter.id,
+            work: chapter.work,
+            number: chapter.number,
+            title: chapter.title,
+            body: chapter.body,
+            word_count: chapter.word_count,
+            created_at: chapter.created_at,
+            edited_at: chapter.edited_at,
+            edit_count: chapter.edit_count,
+        }
+    }
+}
+
+impl From<CreateChapterDto> for Chapter {
+    fn from(dto: CreateChapterDto) -> Self {
+        Self {
+            id: None,
+            work: dto.work,
+            number: dto.number,
+            title: dto.title,
+            word_count: count_words(&dto.body),
+            body: dto.body,
+            created_at: DateTime::now(),
+            edited_at: None,
+            edit_count: 0,
+        }
+    }
+}
+
+impl From<UpdateChapterDto> for UpdateModifications {
+    fn from(dto: UpdateChapterDto) -> Self {
+        let mut doc = Document::new();
+
+        if let Some(title) = dto.title {
+            doc.insert("title", title);
+        }
+        if let Some(body) = dto.body {
+            doc.insert("word_count", count_words(&body) as i64);
+            doc.insert("body", body);
+        }
+
+        UpdateModifications::Document(doc)
+    }
+}
+
+impl From<FanWorkReport> for FanWorkReportDto {
+    fn from(report: FanWorkReport) -> Self {
+        Self {
+            id: report.id,
+            work: report.work,
+            reason: report.reason,
+            comment: report.comment,
+            resolved: report.resolved,
+            created_at: report.created_at,
+        }
+    }
+}
+
+impl From<FanWorkAction> for FanWorkActionDto {
+    fn from(action: FanWorkAction) -> Self {
+        Self {
+            id: action.id,
+            work: action.work,
+            user: action.user,
+            actor: action.actor,
+            action: action.action,
+            rating: action.rating,
+            note: action.note,
+            created_at: action.created_at,
+        }
+    }
+}
+
+/// Counts the words of a chapter, as runs of characters between whitespace.
+fn count_words(text: &str) -> u64 {
+    text.split_whitespace().count() as u64
+}
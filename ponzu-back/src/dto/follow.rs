@@ -0,0 +1,23 @@
+use crate::models::follow::Follow;
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FollowDto {
+    pub follower: String,
+    pub followee: String,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+impl From<Follow> for FollowDto {
+    fn from(follow: Follow) -> Self {
+        Self {
+            follower: follow.follower,
+            followee: follow.followee,
+            created_at: follow.created_at,
+        }
+    }
+}
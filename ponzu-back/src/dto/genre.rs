@@ -1,12 +1,16 @@
 use crate::models::genre::Genre;
+use crate::types::openapi::ObjectIdJson;
+use crate::types::validation::entry_type;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
 };
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct GenreDto {
     #[serde(
         rename = "_id",
@@ -14,6 +18,7 @@ pub struct GenreDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub r#type: String,
@@ -21,18 +26,24 @@ pub struct GenreDto {
     pub count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateGenreDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
+    #[validate(custom(function = "entry_type"))]
     pub r#type: String,
+    #[validate(length(min = 1, max = 100))]
     pub name: String,
     pub count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateGenreDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
+    #[validate(custom(function = "entry_type"))]
     pub r#type: Option<String>,
+    #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
     pub count: Option<u64>,
 }
@@ -0,0 +1,47 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Whether the server or one of its dependencies works.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// Whether the server process is running.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct LivenessDto {
+    pub status: HealthStatus,
+}
+
+/// Whether the server can serve requests, along with the state of each of its dependencies.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ReadinessDto {
+    /// `down` when a dependency is down or the server is shutting down.
+    pub status: HealthStatus,
+    pub shutting_down: bool,
+    pub dependencies: Vec<DependencyDto>,
+}
+
+/// The state of a dependency of the server.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct DependencyDto {
+    pub name: String,
+    pub status: HealthStatus,
+    /// How long the dependency took to answer, or to time out, in milliseconds.
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The build of the running server.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct VersionDto {
+    /// The version of the crate.
+    pub version: String,
+    /// The commit the server was built from, or `unknown`.
+    pub git_sha: String,
+    /// When the server was built, in RFC 3339.
+    pub build_time: String,
+}
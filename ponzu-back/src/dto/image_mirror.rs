@@ -0,0 +1,9 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// The outcome of a mirroring run.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct MirrorRunDto {
+    /// The number of newly mirrored images.
+    pub mirrored: u64,
+}
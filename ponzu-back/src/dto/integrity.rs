@@ -0,0 +1,12 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A declared reference whose IDs point at missing or trashed documents.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct DanglingReferenceDto {
+    pub source: String,
+    pub field: String,
+    pub targets: Vec<String>,
+    pub missing: Vec<String>,
+    pub documents: u64,
+}
@@ -0,0 +1,130 @@
+use crate::models::list_entry::{ListEntry, ListStatus};
+use crate::types::openapi::ObjectIdJson;
+use crate::types::validation::entry_type;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::{to_bson, DateTime, Document};
+use mongodb::options::UpdateModifications;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ListEntryDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub user: String,
+    pub entry: String,
+    pub r#type: String,
+    pub status: ListStatus,
+    pub progress: u32,
+    pub score: Option<u8>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub updated_at: DateTime,
+}
+
+/// The body of a list entry. The user and the entry come from the request.
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreateListEntryDto {
+    #[serde(default)]
+    pub user: String,
+    #[serde(default)]
+    pub entry: String,
+    #[validate(custom(function = "entry_type"))]
+    pub r#type: String,
+    pub status: ListStatus,
+    #[serde(default)]
+    pub progress: u32,
+    #[validate(range(min = 1, max = 10))]
+    pub score: Option<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct UpdateListEntryDto {
+    pub status: Option<ListStatus>,
+    pub progress: Option<u32>,
+    #[validate(range(min = 1, max = 10))]
+    pub score: Option<u8>,
+}
+
+impl From<ListEntry> for ListEntryDto {
+    fn from(entry: ListEntry) -> Self {
+        Self {
+            id: entry.id,
+            user: entry.user,
+            entry: entry.entry,
+            r#type: entry.r#type,
+            status: entry.status,
+            progress: entry.progress,
+            score: entry.score,
+            updated_at: entry.updated_at,
+        }
+    }
+}
+
+impl From<CreateListEntryDto> for ListEntry {
+    fn from(dto: CreateListEntryDto) -> Self {
+        Self {
+            id: None,
+            user: dto.user,
+            entry: dto.entry,
+            r#type: dto.r#type,
+            status: dto.status,
+            progress: dto.progress,
+            score: dto.score,
+            updated_at: DateTime::now(),
+        }
+    }
+}
+
+impl From<CreateListEntryDto> for UpdateListEntryDto {
+    fn from(dto: CreateListEntryDto) -> Self {
+        Self {
+            status: Some(dto.status),
+            progress: Some(dto.progress),
+            score: dto.score,
+        }
+    }
+}
+
+impl From<UpdateListEntryDto> for UpdateModifications {
+    fn from(dto: UpdateListEntryDto) -> Self {
+        let mut doc = Document::new();
+
+        if let Some(status) = dto.status {
+            doc.insert(
+                "status",
+                to_bson(&status).expect("Failed to convert status to bson"),
+            );
+        }
+        if let Some(progress) = dto.progress {
+            doc.insert(
+                "progress",
+                to_bson(&progress).expect("Failed to convert progress to bson"),
+            );
+        }
+        if let Some(score) = dto.score {
+            doc.insert(
+                "score",
+                to_bson(&score).expect("Failed to convert score to bson"),
+            );
+        }
+        if !doc.is_empty() {
+            doc.insert(
+                "updated_at",
+                to_bson(&DateTime::now().try_to_rfc3339_string().unwrap_or_default())
+                    .expect("Failed to convert updated_at to bson"),
+            );
+        }
+
+        UpdateModifications::Document(doc)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::models::magazine::Magazine;
+use crate::types::openapi::ObjectIdJson;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
 };
@@ -6,8 +7,10 @@ use serde::{Deserialize, Serialize};
 
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct MagazineDto {
     #[serde(
         rename = "_id",
@@ -15,22 +18,27 @@ pub struct MagazineDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub name: String,
     pub count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateMagazineDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
+    #[validate(length(min = 1, max = 100))]
     pub name: String,
     pub count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateMagazineDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
+    #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
     pub count: Option<u64>,
 }
@@ -1,7 +1,10 @@
 use crate::models::genre::Genre;
 use crate::models::manga::{Manga, Published, PublishedProp, PublishedPropFromTo};
+use crate::types::jikan::{MangaStatus, MangaType};
 use crate::types::links::{ExternalLink, Images};
+use crate::types::openapi::ObjectIdJson;
 use crate::types::title_meta::{MalEntity, Relation, Title};
+use crate::types::validation::period;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
@@ -10,8 +13,10 @@ use mongodb::bson::DateTime;
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct MangaDto {
     #[serde(
         rename = "_id",
@@ -19,6 +24,7 @@ pub struct MangaDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub images: Images,
@@ -28,10 +34,10 @@ pub struct MangaDto {
     pub title_english: String,
     pub title_japanese: String,
     pub title_synonyms: Vec<String>,
-    pub r#type: String,
+    pub r#type: Option<MangaType>,
     pub chapters: Option<u32>,
     pub volumes: Option<u32>,
-    pub status: String,
+    pub status: MangaStatus,
     pub publishing: bool,
     pub published: PublishedDto,
     pub scored_by: u64,
@@ -49,7 +55,8 @@ pub struct MangaDto {
     pub external: Vec<ExternalLink>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+#[validate(schema(function = "ordered_published"))]
 pub struct PublishedDto {
     #[serde(
         skip_serializing_if = "Option::is_none",
@@ -64,35 +71,43 @@ pub struct PublishedDto {
     pub prop: PublishedPropDto,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Checks that the publishing period does not end before it starts.
+fn ordered_published(published: &PublishedDto) -> Result<(), ValidationError> {
+    period(published.from, published.to)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct PublishedPropDto {
     pub from: PublishedPropFromToDto,
     pub to: PublishedPropFromToDto,
     pub string: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct PublishedPropFromToDto {
     pub day: i32,
     pub month: i32,
     pub year: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateMangaDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
     pub images: Images,
     pub approved: bool,
     pub titles: Vec<Title>,
+    #[validate(length(min = 1, max = 500))]
     pub title: String,
     pub title_english: String,
     pub title_japanese: String,
     pub title_synonyms: Vec<String>,
-    pub r#type: String,
+    pub r#type: Option<MangaType>,
     pub chapters: Option<u32>,
     pub volumes: Option<u32>,
-    pub status: String,
+    pub status: MangaStatus,
     pub publishing: bool,
+    #[validate(nested)]
     pub published: PublishedDto,
     pub scored_by: u64,
     pub members: u64,
@@ -106,24 +121,28 @@ pub struct CreateMangaDto {
     pub themes: Vec<MalEntity>,
     pub demographics: Vec<MalEntity>,
     pub relations: Vec<Relation>,
+    #[validate(nested)]
     pub external: Vec<ExternalLink>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Validate, ToSchema)]
 pub struct UpdateMangaDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
     pub images: Option<Images>,
     pub approved: Option<bool>,
     pub titles: Option<Vec<Title>>,
+    #[validate(length(min = 1, max = 500))]
     pub title: Option<String>,
     pub title_english: Option<String>,
     pub title_japanese: Option<String>,
     pub title_synonyms: Option<Vec<String>>,
-    pub r#type: Option<String>,
+    pub r#type: Option<MangaType>,
     pub chapters: Option<u32>,
     pub volumes: Option<u32>,
-    pub status: Option<String>,
+    pub status: Option<MangaStatus>,
     pub publishing: Option<bool>,
+    #[validate(nested)]
     pub published: Option<PublishedDto>,
     pub scored_by: Option<u64>,
     pub members: Option<u64>,
@@ -137,6 +156,7 @@ pub struct UpdateMangaDto {
     pub themes: Option<Vec<MalEntity>>,
     pub demographics: Option<Vec<MalEntity>>,
     pub relations: Option<Vec<Relation>>,
+    #[validate(nested)]
     pub external: Option<Vec<ExternalLink>>,
 }
 
@@ -1,12 +1,28 @@
+pub mod activity;
 pub mod anime;
+pub mod board;
 pub mod character;
 pub mod club;
+pub mod comment;
+pub mod counters;
 pub mod entry;
+pub mod fan_work;
+pub mod follow;
 pub mod genre;
+pub mod health;
+pub mod image_mirror;
+pub mod integrity;
+pub mod list_entry;
 pub mod magazine;
 pub mod manga;
+pub mod moderation;
+pub mod notification;
 pub mod pagination;
 pub mod person;
 pub mod producer;
+pub mod profile;
+pub mod reaction;
 pub mod review;
+pub mod revision;
+pub mod trash;
 pub mod user;
@@ -0,0 +1,99 @@
+use crate::dto::review::ReviewDto;
+use crate::models::moderation::{
+    ModerationAction, ModerationActionType, ReportReason, ReviewReport,
+};
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ReportDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub review: String,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub resolved: bool,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
+pub struct CreateReportDto {
+    pub reason: ReportReason,
+    #[validate(length(max = 2000))]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ModerateReviewDto {
+    pub action: ModerationActionType,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ModerationActionDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub review: String,
+    pub user: String,
+    pub actor: String,
+    pub action: ModerationActionType,
+    pub note: Option<String>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+/// A review waiting for moderation, with its open reports.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ModerationQueueItemDto {
+    pub review: ReviewDto,
+    pub weight: u64,
+    pub reports: u64,
+    pub reasons: Vec<ReportReason>,
+}
+
+impl From<ReviewReport> for ReportDto {
+    fn from(report: ReviewReport) -> Self {
+        Self {
+            id: report.id,
+            review: report.review,
+            reason: report.reason,
+            comment: report.comment,
+            resolved: report.resolved,
+            created_at: report.created_at,
+        }
+    }
+}
+
+impl From<ModerationAction> for ModerationActionDto {
+    fn from(action: ModerationAction) -> Self {
+        Self {
+            id: action.id,
+            review: action.review,
+            user: action.user,
+            actor: action.actor,
+            action: action.action,
+            note: action.note,
+            created_at: action.created_at,
+        }
+    }
+}
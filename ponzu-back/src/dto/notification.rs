@@ -0,0 +1,77 @@
+use crate::models::notification::{Notification, NotificationKind};
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NotificationDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub user: String,
+    pub kind: NotificationKind,
+    pub actor: Option<String>,
+    pub target: String,
+    pub detail: Option<String>,
+    #[serde(serialize_with = "serialize_option_bson_datetime_as_rfc3339_string")]
+    pub read_at: Option<DateTime>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+/// The notification kinds a user receives.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NotificationSettingsDto {
+    pub enabled: Vec<NotificationKind>,
+}
+
+/// The number of unread notifications of a user.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct UnreadCountDto {
+    pub unread: u64,
+}
+
+/// Query parameters selecting a slice of notifications, newest first
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NotificationQuery {
+    /// The cursor returned with the previous slice.
+    pub before: Option<String>,
+    pub limit: Option<u64>,
+    /// Whether only the unread notifications are listed.
+    #[serde(default)]
+    pub unread: bool,
+}
+
+impl NotificationQuery {
+    /// The requested slice size, capped at 100.
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+impl From<Notification> for NotificationDto {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            user: notification.user,
+            kind: notification.kind,
+            actor: notification.actor,
+            target: notification.target,
+            detail: notification.detail,
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+}
@@ -1,4 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
 /// Pagination struct for returning paginated data
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct Pagination<T> {
     pub current_page: u64,
     pub last_page: u64,
@@ -6,3 +10,64 @@ pub struct Pagination<T> {
     pub total: u64,
     pub payload: Vec<T>,
 }
+
+impl<T> Pagination<T> {
+    /// Creates a page of data.
+    ///
+    /// # Parameters
+    /// - `payload`: The items on the page.
+    /// - `page`: The page number, starting from 1.
+    /// - `limit`: The page size.
+    /// - `total`: The total number of items across all pages.
+    pub fn new(payload: Vec<T>, page: u64, limit: u64, total: u64) -> Self {
+        Self {
+            current_page: page,
+            last_page: total.div_ceil(limit.max(1)),
+            per_page: limit,
+            total,
+            payload,
+        }
+    }
+
+    /// Replaces the items of the page, keeping its position.
+    ///
+    /// # Parameters
+    /// - `payload`: The new items, usually derived from the current ones.
+    pub fn with_payload<U>(self, payload: Vec<U>) -> Pagination<U> {
+        Pagination {
+            current_page: self.current_page,
+            last_page: self.last_page,
+            per_page: self.per_page,
+            total: self.total,
+            payload,
+        }
+    }
+}
+
+/// A slice of data read with a cursor, for listings that grow while being read
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct CursorPage<T> {
+    pub payload: Vec<T>,
+    /// The cursor of the next slice, `None` on the last one.
+    pub next: Option<String>,
+}
+
+/// Query parameters selecting a page of data
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationQuery {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+impl PaginationQuery {
+    /// The requested page, starting from 1.
+    pub fn page(&self) -> u64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    /// The requested page size, capped at 100.
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+}
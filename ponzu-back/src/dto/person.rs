@@ -1,5 +1,7 @@
 use crate::models::person::{Person, PersonMedia, PersonVoice};
 use crate::types::links::Images;
+use crate::types::openapi::ObjectIdJson;
+use crate::types::validation::url_or_empty;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
@@ -9,8 +11,10 @@ use mongodb::bson::DateTime;
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct PersonDto {
     #[serde(
         rename = "_id",
@@ -18,6 +22,7 @@ pub struct PersonDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub url: String,
@@ -36,25 +41,29 @@ pub struct PersonDto {
     pub voices: Vec<PersonVoiceDto>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct PersonMediaDto {
     pub position: String,
     pub media: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct PersonVoiceDto {
     pub role: String,
     pub anime: String,
     pub character: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreatePersonDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: String,
+    #[validate(custom(function = "url_or_empty"))]
     pub website_url: String,
     pub images: Images,
+    #[validate(length(min = 1, max = 200))]
     pub name: String,
     pub given_name: String,
     pub family_name: String,
@@ -68,12 +77,16 @@ pub struct CreatePersonDto {
     pub voices: Vec<PersonVoiceDto>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdatePersonDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: Option<String>,
+    #[validate(custom(function = "url_or_empty"))]
     pub website_url: Option<String>,
     pub images: Option<Images>,
+    #[validate(length(min = 1, max = 200))]
     pub name: Option<String>,
     pub given_name: Option<String>,
     pub family_name: Option<String>,
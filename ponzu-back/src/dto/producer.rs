@@ -1,12 +1,27 @@
 use crate::models::producer::Producer;
 use crate::types::links::{ExternalLink, Images};
+use crate::types::openapi::ObjectIdJson;
 use crate::types::title_meta::MalEntity;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ProducerDto {
+    #[serde(
+        rename = "_id",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
     pub mal_id: u64,
     pub titles: Vec<MalEntity>,
     pub images: Option<Images>,
@@ -17,33 +32,40 @@ pub struct ProducerDto {
     pub external: Vec<ExternalLink>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateProducerDto {
+    #[validate(range(min = 1))]
     pub mal_id: u64,
+    #[validate(length(min = 1))]
     pub titles: Vec<MalEntity>,
     pub images: Option<Images>,
     pub favorites: u64,
     pub count: u64,
     pub established: String,
     pub about: String,
+    #[validate(nested)]
     pub external: Vec<ExternalLink>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateProducerDto {
+    #[validate(range(min = 1))]
     pub mal_id: Option<u64>,
+    #[validate(length(min = 1))]
     pub titles: Option<Vec<MalEntity>>,
     pub images: Option<Images>,
     pub favorites: Option<u64>,
     pub count: Option<u64>,
     pub established: Option<String>,
     pub about: Option<String>,
+    #[validate(nested)]
     pub external: Option<Vec<ExternalLink>>,
 }
 
 impl From<Producer> for ProducerDto {
     fn from(producer: Producer) -> Self {
         Self {
+            id: producer.id,
             mal_id: producer.mal_id,
             titles: producer.titles,
             images: producer.images,
@@ -59,6 +81,7 @@ impl From<Producer> for ProducerDto {
 impl From<ProducerDto> for Producer {
     fn from(dto: ProducerDto) -> Self {
         Self {
+            id: dto.id,
             mal_id: dto.mal_id,
             titles: dto.titles,
             images: dto.images,
@@ -74,6 +97,7 @@ impl From<ProducerDto> for Producer {
 impl From<CreateProducerDto> for Producer {
     fn from(dto: CreateProducerDto) -> Self {
         Self {
+            id: None,
             mal_id: dto.mal_id,
             titles: dto.titles,
             images: dto.images,
@@ -0,0 +1,83 @@
+use crate::dto::user::UserDto;
+use crate::types::links::Images;
+use crate::types::openapi::ObjectIdJson;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The public profile of a user. The birthday and the statistics are left out when the
+/// privacy settings of the user hide them from the caller.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ProfileDto {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
+    pub username: String,
+    pub images: Option<Images>,
+    pub bio: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub birth_date: Option<DateTime>,
+    pub follower_count: u64,
+    pub following_count: u64,
+    pub stats: Option<ProfileStatsDto>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub last_online: DateTime,
+}
+
+impl From<UserDto> for ProfileDto {
+    fn from(user: UserDto) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            images: user.images,
+            bio: user.bio,
+            birth_date: user.birth_date,
+            follower_count: user.follower_count,
+            following_count: user.following_count,
+            stats: None,
+            created_at: user.created_at,
+            last_online: user.last_online,
+        }
+    }
+}
+
+/// Statistics computed from the list of a user.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct ProfileStatsDto {
+    pub anime: ListStatsDto,
+    pub manga: ListStatsDto,
+    /// The episodes watched, summed over the progress of the anime entries.
+    pub episodes_watched: u64,
+    /// The time spent watching them, from the duration of the episodes.
+    pub days_watched: f64,
+    /// The chapters read, summed over the progress of the manga entries.
+    pub chapters_read: u64,
+}
+
+/// The entries of a list per status, and their mean score.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct ListStatsDto {
+    pub current: u64,
+    pub completed: u64,
+    pub on_hold: u64,
+    pub dropped: u64,
+    pub planned: u64,
+    pub total: u64,
+    /// The mean of the scores given, if any.
+    pub mean_score: Option<f64>,
+}
@@ -0,0 +1,19 @@
+use crate::models::reaction::ReactionType;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ToggleReactionDto {
+    pub reaction: ReactionType,
+}
+
+/// A review or a comment together with the reactions the caller gave it.
+///
+/// `my_reactions` is left out for anonymous callers.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ReactedDto<R> {
+    #[serde(flatten)]
+    pub target: R,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_reactions: Option<Vec<ReactionType>>,
+}
@@ -1,15 +1,22 @@
-use crate::models::review::{Reactions, Review};
+use crate::models::reaction::ReactionType;
+use crate::models::review::{Reactions, Review, ReviewStatus};
+use crate::types::openapi::ObjectIdJson;
+use crate::types::validation::{entry_type, object_id, url_or_empty};
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
 };
+use crate::utils::spoiler::split_spoilers;
+use async_graphql::SimpleObject;
 use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
 use mongodb::bson::DateTime;
-use mongodb::bson::{to_bson, Document};
+use mongodb::bson::{doc, to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ReviewDto {
     #[serde(
         rename = "_id",
@@ -17,6 +24,7 @@ pub struct ReviewDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub url: String,
@@ -28,14 +36,31 @@ pub struct ReviewDto {
     pub score: u8,
     pub tags: Vec<String>,
     pub is_spoiler: bool,
+    /// Whether the staff marked the review as a spoiler. Its author cannot clear `is_spoiler` then.
+    pub spoiler_locked: bool,
     pub is_preliminary: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub episodes_watched: Option<u64>,
     pub entry: String,
     pub user: String,
+    pub status: ReviewStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hold_reason: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub created_at: Option<DateTime>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    pub edit_count: u32,
+    pub comment_count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
 pub struct ReactionsDto {
     pub overall: u64,
     pub nice: u64,
@@ -47,44 +72,194 @@ pub struct ReactionsDto {
     pub creative: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct CreateReviewDto {
     pub mal_id: u64,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: String,
+    #[validate(custom(function = "entry_type"))]
     pub r#type: String,
-    pub reactions: ReactionsDto,
-    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    /// Set by the server when the review is posted.
+    #[serde(
+        skip_deserializing,
+        default = "DateTime::now",
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string"
+    )]
     pub date: DateTime,
+    #[validate(length(min = 1, max = 50000))]
     pub review: String,
+    #[validate(range(min = 1, max = 10))]
     pub score: u8,
+    #[validate(length(max = 20))]
     pub tags: Vec<String>,
     pub is_spoiler: bool,
     pub is_preliminary: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub episodes_watched: Option<u64>,
+    #[validate(custom(function = "object_id"))]
     pub entry: String,
+    /// The author, set from the bearer token.
+    #[serde(skip_deserializing, default)]
     pub user: String,
+    #[serde(skip_deserializing, default)]
+    pub status: ReviewStatus,
+    #[serde(skip_deserializing, default)]
+    pub hold_reason: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateReviewDto {
     pub mal_id: Option<u64>,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: Option<String>,
+    #[validate(custom(function = "entry_type"))]
     pub r#type: Option<String>,
-    pub reactions: Option<ReactionsDto>,
-    #[serde(
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
-    )]
-    pub date: Option<DateTime>,
+    #[validate(length(min = 1, max = 50000))]
     pub review: Option<String>,
+    #[validate(range(min = 1, max = 10))]
     pub score: Option<u8>,
+    #[validate(length(max = 20))]
     pub tags: Option<Vec<String>>,
     pub is_spoiler: Option<bool>,
     pub is_preliminary: Option<bool>,
     pub episodes_watched: Option<u64>,
+    #[validate(custom(function = "object_id"))]
     pub entry: Option<String>,
     pub user: Option<String>,
+    #[serde(skip_deserializing, default)]
+    pub status: Option<ReviewStatus>,
+    #[serde(skip_deserializing, default)]
+    pub hold_reason: Option<String>,
+}
+
+/// A review as delivered to a reader, with its spoilers redacted when needed.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ReviewViewDto {
+    #[serde(flatten)]
+    pub review: ReviewDto,
+    pub segments: Vec<ReviewSegmentDto>,
+    pub redacted: bool,
+    /// The fraction of the entry seen by the author of a preliminary review.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_reactions: Option<Vec<ReactionType>>,
+}
+
+impl ReviewViewDto {
+    /// Prepares a review for a reader.
+    ///
+    /// # Parameters
+    /// - `review`: The review.
+    /// - `reveal`: Whether the reader may read its spoilers.
+    /// - `length`: The number of episodes or chapters of the reviewed entry, if known.
+    pub fn new(mut review: ReviewDto, reveal: bool, length: Option<u32>) -> Self {
+        let progress = match (review.is_preliminary, review.episodes_watched, length) {
+            (true, Some(seen), Some(length)) if length > 0 => {
+                Some((seen as f64 / length as f64).min(1.0))
+            }
+            _ => None,
+        };
+
+        if review.is_spoiler && !reveal {
+            review.review.clear();
+            return Self {
+                review,
+                segments: Vec::new(),
+                redacted: true,
+                progress,
+                my_reactions: None,
+            };
+        }
+
+        let parts = split_spoilers(&review.review);
+        let redacted = !reveal && parts.iter().any(|(spoiler, _)| *spoiler);
+        if redacted {
+            review.review = parts
+                .iter()
+                .map(|(spoiler, text)| match spoiler {
+                    true => "[spoiler]",
+                    false => text.as_str(),
+                })
+                .collect();
+        }
+        let segments = parts
+            .into_iter()
+            .map(|(spoiler, text)| ReviewSegmentDto {
+                spoiler,
+                text: (!spoiler || reveal).then_some(text),
+            })
+            .collect();
+
+        Self {
+            review,
+            segments,
+            redacted,
+            progress,
+            my_reactions: None,
+        }
+    }
+}
+
+/// A piece of a review body. The text of a redacted spoiler is left out.
+#[derive(Debug, Serialize, Clone, ToSchema, SimpleObject)]
+pub struct ReviewSegmentDto {
+    pub spoiler: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// How a listing treats the reviews carrying a flag.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagFilter {
+    Hide,
+    Show,
+    Only,
+}
+
+/// Query parameters filtering a review listing
+///
+/// Without `spoilers`, spoiler reviews are listed with their bodies redacted. `show` and `only`
+/// reveal them, while `hide` leaves them out.
+#[derive(Debug, Deserialize, Clone, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReviewQuery {
+    pub entry: Option<String>,
+    /// Set by the profile listing the reviews of a user, never by the query string.
+    #[serde(skip_deserializing)]
+    pub user: Option<String>,
+    pub spoilers: Option<FlagFilter>,
+    pub preliminary: Option<FlagFilter>,
+}
+
+impl ReviewQuery {
+    /// Builds the filter selecting the reviews of the listing. Held and hidden reviews are never listed.
+    pub fn filter(&self) -> Document {
+        let mut filter = doc! { "status": { "$nin": ["held", "hidden"] } };
+        if let Some(entry) = &self.entry {
+            filter.insert("entry", entry);
+        }
+        if let Some(user) = &self.user {
+            filter.insert("user", user);
+        }
+        for (field, flag) in [
+            ("is_spoiler", self.spoilers),
+            ("is_preliminary", self.preliminary),
+        ] {
+            match flag {
+                Some(FlagFilter::Hide) => filter.insert(field, false),
+                Some(FlagFilter::Only) => filter.insert(field, true),
+                Some(FlagFilter::Show) | None => None,
+            };
+        }
+        filter
+    }
+
+    /// Returns whether the caller opted in to reading spoilers.
+    pub fn reveals_spoilers(&self) -> bool {
+        matches!(self.spoilers, Some(FlagFilter::Show | FlagFilter::Only))
+    }
 }
 
 impl From<Review> for ReviewDto {
@@ -100,10 +275,17 @@ impl From<Review> for ReviewDto {
             score: review.score,
             tags: review.tags,
             is_spoiler: review.is_spoiler,
+            spoiler_locked: review.spoiler_locked,
             is_preliminary: review.is_preliminary,
             episodes_watched: review.episodes_watched,
             entry: review.entry,
             user: review.user,
+            status: review.status,
+            hold_reason: review.hold_reason,
+            created_at: review.created_at,
+            edited_at: review.edited_at,
+            edit_count: review.edit_count,
+            comment_count: review.comment_count,
         }
     }
 }
@@ -121,10 +303,17 @@ impl From<ReviewDto> for Review {
             score: dto.score,
             tags: dto.tags,
             is_spoiler: dto.is_spoiler,
+            spoiler_locked: dto.spoiler_locked,
             is_preliminary: dto.is_preliminary,
             episodes_watched: dto.episodes_watched,
             entry: dto.entry,
             user: dto.user,
+            status: dto.status,
+            hold_reason: dto.hold_reason,
+            created_at: dto.created_at,
+            edited_at: dto.edited_at,
+            edit_count: dto.edit_count,
+            comment_count: dto.comment_count,
         }
     }
 }
@@ -136,16 +325,23 @@ impl From<CreateReviewDto> for Review {
             mal_id: dto.mal_id,
             url: dto.url,
             r#type: dto.r#type,
-            reactions: dto.reactions.into(),
+            reactions: Reactions::default(),
             date: dto.date,
             review: dto.review,
             score: dto.score,
             tags: dto.tags,
             is_spoiler: dto.is_spoiler,
+            spoiler_locked: false,
             is_preliminary: dto.is_preliminary,
             episodes_watched: dto.episodes_watched,
             entry: dto.entry,
             user: dto.user,
+            status: dto.status,
+            hold_reason: dto.hold_reason,
+            created_at: Some(DateTime::now()),
+            edited_at: None,
+            edit_count: 0,
+            comment_count: 0,
         }
     }
 }
@@ -169,18 +365,6 @@ impl From<UpdateReviewDto> for UpdateModifications {
                 to_bson(&r#type).expect("Failed to convert type to bson"),
             );
         }
-        if let Some(reactions) = dto.reactions {
-            doc.insert(
-                "reactions",
-                to_bson(&reactions).expect("Failed to convert reactions to bson"),
-            );
-        }
-        if let Some(date) = dto.date {
-            doc.insert(
-                "date",
-                to_bson(&date).expect("Failed to convert date to bson"),
-            );
-        }
         if let Some(review) = dto.review {
             doc.insert(
                 "review",
@@ -229,6 +413,18 @@ impl From<UpdateReviewDto> for UpdateModifications {
                 to_bson(&user).expect("Failed to convert user to bson"),
             );
         }
+        if let Some(status) = dto.status {
+            doc.insert(
+                "status",
+                to_bson(&status).expect("Failed to convert status to bson"),
+            );
+        }
+        if let Some(hold_reason) = dto.hold_reason {
+            doc.insert(
+                "hold_reason",
+                to_bson(&hold_reason).expect("Failed to convert hold_reason to bson"),
+            );
+        }
 
         UpdateModifications::Document(doc)
     }
@@ -251,8 +447,8 @@ impl From<Reactions> for ReactionsDto {
 
 impl From<ReactionsDto> for Reactions {
     fn from(dto: ReactionsDto) -> Self {
-        Self {
-            overall: dto.overall,
+        let mut reactions = Self {
+            overall: 0,
             nice: dto.nice,
             love_it: dto.love_it,
             funny: dto.funny,
@@ -260,6 +456,38 @@ impl From<ReactionsDto> for Reactions {
             informative: dto.informative,
             well_written: dto.well_written,
             creative: dto.creative,
-        }
+        };
+        // `overall` is derived, whatever the client sent
+        reactions.overall = reactions.total();
+        reactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CreateReviewDto;
+    use mongodb::bson::DateTime;
+    use serde_json::json;
+
+    #[test]
+    fn the_author_and_the_date_of_a_new_review_are_not_read_from_the_body() {
+        let before = DateTime::now();
+        let dto: CreateReviewDto = serde_json::from_value(json!({
+            "mal_id": 1,
+            "url": "",
+            "type": "anime",
+            "date": "2001-01-01T00:00:00Z",
+            "review": "A fine show",
+            "score": 8,
+            "tags": [],
+            "is_spoiler": false,
+            "is_preliminary": false,
+            "entry": "65f000000000000000000000",
+            "user": "someone-else",
+            "status": "published",
+        }))
+        .unwrap();
+        assert_eq!(dto.user, "");
+        assert!(dto.date >= before);
     }
 }
@@ -0,0 +1,56 @@
+use crate::models::revision::{FieldChange, Revision, RevisionAction};
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::{Bson, DateTime};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RevisionDto {
+    pub id: Option<String>,
+    pub kind: String,
+    pub document_id: String,
+    pub actor: String,
+    pub action: RevisionAction,
+    pub reverted_to: Option<String>,
+    pub changes: Vec<FieldChangeDto>,
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FieldChangeDto {
+    pub field: String,
+    /// Missing when the field was not set before the change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Value>)]
+    pub before: Option<Bson>,
+    /// Missing when the field was removed by the change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Value>)]
+    pub after: Option<Bson>,
+}
+
+impl From<Revision> for RevisionDto {
+    fn from(revision: Revision) -> Self {
+        Self {
+            id: revision.id,
+            kind: revision.kind,
+            document_id: revision.document_id,
+            actor: revision.actor,
+            action: revision.action,
+            reverted_to: revision.reverted_to,
+            changes: revision.changes.into_iter().map(Into::into).collect(),
+            created_at: revision.created_at,
+        }
+    }
+}
+
+impl From<FieldChange> for FieldChangeDto {
+    fn from(change: FieldChange) -> Self {
+        Self {
+            field: change.field,
+            before: change.before,
+            after: change.after,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use mongodb::bson::serde_helpers::serialize_bson_datetime_as_rfc3339_string;
+use mongodb::bson::DateTime;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A soft-deleted entity together with its deletion metadata.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TrashedDto<R> {
+    #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
+    pub deleted_at: DateTime,
+    pub deleted_by: Option<String>,
+    pub item: R,
+}
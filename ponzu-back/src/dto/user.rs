@@ -1,4 +1,9 @@
-use crate::models::user::User;
+use crate::models::activity::ActivityKind;
+use crate::models::notification::NotificationKind;
+use crate::models::user::{PrivacySettings, User};
+use crate::types::links::Images;
+use crate::types::openapi::ObjectIdJson;
+use crate::types::validation::{past, username_charset};
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
@@ -8,8 +13,10 @@ use mongodb::bson::DateTime;
 use mongodb::bson::{to_bson, Document};
 use mongodb::options::UpdateModifications;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct UserDto {
     #[serde(
         rename = "_id",
@@ -17,20 +24,29 @@ pub struct UserDto {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub username: String,
     pub email: String,
+    /// The Argon2 hash of the password, which is never served.
+    #[serde(skip_serializing)]
     pub password: String,
     pub is_active: bool,
     pub is_staff: bool,
     pub is_superuser: bool,
-    pub images: Option<String>,
+    pub images: Option<Images>,
     pub bio: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
     )]
     pub birth_date: Option<DateTime>,
+    pub published_activities: Vec<ActivityKind>,
+    pub enabled_notifications: Vec<NotificationKind>,
+    pub privacy: PrivacySettings,
+    pub explicit_content: bool,
+    pub follower_count: u64,
+    pub following_count: u64,
     #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
     pub created_at: DateTime,
     #[serde(serialize_with = "serialize_bson_datetime_as_rfc3339_string")]
@@ -39,13 +55,17 @@ pub struct UserDto {
     pub last_online: DateTime,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct RegisterUserDto {
+    #[validate(length(min = 3, max = 32), custom(function = "username_charset"))]
     pub username: String,
+    #[validate(email)]
     pub email: String,
+    #[validate(length(min = 8, max = 128))]
     pub password: String,
-    pub images: Option<String>,
+    #[validate(length(max = 2000))]
     pub bio: Option<String>,
+    #[validate(custom(function = "past"))]
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
@@ -53,24 +73,35 @@ pub struct RegisterUserDto {
     pub birth_date: Option<DateTime>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Validate, ToSchema)]
 pub struct UpdateUserDto {
+    #[validate(length(min = 3, max = 32), custom(function = "username_charset"))]
     pub username: Option<String>,
+    #[validate(email)]
     pub email: Option<String>,
+    #[validate(length(min = 8, max = 128))]
     pub password: Option<String>,
     pub is_active: Option<bool>,
     pub is_staff: Option<bool>,
     pub is_superuser: Option<bool>,
-    pub images: Option<String>,
+    /// Set by the avatar upload, never by the request body.
+    #[serde(skip_deserializing, default)]
+    pub images: Option<Images>,
+    #[validate(length(max = 2000))]
     pub bio: Option<String>,
+    #[validate(custom(function = "past"))]
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
     )]
     pub birth_date: Option<DateTime>,
+    pub published_activities: Option<Vec<ActivityKind>>,
+    pub enabled_notifications: Option<Vec<NotificationKind>>,
+    pub privacy: Option<PrivacySettings>,
+    pub explicit_content: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct LoginDto {
     pub username: String,
     pub password: String,
@@ -89,6 +120,12 @@ impl From<User> for UserDto {
             images: user.images,
             bio: user.bio,
             birth_date: user.birth_date,
+            published_activities: user.published_activities,
+            enabled_notifications: user.enabled_notifications,
+            privacy: user.privacy,
+            explicit_content: user.explicit_content,
+            follower_count: user.follower_count,
+            following_count: user.following_count,
             created_at: user.created_at,
             updated_at: user.updated_at,
             last_online: user.last_online,
@@ -109,6 +146,12 @@ impl From<UserDto> for User {
             images: dto.images,
             bio: dto.bio,
             birth_date: dto.birth_date,
+            published_activities: dto.published_activities,
+            enabled_notifications: dto.enabled_notifications,
+            privacy: dto.privacy,
+            explicit_content: dto.explicit_content,
+            follower_count: dto.follower_count,
+            following_count: dto.following_count,
             created_at: dto.created_at,
             updated_at: dto.updated_at,
             last_online: dto.last_online,
@@ -126,9 +169,15 @@ impl From<RegisterUserDto> for User {
             is_active: false,
             is_staff: false,
             is_superuser: false,
-            images: dto.images,
+            images: None,
             bio: dto.bio,
             birth_date: dto.birth_date,
+            published_activities: ActivityKind::all(),
+            enabled_notifications: NotificationKind::all(),
+            privacy: PrivacySettings::default(),
+            explicit_content: false,
+            follower_count: 0,
+            following_count: 0,
             created_at: DateTime::now(),
             updated_at: DateTime::now(),
             last_online: DateTime::now(),
@@ -191,7 +240,73 @@ impl From<UpdateUserDto> for UpdateModifications {
                 to_bson(&birth_date).expect("Failed to convert birth_date to bson"),
             );
         }
+        if let Some(published_activities) = dto.published_activities {
+            doc.insert(
+                "published_activities",
+                to_bson(&published_activities)
+                    .expect("Failed to convert published_activities to bson"),
+            );
+        }
+        if let Some(enabled_notifications) = dto.enabled_notifications {
+            doc.insert(
+                "enabled_notifications",
+                to_bson(&enabled_notifications)
+                    .expect("Failed to convert enabled_notifications to bson"),
+            );
+        }
+        if let Some(privacy) = dto.privacy {
+            doc.insert(
+                "privacy",
+                to_bson(&privacy).expect("Failed to convert privacy to bson"),
+            );
+        }
+        if let Some(explicit_content) = dto.explicit_content {
+            doc.insert(
+                "explicit_content",
+                to_bson(&explicit_content).expect("Failed to convert explicit_content to bson"),
+            );
+        }
 
         UpdateModifications::Document(doc)
     }
 }
+
+/// Whether a user is shown the explicit titles.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ContentSettingsDto {
+    pub explicit_content: bool,
+}
+
+/// Access token issued on login or registration.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TokenDto {
+    pub token: String,
+    pub user_id: String,
+    pub username: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserDto;
+    use crate::models::user::User;
+
+    #[test]
+    fn the_password_hash_is_never_served() {
+        let user = User::new(
+            "rin".to_string(),
+            "rin@example.com".to_string(),
+            "$argon2id$v=19$hash".to_string(),
+            true,
+            false,
+            false,
+            None,
+            None,
+            None,
+        );
+        let dto = UserDto::from(user);
+        assert_eq!(dto.password, "$argon2id$v=19$hash");
+        let served = serde_json::to_value(&dto).unwrap();
+        assert!(served.get("password").is_none());
+        assert!(!served.to_string().contains("argon2"));
+    }
+}
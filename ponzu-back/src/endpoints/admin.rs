@@ -0,0 +1,100 @@
+use crate::dto::counters::CounterDriftDto;
+use crate::dto::image_mirror::MirrorRunDto;
+use crate::dto::integrity::DanglingReferenceDto;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use actix_web::web::{scope, Data};
+use actix_web::{get, post, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_admin_scope() -> actix_web::Scope {
+    scope("/admin")
+        .service(get_dangling_references)
+        .service(get_counter_drift)
+        .service(reconcile_counters)
+        .service(mirror_images)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_dangling_references,
+    get_counter_drift,
+    reconcile_counters,
+    mirror_images
+))]
+pub struct AdminApi;
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The references to missing documents",
+            body = Vec<DanglingReferenceDto>
+        )
+    )
+)]
+#[get("integrity/dangling")]
+pub async fn get_dangling_references(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let report = data.integrity_service.dangling().await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The counters that drifted",
+            body = Vec<CounterDriftDto>
+        )
+    )
+)]
+#[get("counters/drift")]
+pub async fn get_counter_drift(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let drift = data.counter_service.reconcile(false).await?;
+    Ok(HttpResponse::Ok().json(drift))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The counters that were corrected",
+            body = Vec<CounterDriftDto>
+        )
+    )
+)]
+#[post("counters/reconcile")]
+pub async fn reconcile_counters(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let drift = data.counter_service.reconcile(true).await?;
+    Ok(HttpResponse::Ok().json(drift))
+}
+
+/// Mirrors the new external images right away, instead of waiting for the next run.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The number of mirrored images", body = MirrorRunDto)
+    )
+)]
+#[post("images/mirror")]
+pub async fn mirror_images(user: AuthUser, data: Data<AppState>) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let mirrored = data.image_mirror_service.mirror_all().await?;
+    Ok(HttpResponse::Ok().json(MirrorRunDto { mirrored }))
+}
@@ -0,0 +1,33 @@
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::review::{ReviewQuery, ReviewViewDto};
+use crate::endpoints::review::list_reviews;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use actix_web::web::{Data, Path, Query};
+use actix_web::{get, HttpResponse};
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery, ReviewQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the reviews of the anime",
+            body = Pagination<ReviewViewDto>
+        )
+    )
+)]
+#[get("{id}/reviews")]
+pub async fn get_anime_reviews(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    page: Query<PaginationQuery>,
+    query: Query<ReviewQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut query = query.into_inner();
+    query.entry = Some(path.into_inner());
+    let page = list_reviews(user.as_ref(), &page, &query, &data).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
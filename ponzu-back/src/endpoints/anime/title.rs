@@ -1,61 +1,292 @@
-use actix_web::web::{scope};
+use crate::dto::anime::{AnimeDto, CreateAnimeDto, UpdateAnimeDto};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::endpoints::anime::review::get_anime_reviews;
+use crate::endpoints::club::get_anime_clubs;
+use crate::models::notification::NotificationKind;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::openapi::Binary;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Bytes, Data, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpResponse};
+use mongodb::bson::doc;
+use utoipa::OpenApi;
 
 pub fn create_anime_scope() -> actix_web::Scope {
     scope("/anime")
-        // .service(get_all_anime_titles)
-        // .service(get_anime_title)
-}
-
-// #[get("")]
-// pub async fn get_all_anime_titles(data: Data<AppState>) -> impl Responder {
-//     match data.anime_service.find(None, None).await {
-//         Ok(anime) => Ok(HttpResponse::Ok().json(anime)),
-//         Err(e) => Err(e),
-//     }
-// }
-
-// #[get("{id}")]
-// pub async fn get_anime_title(path: Path<String>, data: Data<AppState>) -> impl Responder {
-//     let id = path.into_inner();
-//     match data.anime_service.find_one(doc! {"_id": id.as_str()}).await {
-//         Ok(anime) => match anime {
-//             Some(a) => Ok(HttpResponse::Ok().json(a)),
-//             None => Err(AppError::NotFound("Anime not found".to_string())),
-//         },
-//         Err(e) => Err(e),
-//     }
-// }
-
-// #[post("")]
-// pub async fn create_anime_title(form: Form<Anime>, data: Data<AppState>) -> impl Responder {
-//     match data.anime_service.insert_one(form.into_inner()).await {
-//         Ok(anime) => Ok(HttpResponse::Created().json(anime)),
-//         Err(e) => Err(e),
-//     }
-// }
-
-// #[patch("{id}")]
-// pub async fn update_anime_title(
-//     path: Path<String>,
-//     form: Form<Anime>,
-//     data: Data<AppState>
-// ) -> impl Responder {
-//     let id = path.into_inner();
-//     match data.anime_service.update_by_id(id.as_str(), form.into_inner()).await {
-//         Ok(anime) => Ok(HttpResponse::Ok().json(anime)),
-//         Err(e) => Err(e),
-//     }
-// }
-
-// #[delete("{id}")]
-// pub async fn delete_anime_title(path: Path<String>, data: Data<AppState>) -> impl Responder {
-//     let id = path.into_inner();
-//     match data
-//         .anime_service
-//         .delete_one(doc! {"_id": id.as_str()})
-//         .await
-//     {
-//         Ok(_) => Ok(HttpResponse::NoContent().finish()),
-//         Err(e) => Err(e),
-//     }
-// }
+        .service(get_anime_trash)
+        .service(restore_anime_title)
+        .service(purge_anime_title)
+        .service(get_all_anime_titles)
+        .service(get_anime_title)
+        .service(create_anime_title)
+        .service(update_anime_title)
+        .service(delete_anime_title)
+        .service(upload_anime_cover)
+        .service(get_anime_title_history)
+        .service(revert_anime_title)
+        .service(get_anime_reviews)
+        .service(get_anime_clubs)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_anime_titles,
+    get_anime_title,
+    create_anime_title,
+    update_anime_title,
+    delete_anime_title,
+    upload_anime_cover,
+    get_anime_trash,
+    restore_anime_title,
+    purge_anime_title,
+    get_anime_title_history,
+    revert_anime_title,
+    crate::endpoints::anime::review::get_anime_reviews,
+    crate::endpoints::club::get_anime_clubs
+))]
+pub struct AnimeApi;
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses((status = 200, description = "A page of anime", body = Pagination<AnimeDto>))
+)]
+#[get("")]
+pub async fn get_all_anime_titles(
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .anime_service
+        .get_paginated(None, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    responses((status = 200, description = "The anime", body = AnimeDto))
+)]
+#[get("{id}")]
+pub async fn get_anime_title(
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    match data.anime_service.get_by_id(&path.into_inner()).await? {
+        Some(anime) => Ok(HttpResponse::Ok().json(anime)),
+        None => Err(AppError::NotFound("Anime not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateAnimeDto,
+    responses((status = 201, description = "The anime was created", body = AnimeDto))
+)]
+#[post("")]
+pub async fn create_anime_title(
+    user: AuthUser,
+    body: ValidJson<CreateAnimeDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let anime = data.anime_service.create(body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(anime))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateAnimeDto,
+    responses((status = 200, description = "The anime was updated", body = AnimeDto))
+)]
+#[patch("{id}")]
+pub async fn update_anime_title(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateAnimeDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let id = path.into_inner();
+    let before = data.anime_service.get_by_id(&id).await?;
+    let anime = data
+        .anime_service
+        .update(&id, body.into_inner(), &user.id)
+        .await?;
+
+    // More episodes on an airing show means a new one aired, for the users watching it
+    let aired = before.and_then(|b| b.episodes).unwrap_or(0);
+    if let Some(episodes) = anime.episodes.filter(|e| anime.airing && *e > aired) {
+        let watching = data
+            .list_service
+            .find(Some(doc! { "entry": &id, "status": "current" }), None)
+            .await?
+            .into_iter()
+            .map(|e| e.user)
+            .collect::<Vec<_>>();
+        data.notification_service
+            .notify_many(
+                &watching,
+                NotificationKind::NewEpisode,
+                None,
+                &id,
+                Some(episodes.to_string()),
+            )
+            .await?;
+    }
+    Ok(HttpResponse::Ok().json(anime))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The anime was moved to the trash"))
+)]
+#[delete("{id}")]
+pub async fn delete_anime_title(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data
+        .anime_service
+        .delete(&path.into_inner(), &user.id)
+        .await?
+    {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Anime not found".to_string())),
+    }
+}
+
+/// Uploads the cover of an anime title, sent as the raw request body.
+///
+/// The previous cover is kept, as the edit history can still revert to it.
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body(content = Binary, content_type = "image/*"),
+    responses((status = 200, description = "The cover was replaced", body = AnimeDto))
+)]
+#[put("{id}/cover")]
+pub async fn upload_anime_cover(
+    user: AuthUser,
+    path: Path<String>,
+    body: Bytes,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let id = path.into_inner();
+    if data.anime_service.get_by_id(&id).await?.is_none() {
+        return Err(AppError::NotFound("Anime not found".to_string()));
+    }
+    let images = data
+        .image_service
+        .upload(&format!("anime/{}", id), body)
+        .await?;
+    let update = UpdateAnimeDto {
+        images: Some(images.clone()),
+        ..Default::default()
+    };
+    match data.anime_service.update(&id, update, &user.id).await {
+        Ok(anime) => Ok(HttpResponse::Ok().json(anime)),
+        Err(e) => {
+            data.image_service.remove(&images).await?;
+            Err(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of trashed anime",
+            body = Pagination<TrashedDto<AnimeDto>>
+        )
+    )
+)]
+#[get("trash")]
+pub async fn get_anime_trash(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let trash = data
+        .anime_service
+        .get_trash(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The anime was restored", body = AnimeDto))
+)]
+#[post("trash/{id}/restore")]
+pub async fn restore_anime_title(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let anime = data.anime_service.restore(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(anime))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The anime was deleted for good"))
+)]
+#[delete("trash/{id}")]
+pub async fn purge_anime_title(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data.anime_service.purge(&path.into_inner()).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Anime not found in trash".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The revisions of the anime", body = Vec<RevisionDto>)
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_anime_title_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.anime_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The anime was reverted", body = AnimeDto))
+)]
+#[post("{id}/history/{revision_id}/revert")]
+pub async fn revert_anime_title(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let (id, revision_id) = path.into_inner();
+    let anime = data
+        .anime_service
+        .revert(&id, &revision_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(anime))
+}
@@ -0,0 +1,79 @@
+use crate::dto::user::{LoginDto, RegisterUserDto, TokenDto, UserDto};
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::issue_token;
+use crate::types::error_response::ErrorCode;
+use crate::types::validation::ValidJson;
+use crate::utils::password::{hash_password, verify_password};
+use actix_web::web::{scope, Data, Json};
+use actix_web::{post, HttpResponse};
+use mongodb::bson::doc;
+use utoipa::OpenApi;
+
+pub fn create_auth_scope() -> actix_web::Scope {
+    scope("/auth").service(register).service(login)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(register, login))]
+pub struct AuthApi;
+
+/// Creates an account and signs it in.
+#[utoipa::path(
+    request_body = RegisterUserDto,
+    responses((status = 201, description = "The account was created", body = TokenDto))
+)]
+#[post("register")]
+pub async fn register(
+    body: ValidJson<RegisterUserDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut dto = body.into_inner();
+    let taken = data
+        .user_service
+        .count(Some(doc! {
+            "$or": [{ "username": &dto.username }, { "email": &dto.email }]
+        }))
+        .await;
+    if taken > 0 {
+        return Err(AppError::from((
+            ErrorCode::AccountTaken,
+            "Username or email is already taken",
+        )));
+    }
+
+    dto.password = hash_password(&dto.password)?;
+    let user = data.user_service.create(dto).await?;
+    Ok(HttpResponse::Created().json(token_for(&user, &data)?))
+}
+
+/// Signs a user in.
+#[utoipa::path(responses((status = 200, description = "The user was signed in", body = TokenDto)))]
+#[post("login")]
+pub async fn login(body: Json<LoginDto>, data: Data<AppState>) -> Result<HttpResponse, AppError> {
+    let user = data
+        .user_service
+        .find(Some(doc! { "username": &body.username }), None)
+        .await?
+        .into_iter()
+        .next()
+        .filter(|user| verify_password(&body.password, &user.password))
+        .ok_or_else(|| {
+            AppError::from((
+                ErrorCode::InvalidCredentials,
+                "Invalid username or password",
+            ))
+        })?;
+
+    Ok(HttpResponse::Ok().json(token_for(&user, &data)?))
+}
+
+/// Issues a `TokenDto` for the given user.
+fn token_for(user: &UserDto, data: &AppState) -> Result<TokenDto, AppError> {
+    Ok(TokenDto {
+        token: issue_token(user, &data.config)?,
+        user_id: user.id.clone().unwrap_or_default(),
+        username: user.username.clone(),
+    })
+}
@@ -0,0 +1,399 @@
+use crate::dto::board::{
+    CreatePostDto, CreateTopicDto, PostDto, TopicDto, TopicViewDto, UpdatePostDto, UpdateTopicDto,
+};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::endpoints::club::{acting_role, find_visible_club};
+use crate::models::club::{ClubAccess, ClubRole};
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::validation::ValidJson;
+use crate::utils::mentions::parse_mentions;
+use actix_web::web::{Data, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use mongodb::bson::doc;
+use mongodb::options::FindOptions;
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the topics of the club",
+            body = Pagination<TopicViewDto>
+        )
+    )
+)]
+#[get("{id}/topics")]
+pub async fn get_topics(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    let role = board_access(&id, user.as_ref(), &data).await?;
+
+    let filter = doc! { "club": &id };
+    let total = data.topic_service.count(Some(filter.clone())).await;
+    let options = FindOptions::builder()
+        .sort(doc! { "pinned": -1, "last_post_at": -1 })
+        .skip((query.page() - 1) * query.limit())
+        .limit(query.limit() as i64)
+        .build();
+    let topics = data.topic_service.find(Some(filter), Some(options)).await?;
+
+    // Members get unread markers
+    let read = match (&user, role) {
+        (Some(user), Some(_)) => {
+            let ids = topics
+                .iter()
+                .filter_map(|t| t.id.clone())
+                .collect::<Vec<_>>();
+            Some(data.read_marker_service.read_at(&user.id, &ids).await?)
+        }
+        _ => None,
+    };
+    let payload = topics
+        .into_iter()
+        .map(|topic| {
+            let unread = read.as_ref().map(|read| {
+                topic
+                    .id
+                    .as_ref()
+                    .and_then(|id| read.get(id))
+                    .is_none_or(|read_at| topic.last_post_at > *read_at)
+            });
+            TopicViewDto { topic, unread }
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(Pagination::new(payload, query.page(), query.limit(), total)))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateTopicDto,
+    responses((status = 201, description = "The topic was created", body = TopicDto))
+)]
+#[post("{id}/topics")]
+pub async fn create_topic(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<CreateTopicDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_member(&id, &user, &data).await?;
+
+    let mut dto = body.into_inner();
+    if dto.title.trim().is_empty() || dto.body.trim().is_empty() {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A topic needs a title and a first post",
+        )));
+    }
+    dto.club = id;
+    dto.user = user.id.clone();
+    let text = dto.body.clone();
+    let topic = data.topic_service.create(dto).await?;
+
+    let topic_id = topic.id.clone().unwrap_or_default();
+    let post = CreatePostDto {
+        topic: topic_id.clone(),
+        user: user.id,
+        mentions: resolve_mentions(&text, &data).await?,
+        body: text,
+    };
+    data.post_service.create(post).await?;
+    data.read_marker_service
+        .mark_read(&topic_id, &topic.user)
+        .await?;
+
+    Ok(HttpResponse::Created().json(find_topic(&topic.club, &topic_id, &data).await?))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    responses((status = 200, description = "The topic", body = TopicDto))
+)]
+#[get("{id}/topics/{topic_id}")]
+pub async fn get_topic(
+    user: Option<AuthUser>,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id) = path.into_inner();
+    board_access(&id, user.as_ref(), &data).await?;
+    Ok(HttpResponse::Ok().json(find_topic(&id, &topic_id, &data).await?))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateTopicDto,
+    responses((status = 200, description = "The topic was updated", body = TopicDto))
+)]
+#[patch("{id}/topics/{topic_id}")]
+pub async fn update_topic(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    body: ValidJson<UpdateTopicDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id) = path.into_inner();
+    let role = ensure_member(&id, &user, &data).await?;
+    let topic = find_topic(&id, &topic_id, &data).await?;
+
+    let dto = body.into_inner();
+    let moderates = dto.pinned.is_some() || dto.locked.is_some();
+    if (moderates || topic.user != user.id) && !role.manages() {
+        return Err(AppError::from((
+            ErrorCode::NotOfficer,
+            "Only officers can pin, lock or rename other members' topics",
+        )));
+    }
+    let topic = data.topic_service.update(&topic_id, dto, &user.id).await?;
+    Ok(HttpResponse::Ok().json(topic))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The topic was deleted"))
+)]
+#[delete("{id}/topics/{topic_id}")]
+pub async fn delete_topic(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id) = path.into_inner();
+    let role = ensure_member(&id, &user, &data).await?;
+    let topic = find_topic(&id, &topic_id, &data).await?;
+    if topic.user != user.id && !role.manages() {
+        return Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author or an officer can delete this topic",
+        )));
+    }
+    match data.topic_service.delete(&topic_id, &user.id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Topic not found".to_string())),
+    }
+}
+
+/// Reads a page of the posts of a topic, oldest first. Members reading it are marked as caught up.
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the posts of the topic",
+            body = Pagination<PostDto>
+        )
+    )
+)]
+#[get("{id}/topics/{topic_id}/posts")]
+pub async fn get_posts(
+    user: Option<AuthUser>,
+    path: Path<(String, String)>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id) = path.into_inner();
+    let role = board_access(&id, user.as_ref(), &data).await?;
+    find_topic(&id, &topic_id, &data).await?;
+
+    let filter = doc! { "topic": &topic_id };
+    let total = data.post_service.count(Some(filter.clone())).await;
+    let options = FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .skip((query.page() - 1) * query.limit())
+        .limit(query.limit() as i64)
+        .build();
+    let posts = data.post_service.find(Some(filter), Some(options)).await?;
+
+    if let (Some(user), Some(_)) = (&user, role) {
+        data.read_marker_service
+            .mark_read(&topic_id, &user.id)
+            .await?;
+    }
+    Ok(HttpResponse::Ok().json(Pagination::new(posts, query.page(), query.limit(), total)))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreatePostDto,
+    responses((status = 201, description = "The post was created", body = PostDto))
+)]
+#[post("{id}/topics/{topic_id}/posts")]
+pub async fn create_post(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    body: ValidJson<CreatePostDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id) = path.into_inner();
+    let role = ensure_member(&id, &user, &data).await?;
+    let topic = find_topic(&id, &topic_id, &data).await?;
+    if topic.locked && !role.manages() {
+        return Err(AppError::from((
+            ErrorCode::TopicLocked,
+            "This topic is locked",
+        )));
+    }
+
+    let mut dto = body.into_inner();
+    if dto.body.trim().is_empty() {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A post cannot be empty",
+        )));
+    }
+    dto.topic = topic_id.clone();
+    dto.user = user.id.clone();
+    dto.mentions = resolve_mentions(&dto.body, &data).await?;
+    let post = data.post_service.create(dto).await?;
+
+    let bump = UpdateTopicDto {
+        title: None,
+        pinned: None,
+        locked: None,
+        last_post_at: Some(post.created_at),
+    };
+    data.topic_service.update(&topic_id, bump, &user.id).await?;
+    data.read_marker_service
+        .mark_read(&topic_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Created().json(post))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdatePostDto,
+    responses((status = 200, description = "The post was updated", body = PostDto))
+)]
+#[patch("{id}/topics/{topic_id}/posts/{post_id}")]
+pub async fn update_post(
+    user: AuthUser,
+    path: Path<(String, String, String)>,
+    body: ValidJson<UpdatePostDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id, post_id) = path.into_inner();
+    ensure_member(&id, &user, &data).await?;
+    find_topic(&id, &topic_id, &data).await?;
+    let post = find_post(&topic_id, &post_id, &data).await?;
+    if post.user != user.id {
+        return Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author can edit this post",
+        )));
+    }
+
+    let mut dto = body.into_inner();
+    if let Some(text) = &dto.body {
+        if text.trim().is_empty() {
+            return Err(AppError::from((
+                ErrorCode::EmptyContent,
+                "A post cannot be empty",
+            )));
+        }
+        dto.mentions = Some(resolve_mentions(text, &data).await?);
+    }
+    let post = data.post_service.update(&post_id, dto, &user.id).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The post was deleted"))
+)]
+#[delete("{id}/topics/{topic_id}/posts/{post_id}")]
+pub async fn delete_post(
+    user: AuthUser,
+    path: Path<(String, String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, topic_id, post_id) = path.into_inner();
+    let role = ensure_member(&id, &user, &data).await?;
+    find_topic(&id, &topic_id, &data).await?;
+    let post = find_post(&topic_id, &post_id, &data).await?;
+    if post.user != user.id && !role.manages() {
+        return Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author or an officer can delete this post",
+        )));
+    }
+    match data.post_service.delete(&post_id, &user.id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Post not found".to_string())),
+    }
+}
+
+/// Ensures the caller may read the boards of a club, and returns their role in it.
+///
+/// The boards of public clubs are readable by anyone, the others only by their members.
+async fn board_access(
+    club: &str,
+    user: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<Option<ClubRole>, AppError> {
+    let found = find_visible_club(club, user, data).await?;
+    let role = match user {
+        Some(user) => acting_role(club, user, data).await?,
+        None => None,
+    };
+    if found.access != ClubAccess::Public && role.is_none() {
+        return Err(AppError::from((
+            ErrorCode::NotMember,
+            "Only members can read the boards of this club",
+        )));
+    }
+    Ok(role)
+}
+
+/// Ensures the caller is a member of a club, and returns their role in it.
+async fn ensure_member(club: &str, user: &AuthUser, data: &AppState) -> Result<ClubRole, AppError> {
+    board_access(club, Some(user), data).await?.ok_or_else(|| {
+        AppError::from((
+            ErrorCode::NotMember,
+            "Only members can post on the boards of this club",
+        ))
+    })
+}
+
+/// Loads a topic of a club, or fails with a 404.
+async fn find_topic(club: &str, topic_id: &str, data: &AppState) -> Result<TopicDto, AppError> {
+    data.topic_service
+        .get_by_id(topic_id)
+        .await?
+        .filter(|t| t.club == club)
+        .ok_or_else(|| AppError::NotFound("Topic not found".to_string()))
+}
+
+/// Loads a post of a topic, or fails with a 404.
+async fn find_post(topic_id: &str, post_id: &str, data: &AppState) -> Result<PostDto, AppError> {
+    data.post_service
+        .get_by_id(post_id)
+        .await?
+        .filter(|p| p.topic == topic_id)
+        .ok_or_else(|| AppError::NotFound("Post not found".to_string()))
+}
+
+/// Resolves the `@username` mentions of a text to user IDs. Unknown usernames are ignored.
+async fn resolve_mentions(text: &str, data: &AppState) -> Result<Vec<String>, AppError> {
+    let names = parse_mentions(text);
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(data
+        .user_service
+        .find(Some(doc! { "username": { "$in": names } }), None)
+        .await?
+        .into_iter()
+        .filter_map(|u| u.id)
+        .collect())
+}
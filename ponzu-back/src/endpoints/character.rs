@@ -0,0 +1,236 @@
+use crate::dto::character::{CharacterDto, CreateCharacterDto, UpdateCharacterDto};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::endpoints::club::get_character_clubs;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_character_scope() -> actix_web::Scope {
+    scope("/characters")
+        .service(get_character_trash)
+        .service(restore_character)
+        .service(purge_character)
+        .service(get_all_characters)
+        .service(get_character)
+        .service(create_character)
+        .service(update_character)
+        .service(delete_character)
+        .service(get_character_history)
+        .service(revert_character)
+        .service(get_character_clubs)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_characters,
+    get_character,
+    create_character,
+    update_character,
+    delete_character,
+    get_character_trash,
+    restore_character,
+    purge_character,
+    get_character_history,
+    revert_character,
+    crate::endpoints::club::get_character_clubs
+))]
+pub struct CharacterApi;
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of characters",
+            body = Pagination<CharacterDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_all_characters(
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .character_service
+        .get_paginated(None, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    responses((status = 200, description = "The character", body = CharacterDto))
+)]
+#[get("{id}")]
+pub async fn get_character(
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    match data.character_service.get_by_id(&path.into_inner()).await? {
+        Some(character) => Ok(HttpResponse::Ok().json(character)),
+        None => Err(AppError::NotFound("Character not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateCharacterDto,
+    responses((status = 201, description = "The character was created", body = CharacterDto))
+)]
+#[post("")]
+pub async fn create_character(
+    user: AuthUser,
+    body: ValidJson<CreateCharacterDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let character = data.character_service.create(body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(character))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateCharacterDto,
+    responses((status = 200, description = "The character was updated", body = CharacterDto))
+)]
+#[patch("{id}")]
+pub async fn update_character(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateCharacterDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let character = data
+        .character_service
+        .update(&path.into_inner(), body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(character))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The character was moved to the trash"))
+)]
+#[delete("{id}")]
+pub async fn delete_character(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data
+        .character_service
+        .delete(&path.into_inner(), &user.id)
+        .await?
+    {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Character not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of trashed characters",
+            body = Pagination<TrashedDto<CharacterDto>>
+        )
+    )
+)]
+#[get("trash")]
+pub async fn get_character_trash(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let trash = data
+        .character_service
+        .get_trash(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The character was restored", body = CharacterDto))
+)]
+#[post("trash/{id}/restore")]
+pub async fn restore_character(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let character = data.character_service.restore(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(character))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The character was deleted for good"))
+)]
+#[delete("trash/{id}")]
+pub async fn purge_character(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data.character_service.purge(&path.into_inner()).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound(
+            "Character not found in trash".to_string(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The revisions of the character",
+            body = Vec<RevisionDto>
+        )
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_character_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.character_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The character was reverted", body = CharacterDto))
+)]
+#[post("{id}/history/{revision_id}/revert")]
+pub async fn revert_character(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let (id, revision_id) = path.into_inner();
+    let character = data
+        .character_service
+        .revert(&id, &revision_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(character))
+}
@@ -0,0 +1,737 @@
+use crate::dto::club::{
+    BanMemberDto, ClubBanDto, ClubDto, ClubMemberDto, ClubRequestDto, CreateClubDto,
+    InviteMemberDto, JoinClubDto, LinkedTitleDto, SetMemberRoleDto, UpdateClubDto,
+};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::endpoints::board::{
+    create_post, create_topic, delete_post, delete_topic, get_posts, get_topic, get_topics,
+    update_post, update_topic,
+};
+use crate::models::activity::ActivityKind;
+use crate::models::club::{ClubAccess, ClubRole};
+use crate::models::notification::NotificationKind;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use std::collections::HashMap;
+use utoipa::OpenApi;
+
+pub fn create_club_scope() -> actix_web::Scope {
+    scope("/clubs")
+        .service(get_all_clubs)
+        .service(get_club)
+        .service(create_club)
+        .service(update_club)
+        .service(delete_club)
+        .service(join_club)
+        .service(leave_club)
+        .service(get_club_members)
+        .service(kick_club_member)
+        .service(set_club_member_role)
+        .service(get_club_requests)
+        .service(accept_club_request)
+        .service(dismiss_club_request)
+        .service(invite_club_member)
+        .service(get_club_bans)
+        .service(ban_club_member)
+        .service(unban_club_member)
+        .service(get_topics)
+        .service(create_topic)
+        .service(get_topic)
+        .service(update_topic)
+        .service(delete_topic)
+        .service(get_posts)
+        .service(create_post)
+        .service(update_post)
+        .service(delete_post)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_clubs,
+    get_club,
+    create_club,
+    update_club,
+    delete_club,
+    join_club,
+    leave_club,
+    get_club_members,
+    kick_club_member,
+    set_club_member_role,
+    get_club_requests,
+    accept_club_request,
+    dismiss_club_request,
+    invite_club_member,
+    get_club_bans,
+    ban_club_member,
+    unban_club_member,
+    crate::endpoints::board::get_topics,
+    crate::endpoints::board::create_topic,
+    crate::endpoints::board::get_topic,
+    crate::endpoints::board::update_topic,
+    crate::endpoints::board::delete_topic,
+    crate::endpoints::board::get_posts,
+    crate::endpoints::board::create_post,
+    crate::endpoints::board::update_post,
+    crate::endpoints::board::delete_post
+))]
+pub struct ClubApi;
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the clubs visible to the caller",
+            body = Pagination<ClubDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_all_clubs(
+    user: Option<AuthUser>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = list_clubs(user.as_ref(), &query, &data).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    responses((status = 200, description = "The club", body = ClubDto))
+)]
+#[get("{id}")]
+pub async fn get_club(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let club = find_visible_club(&path.into_inner(), user.as_ref(), &data).await?;
+    Ok(HttpResponse::Ok().json(present_clubs(vec![club], &data).await?.remove(0)))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateClubDto,
+    responses((status = 201, description = "The club was created", body = ClubDto))
+)]
+#[post("")]
+pub async fn create_club(
+    user: AuthUser,
+    body: ValidJson<CreateClubDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let club = data.club_service.create(body.into_inner()).await?;
+    let id = club.id.clone().unwrap_or_default();
+    // A club is never left without an owner
+    if let Err(e) = data.membership_service.found(&id, &user.id).await {
+        data.club_service.delete(&id, &user.id).await?;
+        data.club_service.purge(&id).await?;
+        return Err(e);
+    }
+    let club = find_club(&id, &data).await?;
+    Ok(HttpResponse::Created().json(present_clubs(vec![club], &data).await?.remove(0)))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateClubDto,
+    responses((status = 200, description = "The club was updated", body = ClubDto))
+)]
+#[patch("{id}")]
+pub async fn update_club(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateClubDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_manager(&id, &user, &data).await?;
+    let club = data
+        .club_service
+        .update(&id, body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(present_clubs(vec![club], &data).await?.remove(0)))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The club was deleted"))
+)]
+#[delete("{id}")]
+pub async fn delete_club(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    if acting_role(&id, &user, &data).await? != Some(ClubRole::Owner) {
+        return Err(AppError::from((
+            ErrorCode::NotOwner,
+            "Only the owner can delete this club",
+        )));
+    }
+    match data.club_service.delete(&id, &user.id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Club not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The caller joined the club, or asked to",
+            body = JoinClubDto
+        )
+    )
+)]
+#[post("{id}/join")]
+pub async fn join_club(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let club = find_club(&path.into_inner(), &data).await?;
+    let joined = data.membership_service.join(&club, &user.id).await?;
+    if let JoinClubDto::Joined { .. } = joined {
+        record_join(&club, &user.id, &data).await?;
+    }
+    Ok(HttpResponse::Ok().json(joined))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The caller left the club"))
+)]
+#[post("{id}/leave")]
+pub async fn leave_club(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    data.membership_service
+        .leave(&path.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the members of the club",
+            body = Pagination<ClubMemberDto>
+        )
+    )
+)]
+#[get("{id}/members")]
+pub async fn get_club_members(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_club(&id, user.as_ref(), &data).await?;
+    let members = data
+        .membership_service
+        .members(&id, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(members))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The member was kicked"))
+)]
+#[delete("{id}/members/{user_id}")]
+pub async fn kick_club_member(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, user_id) = path.into_inner();
+    let role = ensure_manager(&id, &user, &data).await?;
+    data.membership_service.kick(&id, role, &user_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The role of the member was changed",
+            body = ClubMemberDto
+        )
+    )
+)]
+#[put("{id}/members/{user_id}/role")]
+pub async fn set_club_member_role(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    body: Json<SetMemberRoleDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, user_id) = path.into_inner();
+    let role = ensure_manager(&id, &user, &data).await?;
+    let member = data
+        .membership_service
+        .set_role(&id, role, &user_id, body.role)
+        .await?;
+    Ok(HttpResponse::Ok().json(member))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the pending join requests",
+            body = Pagination<ClubRequestDto>
+        )
+    )
+)]
+#[get("{id}/requests")]
+pub async fn get_club_requests(
+    user: AuthUser,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_manager(&id, &user, &data).await?;
+    let requests = data
+        .membership_service
+        .requests(&id, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(requests))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The request was accepted", body = ClubMemberDto))
+)]
+#[post("{id}/requests/{user_id}/accept")]
+pub async fn accept_club_request(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, user_id) = path.into_inner();
+    ensure_manager(&id, &user, &data).await?;
+    let member = data.membership_service.accept(&id, &user_id).await?;
+    record_join(&find_club(&id, &data).await?, &user_id, &data).await?;
+    Ok(HttpResponse::Ok().json(member))
+}
+
+/// Declines a join request or withdraws an invitation. Users can also withdraw their own
+/// request, or decline their own invitation.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The request was dismissed"))
+)]
+#[delete("{id}/requests/{user_id}")]
+pub async fn dismiss_club_request(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, user_id) = path.into_inner();
+    if user_id != user.id {
+        ensure_manager(&id, &user, &data).await?;
+    }
+    match data.membership_service.dismiss(&id, &user_id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Request not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The user was invited", body = JoinClubDto))
+)]
+#[post("{id}/invitations")]
+pub async fn invite_club_member(
+    user: AuthUser,
+    path: Path<String>,
+    body: Json<InviteMemberDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_manager(&id, &user, &data).await?;
+    if data.user_service.get_by_id(&body.user).await?.is_none() {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
+    let invited = data
+        .membership_service
+        .invite(&id, &body.user, &user.id)
+        .await?;
+    match invited {
+        JoinClubDto::Joined { .. } => {
+            record_join(&find_club(&id, &data).await?, &body.user, &data).await?;
+        }
+        JoinClubDto::Pending { .. } => {
+            data.notification_service
+                .notify(
+                    &body.user,
+                    NotificationKind::ClubInvitation,
+                    Some(&user.id),
+                    &id,
+                    None,
+                )
+                .await?;
+        }
+    }
+    Ok(HttpResponse::Ok().json(invited))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the users banned from the club",
+            body = Pagination<ClubBanDto>
+        )
+    )
+)]
+#[get("{id}/bans")]
+pub async fn get_club_bans(
+    user: AuthUser,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_manager(&id, &user, &data).await?;
+    let bans = data
+        .membership_service
+        .bans(&id, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(bans))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 201, description = "The user was banned", body = ClubBanDto))
+)]
+#[post("{id}/bans/{user_id}")]
+pub async fn ban_club_member(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    body: Json<BanMemberDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, user_id) = path.into_inner();
+    let role = ensure_manager(&id, &user, &data).await?;
+    let ban = data
+        .membership_service
+        .ban(&id, role, &user.id, &user_id, body.into_inner().reason)
+        .await?;
+    Ok(HttpResponse::Created().json(ban))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The user was unbanned"))
+)]
+#[delete("{id}/bans/{user_id}")]
+pub async fn unban_club_member(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, user_id) = path.into_inner();
+    ensure_manager(&id, &user, &data).await?;
+    match data.membership_service.unban(&id, &user_id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Ban not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the clubs linked to the anime",
+            body = Pagination<ClubDto>
+        )
+    )
+)]
+#[get("{id}/clubs")]
+pub async fn get_anime_clubs(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = list_linked_clubs("anime", &path.into_inner(), user.as_ref(), &query, &data).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the clubs linked to the manga",
+            body = Pagination<ClubDto>
+        )
+    )
+)]
+#[get("{id}/clubs")]
+pub async fn get_manga_clubs(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = list_linked_clubs("manga", &path.into_inner(), user.as_ref(), &query, &data).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the clubs linked to the character",
+            body = Pagination<ClubDto>
+        )
+    )
+)]
+#[get("{id}/clubs")]
+pub async fn get_character_clubs(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = list_linked_clubs(
+        "characters",
+        &path.into_inner(),
+        user.as_ref(),
+        &query,
+        &data,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the clubs linked to the person",
+            body = Pagination<ClubDto>
+        )
+    )
+)]
+#[get("{id}/clubs")]
+pub async fn get_person_clubs(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page =
+        list_linked_clubs("people", &path.into_inner(), user.as_ref(), &query, &data).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+/// Reads a page of the clubs the caller may list.
+pub async fn list_clubs(
+    user: Option<&AuthUser>,
+    query: &PaginationQuery,
+    data: &AppState,
+) -> Result<Pagination<ClubDto>, AppError> {
+    let filter = visible_clubs_filter(user, data).await?;
+    let page = data
+        .club_service
+        .get_paginated(filter, query.page(), query.limit())
+        .await?;
+    let clubs = present_clubs(page.payload.clone(), data).await?;
+    Ok(page.with_payload(clubs))
+}
+
+/// Reads a page of the clubs linked to an anime, manga, character or person, the largest first.
+///
+/// # Parameters
+/// - `field`: The club field holding the links, such as `anime` or `characters`.
+/// - `id`: The ID of the linked document.
+pub async fn list_linked_clubs(
+    field: &str,
+    id: &str,
+    user: Option<&AuthUser>,
+    query: &PaginationQuery,
+    data: &AppState,
+) -> Result<Pagination<ClubDto>, AppError> {
+    let mut filter = doc! { field: id };
+    if let Some(visible) = visible_clubs_filter(user, data).await? {
+        filter.extend(visible);
+    }
+    let total = data.club_service.count(Some(filter.clone())).await;
+    let options = FindOptions::builder()
+        .sort(doc! { "member_count": -1, "_id": 1 })
+        .skip((query.page() - 1) * query.limit())
+        .limit(query.limit() as i64)
+        .build();
+    let clubs = data.club_service.find(Some(filter), Some(options)).await?;
+    let clubs = present_clubs(clubs, data).await?;
+    Ok(Pagination::new(clubs, query.page(), query.limit(), total))
+}
+
+/// Fills in the summaries of the anime and manga linked to clubs, in the order of their IDs.
+/// The titles the caller may not read are left out.
+pub async fn present_clubs(
+    mut clubs: Vec<ClubDto>,
+    data: &AppState,
+) -> Result<Vec<ClubDto>, AppError> {
+    let linked = |ids: Vec<&String>| {
+        let oids = ids
+            .into_iter()
+            .filter_map(|id| ObjectId::parse_str(id).ok())
+            .collect::<Vec<_>>();
+        doc! { "_id": { "$in": oids } }
+    };
+    let anime = linked(clubs.iter().flat_map(|c| &c.anime).collect());
+    let manga = linked(clubs.iter().flat_map(|c| &c.manga).collect());
+
+    let titles = data
+        .anime_service
+        .find(Some(anime), None)
+        .await?
+        .into_iter()
+        .map(LinkedTitleDto::from)
+        .chain(
+            data.manga_service
+                .find(Some(manga), None)
+                .await?
+                .into_iter()
+                .map(LinkedTitleDto::from),
+        )
+        .map(|title| (title.id.clone(), title))
+        .collect::<HashMap<_, _>>();
+    for club in &mut clubs {
+        club.titles = club
+            .anime
+            .iter()
+            .chain(&club.manga)
+            .filter_map(|id| titles.get(id).cloned())
+            .collect();
+    }
+    Ok(clubs)
+}
+
+/// Builds the filter of the clubs the caller may list. Secret clubs are only listed to their
+/// members and to staff.
+async fn visible_clubs_filter(
+    user: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<Option<Document>, AppError> {
+    Ok(match user {
+        Some(user) if user.is_staff => None,
+        Some(user) => {
+            let mine = data
+                .membership_service
+                .clubs_of(&user.id)
+                .await?
+                .iter()
+                .filter_map(|id| ObjectId::parse_str(id).ok())
+                .collect::<Vec<_>>();
+            Some(doc! { "$or": [{ "access": { "$ne": "secret" } }, { "_id": { "$in": mine } }] })
+        }
+        None => Some(doc! { "access": { "$ne": "secret" } }),
+    })
+}
+
+/// Records that a user joined a club as an activity. Joining a secret club is not recorded.
+async fn record_join(club: &ClubDto, user: &str, data: &AppState) -> Result<(), AppError> {
+    if club.access == ClubAccess::Secret {
+        return Ok(());
+    }
+    let id = club.id.as_deref().unwrap_or_default();
+    data.activity_service
+        .record(user, ActivityKind::ClubJoin, id, None)
+        .await
+}
+
+/// Loads a club, or fails with a 404.
+async fn find_club(id: &str, data: &AppState) -> Result<ClubDto, AppError> {
+    data.club_service
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Club not found".to_string()))
+}
+
+/// Loads a club the caller may see, or fails with a 404.
+///
+/// Secret clubs are only shown to their members and to staff.
+pub async fn find_visible_club(
+    id: &str,
+    user: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<ClubDto, AppError> {
+    let club = find_club(id, data).await?;
+    if club.access == ClubAccess::Secret {
+        let role = match user {
+            Some(user) => acting_role(id, user, data).await?,
+            None => None,
+        };
+        if role.is_none() {
+            return Err(AppError::NotFound("Club not found".to_string()));
+        }
+    }
+    Ok(club)
+}
+
+/// Returns the role the user acts with in a club. Staff members act as owners.
+pub async fn acting_role(
+    club: &str,
+    user: &AuthUser,
+    data: &AppState,
+) -> Result<Option<ClubRole>, AppError> {
+    match user.is_staff {
+        true => Ok(Some(ClubRole::Owner)),
+        false => data.membership_service.role(club, &user.id).await,
+    }
+}
+
+/// Ensures the user is an officer or the owner of the club, and returns their role.
+async fn ensure_manager(
+    club: &str,
+    user: &AuthUser,
+    data: &AppState,
+) -> Result<ClubRole, AppError> {
+    find_club(club, data).await?;
+    match acting_role(club, user, data).await? {
+        Some(role) if role.manages() => Ok(role),
+        _ => Err(AppError::from((
+            ErrorCode::NotOfficer,
+            "Only the officers of the club can do this",
+        ))),
+    }
+}
@@ -0,0 +1,257 @@
+use crate::dto::comment::{CommentDto, CommentQuery, CreateCommentDto, UpdateCommentDto};
+use crate::dto::pagination::CursorPage;
+use crate::dto::reaction::{ReactedDto, ToggleReactionDto};
+use crate::endpoints::review::{find_visible_review, record_reaction};
+use crate::models::notification::NotificationKind;
+use crate::models::reaction::ReactionType;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::validation::ValidJson;
+use crate::utils::bson::parse_object_id;
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use mongodb::bson::{doc, Bson};
+use mongodb::options::FindOptions;
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(CommentQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the comments of the review",
+            body = CursorPage<CommentDto>
+        )
+    )
+)]
+#[get("{id}/comments")]
+pub async fn get_comments(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<CommentQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_review(&id, user.as_ref(), &data).await?;
+
+    let mut filter = doc! {
+        "review": &id,
+        "parent": query.parent.as_ref().map_or(Bson::Null, |p| Bson::String(p.clone())),
+    };
+    if let Some(after) = &query.after {
+        filter.insert("_id", doc! { "$gt": parse_object_id(after)? });
+    }
+    // Read one more comment than requested to know whether a next slice exists
+    let limit = query.limit();
+    let options = FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .limit((limit + 1) as i64)
+        .build();
+    let mut payload = data
+        .comment_service
+        .find(Some(filter), Some(options))
+        .await?;
+
+    let next = match payload.len() as u64 > limit {
+        true => {
+            payload.truncate(limit as usize);
+            payload.last().and_then(|c| c.id.clone())
+        }
+        false => None,
+    };
+    Ok(HttpResponse::Ok().json(CursorPage { payload, next }))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateCommentDto,
+    responses((status = 201, description = "The comment was created", body = CommentDto))
+)]
+#[post("{id}/comments")]
+pub async fn create_comment(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<CreateCommentDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_review(&id, Some(&user), &data).await?;
+
+    let mut dto = body.into_inner();
+    if dto.body.trim().is_empty() {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A comment cannot be empty",
+        )));
+    }
+    let mut replied = None;
+    if let Some(parent) = &dto.parent {
+        let parent = find_comment(&id, parent, &data).await.map_err(|_| {
+            AppError::from((
+                ErrorCode::ReplyMissing,
+                "The replied comment does not exist",
+            ))
+        })?;
+        dto.depth = parent.depth + 1;
+        replied = Some(parent.user);
+        if dto.depth > data.config.comment_max_depth {
+            return Err(AppError::from((
+                ErrorCode::ReplyTooDeep,
+                format!(
+                    "Replies cannot be nested more than {} levels deep",
+                    data.config.comment_max_depth
+                ),
+            )));
+        }
+    }
+    dto.review = id;
+    dto.user = user.id;
+
+    let comment = data.comment_service.create(dto).await?;
+    if let Some(replied) = replied {
+        data.notification_service
+            .notify(
+                &replied,
+                NotificationKind::CommentReply,
+                Some(&comment.user),
+                comment.id.as_deref().unwrap_or_default(),
+                None,
+            )
+            .await?;
+    }
+    Ok(HttpResponse::Created().json(comment))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateCommentDto,
+    responses((status = 200, description = "The comment was updated", body = CommentDto))
+)]
+#[patch("{id}/comments/{comment_id}")]
+pub async fn update_comment(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    body: ValidJson<UpdateCommentDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, comment_id) = path.into_inner();
+    let comment = find_comment(&id, &comment_id, &data).await?;
+    if comment.user != user.id {
+        return Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author can edit this comment",
+        )));
+    }
+    if body.body.as_ref().is_some_and(|b| b.trim().is_empty()) {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A comment cannot be empty",
+        )));
+    }
+
+    let comment = data
+        .comment_service
+        .update(&comment_id, body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The comment was deleted"))
+)]
+#[delete("{id}/comments/{comment_id}")]
+pub async fn delete_comment(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, comment_id) = path.into_inner();
+    let comment = find_comment(&id, &comment_id, &data).await?;
+    if !user.is_staff && comment.user != user.id {
+        return Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author can delete this comment",
+        )));
+    }
+
+    match data.comment_service.delete(&comment_id, &user.id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Comment not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The reactions the caller gave the comment",
+            body = Vec<ReactionType>
+        )
+    )
+)]
+#[get("{id}/comments/{comment_id}/reactions")]
+pub async fn get_my_comment_reactions(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, comment_id) = path.into_inner();
+    find_comment(&id, &comment_id, &data).await?;
+    let reactions = data
+        .comment_reaction_service
+        .of_user(&comment_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(reactions))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The reaction was toggled",
+            body = ReactedDto<CommentDto>
+        )
+    )
+)]
+#[post("{id}/comments/{comment_id}/reactions")]
+pub async fn toggle_comment_reaction(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    body: Json<ToggleReactionDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, comment_id) = path.into_inner();
+    find_comment(&id, &comment_id, &data).await?;
+    let comment = data
+        .comment_reaction_service
+        .toggle(&comment_id, &user.id, body.reaction)
+        .await?;
+    record_reaction(
+        &user,
+        &comment_id,
+        body.reaction,
+        &comment.my_reactions,
+        &data,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+/// Loads a comment of a review, or fails with a 404.
+async fn find_comment(
+    review_id: &str,
+    comment_id: &str,
+    data: &AppState,
+) -> Result<CommentDto, AppError> {
+    data.comment_service
+        .get_by_id(comment_id)
+        .await?
+        .filter(|c| c.review == review_id)
+        .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))
+}
@@ -1,11 +1,13 @@
-use crate::types::error_response::SerializableError;
+use crate::types::error_response::{ErrorCode, SerializableError};
+use crate::types::request_id;
 use actix_web::{HttpRequest, HttpResponse, Responder};
 
 pub async fn default_responder(req: HttpRequest) -> impl Responder {
     HttpResponse::NotFound().json(
         SerializableError::new(
-            format!("Resource {} not found", req.path()),
-            404
+            ErrorCode::NotFound,
+            format!("Resource {} not found", req.path())
         )
+        .with_request_id(request_id::current())
     )
 }
@@ -0,0 +1,255 @@
+use crate::dto::review::FlagFilter;
+use crate::endpoints::admin::AdminApi;
+use crate::endpoints::anime::title::AnimeApi;
+use crate::endpoints::auth::AuthApi;
+use crate::endpoints::character::CharacterApi;
+use crate::endpoints::club::ClubApi;
+use crate::endpoints::fan_work::FanWorkApi;
+use crate::endpoints::feed::FeedApi;
+use crate::endpoints::file::FileApi;
+use crate::endpoints::graphql::GraphqlApi;
+use crate::endpoints::health::HealthApi;
+use crate::endpoints::image::ImageApi;
+use crate::endpoints::list::ListApi;
+use crate::endpoints::magazine::MagazineApi;
+use crate::endpoints::manga::title::MangaApi;
+use crate::endpoints::notification::NotificationApi;
+use crate::endpoints::person::PersonApi;
+use crate::endpoints::producer::ProducerApi;
+use crate::endpoints::review::ReviewApi;
+use crate::endpoints::user::UserApi;
+use crate::models::fan_work::{ContentRating, FanWorkKind};
+use crate::models::list_entry::ListStatus;
+use crate::types::error_response::{ErrorCode, SerializableError};
+use actix_web::web::{redirect, ServiceConfig};
+use utoipa::openapi::path::Operation;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{ContentBuilder, Ref, RefOr, ResponseBuilder};
+use utoipa::{OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+/// The path the OpenAPI document is served at.
+pub const OPENAPI_PATH: &str = "/api/openapi.json";
+
+/// The name of the bearer token security scheme, required by the paths of authenticated users.
+pub const BEARER: &str = "bearer";
+
+/// The name of the response shared by every error.
+const ERROR_RESPONSE: &str = "Error";
+
+/// The base of the document, completed by `openapi` with the paths of every scope.
+///
+/// Schemas only referenced by query parameters are listed here, as `IntoParams` does not
+/// register them.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Ponzu API",
+        description = "The API of Ponzu, an anime and manga library.\n\n\
+            Lists are paginated with the `page` and `limit` query parameters, and answered with \
+            the items in `payload` along with the position of the page. Growing listings such as \
+            comments and notifications are read with a cursor instead.\n\n\
+            Errors are answered with a `SerializableError`, whose `code` is stable and always \
+            sent with the same HTTP status."
+    ),
+    components(schemas(
+        SerializableError,
+        ErrorCode,
+        FlagFilter,
+        FanWorkKind,
+        ContentRating,
+        ListStatus
+    ))
+)]
+struct ApiDoc;
+
+/// Builds the OpenAPI document of the API.
+///
+/// The paths of each scope are nested under `/api` and tagged with the name of their scope,
+/// the probes are added as they are served, and every operation documents the error response.
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let scopes = [
+        ("auth", AuthApi::openapi()),
+        ("anime", AnimeApi::openapi()),
+        ("manga", MangaApi::openapi()),
+        ("characters", CharacterApi::openapi()),
+        ("people", PersonApi::openapi()),
+        ("producers", ProducerApi::openapi()),
+        ("magazines", MagazineApi::openapi()),
+        ("reviews", ReviewApi::openapi()),
+        ("list", ListApi::openapi()),
+        ("clubs", ClubApi::openapi()),
+        ("fan-works", FanWorkApi::openapi()),
+        ("files", FileApi::openapi()),
+        ("images", ImageApi::openapi()),
+        ("users", UserApi::openapi()),
+        ("feed", FeedApi::openapi()),
+        ("notifications", NotificationApi::openapi()),
+        ("admin", AdminApi::openapi()),
+        ("graphql", GraphqlApi::openapi()),
+    ];
+
+    let mut doc = ApiDoc::openapi();
+    for (name, mut api) in scopes {
+        tag(&mut api, name);
+        doc = doc.nest_with_path_composer(format!("/api/{}", name), api, join);
+    }
+
+    // The probes are served outside of `/api`, where deployments look for them
+    let mut health = HealthApi::openapi();
+    tag(&mut health, "health");
+    doc.merge(health);
+
+    for item in doc.paths.paths.values_mut() {
+        for operation in operations(item) {
+            operation
+                .responses
+                .responses
+                .entry("default".to_string())
+                .or_insert_with(|| RefOr::Ref(Ref::from_response_name(ERROR_RESPONSE)));
+        }
+    }
+    let components = doc.components.get_or_insert_with(Default::default);
+    components.responses.insert(
+        ERROR_RESPONSE.to_string(),
+        RefOr::T(
+            ResponseBuilder::new()
+                .description("The request failed, see `code` for the reason")
+                .content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name(SerializableError::name())))
+                        .build(),
+                )
+                .build(),
+        ),
+    );
+    components.add_security_scheme(
+        BEARER,
+        SecurityScheme::Http(
+            HttpBuilder::new()
+                .scheme(HttpAuthScheme::Bearer)
+                .bearer_format("JWT")
+                .description(Some("The token returned by `/api/auth/login`"))
+                .build(),
+        ),
+    );
+    doc
+}
+
+/// Serves the OpenAPI document, and the Swagger UI browsing it under `/api/docs`.
+///
+/// It has to be configured before the `/api` scope, which would shadow it.
+pub fn configure_docs(cfg: &mut ServiceConfig) {
+    cfg.service(redirect("/api/docs", "/api/docs/index.html"))
+        .service(SwaggerUi::new("/api/docs/{_:.*}").url(OPENAPI_PATH, openapi()));
+}
+
+/// Joins the path of a scope with the path of one of its routes, which actix leaves relative.
+fn join(base: &str, path: &str) -> String {
+    match path.trim_start_matches('/') {
+        "" => base.to_string(),
+        path => format!("{}/{}", base, path),
+    }
+}
+
+/// Tags every operation of a document with the name of its scope.
+fn tag(api: &mut utoipa::openapi::OpenApi, name: &str) {
+    for item in api.paths.paths.values_mut() {
+        for operation in operations(item) {
+            operation.tags = Some(vec![name.to_string()]);
+        }
+    }
+}
+
+/// The operations of a path.
+fn operations(item: &mut utoipa::openapi::PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.options,
+        &mut item.head,
+        &mut item.patch,
+        &mut item.trace,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{openapi, operations};
+    use std::path::Path;
+    use std::{env, fs};
+
+    /// The committed document, regenerated with `UPDATE_OPENAPI=1 cargo test`.
+    const DOCUMENT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+    const ROUTES: [&str; 5] = ["#[get(", "#[post(", "#[put(", "#[patch(", "#[delete("];
+
+    #[test]
+    fn document_matches_the_committed_one() {
+        let document = openapi().to_pretty_json().unwrap() + "\n";
+        if env::var_os("UPDATE_OPENAPI").is_some() {
+            fs::write(DOCUMENT, &document).unwrap();
+            return;
+        }
+
+        let committed = fs::read_to_string(DOCUMENT).unwrap_or_default();
+        assert!(
+            committed == document,
+            "openapi.json drifted from the code, regenerate it with `UPDATE_OPENAPI=1 cargo test`"
+        );
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let endpoints = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/endpoints");
+        let routes = count_routes(&endpoints);
+
+        let mut document = openapi();
+        let documented: usize = document
+            .paths
+            .paths
+            .values_mut()
+            .map(|item| operations(item).count())
+            .sum();
+        assert_eq!(
+            documented, routes,
+            "every route needs a #[utoipa::path] listed in its scope"
+        );
+    }
+
+    #[test]
+    fn every_reference_is_resolved() {
+        let document = openapi();
+        let schemas = &document.components.as_ref().unwrap().schemas;
+        let json = document.to_json().unwrap();
+        for reference in json.split("\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(
+                schemas.contains_key(name),
+                "{} is referenced but not registered",
+                name
+            );
+        }
+    }
+
+    /// Counts the route attributes of the handlers under `dir`.
+    fn count_routes(dir: &Path) -> usize {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .map(|path| match path.is_dir() {
+                true => count_routes(&path),
+                false => fs::read_to_string(&path)
+                    .unwrap()
+                    .lines()
+                    .filter(|line| ROUTES.iter().any(|route| line.starts_with(route)))
+                    .count(),
+            })
+            .sum()
+    }
+}
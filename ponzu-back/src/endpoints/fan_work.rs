@@ -0,0 +1,524 @@
+use crate::dto::fan_work::{
+    ChapterDto, CreateChapterDto, CreateFanWorkDto, FanWorkActionDto, FanWorkDto, FanWorkQuery,
+    FanWorkQueueItemDto, FanWorkReportDto, LikedFanWorkDto, ModerateFanWorkDto, UpdateChapterDto,
+    UpdateFanWorkDto,
+};
+use crate::dto::moderation::CreateReportDto;
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::models::fan_work::{FanWorkKind, FanWorkStatus};
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::openapi::Binary;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Bytes, Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpResponse};
+use mongodb::bson::doc;
+use mongodb::options::FindOptions;
+use utoipa::OpenApi;
+
+pub fn create_fan_work_scope() -> actix_web::Scope {
+    scope("/fan-works")
+        .service(get_fan_work_moderation_queue)
+        .service(get_all_fan_works)
+        .service(get_fan_work)
+        .service(create_fan_work)
+        .service(update_fan_work)
+        .service(delete_fan_work)
+        .service(upload_fan_work_image)
+        .service(get_chapters)
+        .service(create_chapter)
+        .service(get_chapter)
+        .service(update_chapter)
+        .service(delete_chapter)
+        .service(like_fan_work)
+        .service(unlike_fan_work)
+        .service(report_fan_work)
+        .service(moderate_fan_work)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_fan_works,
+    get_fan_work,
+    create_fan_work,
+    update_fan_work,
+    delete_fan_work,
+    upload_fan_work_image,
+    get_chapters,
+    create_chapter,
+    get_chapter,
+    update_chapter,
+    delete_chapter,
+    like_fan_work,
+    unlike_fan_work,
+    report_fan_work,
+    get_fan_work_moderation_queue,
+    moderate_fan_work
+))]
+pub struct FanWorkApi;
+
+#[utoipa::path(
+    params(PaginationQuery, FanWorkQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of published fan works",
+            body = Pagination<FanWorkDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_all_fan_works(
+    page: Query<PaginationQuery>,
+    query: Query<FanWorkQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let filter = query.filter();
+    let total = data.fan_work_service.count(Some(filter.clone())).await;
+    let options = FindOptions::builder()
+        .sort(doc! { "_id": -1 })
+        .skip((page.page() - 1) * page.limit())
+        .limit(page.limit() as i64)
+        .build();
+    let works = data
+        .fan_work_service
+        .find(Some(filter), Some(options))
+        .await?;
+    Ok(HttpResponse::Ok().json(Pagination::new(works, page.page(), page.limit(), total)))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The fan work, and whether the caller liked it",
+            body = LikedFanWorkDto
+        )
+    )
+)]
+#[get("{id}")]
+pub async fn get_fan_work(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    let work = find_visible_fan_work(&id, user.as_ref(), &data).await?;
+    let liked = match user {
+        Some(user) => Some(
+            data.fan_work_interaction_service
+                .is_liked(&id, &user.id)
+                .await?,
+        ),
+        None => None,
+    };
+    Ok(HttpResponse::Ok().json(LikedFanWorkDto { work, liked }))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateFanWorkDto,
+    responses((status = 201, description = "The fan work was created", body = FanWorkDto))
+)]
+#[post("")]
+pub async fn create_fan_work(
+    user: AuthUser,
+    body: ValidJson<CreateFanWorkDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut dto = body.into_inner();
+    if dto.title.trim().is_empty() {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A fan work needs a title",
+        )));
+    }
+    dto.user = user.id;
+    let work = data.fan_work_service.create(dto).await?;
+    Ok(HttpResponse::Created().json(work))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateFanWorkDto,
+    responses((status = 200, description = "The fan work was updated", body = FanWorkDto))
+)]
+#[patch("{id}")]
+pub async fn update_fan_work(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateFanWorkDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_author(&find_fan_work(&id, &data).await?, &user)?;
+    let work = data
+        .fan_work_service
+        .update(&id, body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(work))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The fan work was deleted"))
+)]
+#[delete("{id}")]
+pub async fn delete_fan_work(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_author(&find_fan_work(&id, &data).await?, &user)?;
+    match data.fan_work_service.delete(&id, &user.id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Fan work not found".to_string())),
+    }
+}
+
+/// Uploads the image of a fanart, sent as the raw request body.
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body(content = Binary, content_type = "image/*"),
+    responses((status = 200, description = "The image was replaced", body = FanWorkDto))
+)]
+#[put("{id}/image")]
+pub async fn upload_fan_work_image(
+    user: AuthUser,
+    path: Path<String>,
+    body: Bytes,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let work = find_fan_work(&path.into_inner(), &data).await?;
+    ensure_author(&work, &user)?;
+    let work = data
+        .fan_work_interaction_service
+        .upload_image(&work, body)
+        .await?;
+    Ok(HttpResponse::Ok().json(work))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the chapters of the fan work",
+            body = Pagination<ChapterDto>
+        )
+    )
+)]
+#[get("{id}/chapters")]
+pub async fn get_chapters(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_fan_work(&id, user.as_ref(), &data).await?;
+
+    let filter = doc! { "work": &id };
+    let total = data.chapter_service.count(Some(filter.clone())).await;
+    let options = FindOptions::builder()
+        .sort(doc! { "number": 1 })
+        .skip((query.page() - 1) * query.limit())
+        .limit(query.limit() as i64)
+        .build();
+    let chapters = data
+        .chapter_service
+        .find(Some(filter), Some(options))
+        .await?;
+    Ok(HttpResponse::Ok().json(Pagination::new(
+        chapters,
+        query.page(),
+        query.limit(),
+        total,
+    )))
+}
+
+/// Appends a chapter to a fanfiction.
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateChapterDto,
+    responses((status = 201, description = "The chapter was created", body = ChapterDto))
+)]
+#[post("{id}/chapters")]
+pub async fn create_chapter(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<CreateChapterDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    let work = find_fan_work(&id, &data).await?;
+    ensure_author(&work, &user)?;
+    if work.kind != FanWorkKind::Fanfiction {
+        return Err(AppError::from((
+            ErrorCode::WrongWorkKind,
+            "Only fanfiction has chapters",
+        )));
+    }
+
+    let mut dto = body.into_inner();
+    if dto.body.trim().is_empty() {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A chapter cannot be empty",
+        )));
+    }
+    let options = FindOptions::builder()
+        .sort(doc! { "number": -1 })
+        .limit(1)
+        .build();
+    let last = data
+        .chapter_service
+        .find(Some(doc! { "work": &id }), Some(options))
+        .await?;
+    dto.number = last.first().map_or(1, |c| c.number + 1);
+    dto.work = id.clone();
+    let chapter = data.chapter_service.create(dto).await?;
+    data.fan_work_interaction_service
+        .refresh_word_count(&id)
+        .await?;
+    Ok(HttpResponse::Created().json(chapter))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    responses((status = 200, description = "The chapter", body = ChapterDto))
+)]
+#[get("{id}/chapters/{number}")]
+pub async fn get_chapter(
+    user: Option<AuthUser>,
+    path: Path<(String, u32)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, number) = path.into_inner();
+    find_visible_fan_work(&id, user.as_ref(), &data).await?;
+    let chapter = find_chapter(&id, number, &data).await?;
+    Ok(HttpResponse::Ok().json(chapter))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateChapterDto,
+    responses((status = 200, description = "The chapter was updated", body = ChapterDto))
+)]
+#[patch("{id}/chapters/{number}")]
+pub async fn update_chapter(
+    user: AuthUser,
+    path: Path<(String, u32)>,
+    body: ValidJson<UpdateChapterDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, number) = path.into_inner();
+    ensure_author(&find_fan_work(&id, &data).await?, &user)?;
+    let chapter = find_chapter(&id, number, &data).await?;
+    let dto = body.into_inner();
+    if dto.body.as_ref().is_some_and(|b| b.trim().is_empty()) {
+        return Err(AppError::from((
+            ErrorCode::EmptyContent,
+            "A chapter cannot be empty",
+        )));
+    }
+    let chapter_id = chapter.id.unwrap_or_default();
+    let chapter = data
+        .chapter_service
+        .update(&chapter_id, dto, &user.id)
+        .await?;
+    data.fan_work_interaction_service
+        .refresh_word_count(&id)
+        .await?;
+    Ok(HttpResponse::Ok().json(chapter))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The chapter was deleted"))
+)]
+#[delete("{id}/chapters/{number}")]
+pub async fn delete_chapter(
+    user: AuthUser,
+    path: Path<(String, u32)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, number) = path.into_inner();
+    ensure_author(&find_fan_work(&id, &data).await?, &user)?;
+    let chapter = find_chapter(&id, number, &data).await?;
+    let chapter_id = chapter.id.unwrap_or_default();
+    data.chapter_service.delete(&chapter_id, &user.id).await?;
+    data.fan_work_interaction_service
+        .refresh_word_count(&id)
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The fan work was liked", body = LikedFanWorkDto))
+)]
+#[post("{id}/like")]
+pub async fn like_fan_work(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_fan_work(&id, Some(&user), &data).await?;
+    data.fan_work_interaction_service
+        .like(&id, &user.id)
+        .await?;
+    let work = find_fan_work(&id, &data).await?;
+    Ok(HttpResponse::Ok().json(LikedFanWorkDto {
+        work,
+        liked: Some(true),
+    }))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The like was taken back", body = LikedFanWorkDto))
+)]
+#[delete("{id}/like")]
+pub async fn unlike_fan_work(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_fan_work(&id, Some(&user), &data).await?;
+    data.fan_work_interaction_service
+        .unlike(&id, &user.id)
+        .await?;
+    let work = find_fan_work(&id, &data).await?;
+    Ok(HttpResponse::Ok().json(LikedFanWorkDto {
+        work,
+        liked: Some(false),
+    }))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateReportDto,
+    responses(
+        (status = 201, description = "The fan work was reported", body = FanWorkReportDto)
+    )
+)]
+#[post("{id}/reports")]
+pub async fn report_fan_work(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<CreateReportDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let report = data
+        .fan_work_interaction_service
+        .report(&path.into_inner(), &user.id, body.into_inner())
+        .await?;
+    Ok(HttpResponse::Created().json(report))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of fan works waiting for moderation",
+            body = Pagination<FanWorkQueueItemDto>
+        )
+    )
+)]
+#[get("moderation/queue")]
+pub async fn get_fan_work_moderation_queue(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let queue = data
+        .fan_work_interaction_service
+        .queue(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(queue))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The fan work was moderated", body = FanWorkActionDto)
+    )
+)]
+#[post("{id}/moderation")]
+pub async fn moderate_fan_work(
+    user: AuthUser,
+    path: Path<String>,
+    body: Json<ModerateFanWorkDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let action = data
+        .fan_work_interaction_service
+        .moderate(
+            &data.fan_work_service,
+            &path.into_inner(),
+            &user.id,
+            body.into_inner(),
+        )
+        .await?;
+    Ok(HttpResponse::Ok().json(action))
+}
+
+/// Loads a fan work, or fails with a 404.
+async fn find_fan_work(id: &str, data: &AppState) -> Result<FanWorkDto, AppError> {
+    data.fan_work_service
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Fan work not found".to_string()))
+}
+
+/// Loads a fan work the caller may read, or fails with a 404.
+///
+/// Held and hidden fan works are only shown to their author and to staff.
+async fn find_visible_fan_work(
+    id: &str,
+    user: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<FanWorkDto, AppError> {
+    let work = find_fan_work(id, data).await?;
+    if work.status != FanWorkStatus::Published
+        && user.is_none_or(|u| ensure_author(&work, u).is_err())
+    {
+        return Err(AppError::NotFound("Fan work not found".to_string()));
+    }
+    Ok(work)
+}
+
+/// Loads a chapter of a fanfiction by its number, or fails with a 404.
+async fn find_chapter(work: &str, number: u32, data: &AppState) -> Result<ChapterDto, AppError> {
+    data.chapter_service
+        .find(Some(doc! { "work": work, "number": number }), None)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::NotFound("Chapter not found".to_string()))
+}
+
+/// Ensures the user created the fan work, or is a staff member.
+fn ensure_author(work: &FanWorkDto, user: &AuthUser) -> Result<(), AppError> {
+    if user.is_staff || work.user == user.id {
+        Ok(())
+    } else {
+        Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author can change this fan work",
+        )))
+    }
+}
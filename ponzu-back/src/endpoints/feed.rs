@@ -0,0 +1,130 @@
+use crate::dto::activity::{ActivityDto, ActivitySettingsDto, FeedQuery};
+use crate::dto::pagination::CursorPage;
+use crate::dto::user::UpdateUserDto;
+use crate::endpoints::user::find_user;
+use crate::models::user::Visibility;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::utils::bson::parse_object_id;
+use actix_web::web::{scope, Data, Json, Query};
+use actix_web::{get, put, HttpResponse};
+use mongodb::bson::doc;
+use utoipa::OpenApi;
+
+pub fn create_feed_scope() -> actix_web::Scope {
+    scope("/feed")
+        .service(get_feed)
+        .service(get_activity_settings)
+        .service(update_activity_settings)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_feed, get_activity_settings, update_activity_settings))]
+pub struct FeedApi;
+
+/// Reads the activity of the users the caller follows, newest first.
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(FeedQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the activity of the followed users",
+            body = CursorPage<ActivityDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_feed(
+    user: AuthUser,
+    query: Query<FeedQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let ids = data
+        .follow_service
+        .followed_ids(&user.id)
+        .await?
+        .iter()
+        .map(|id| parse_object_id(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let authors = match ids.is_empty() {
+        true => Vec::new(),
+        false => data
+            .user_service
+            .find(Some(doc! { "_id": { "$in": ids } }), None)
+            .await?
+            .into_iter()
+            .filter(|u| u.privacy.activity != Visibility::Private)
+            .filter_map(|u| u.id.map(|id| (id, u.published_activities)))
+            .collect(),
+    };
+    let page = data
+        .activity_service
+        .stream(authors, query.before.as_deref(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The activity settings of the caller",
+            body = ActivitySettingsDto
+        )
+    )
+)]
+#[get("settings")]
+pub async fn get_activity_settings(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user(&user.id, &data).await?;
+    Ok(HttpResponse::Ok().json(ActivitySettingsDto {
+        published: found.published_activities,
+    }))
+}
+
+/// Chooses which kinds of activity the caller shows to their followers.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The activity settings were updated",
+            body = ActivitySettingsDto
+        )
+    )
+)]
+#[put("settings")]
+pub async fn update_activity_settings(
+    user: AuthUser,
+    body: Json<ActivitySettingsDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut published = body.into_inner().published;
+    published.sort();
+    published.dedup();
+    let dto = UpdateUserDto {
+        username: None,
+        email: None,
+        password: None,
+        is_active: None,
+        is_staff: None,
+        is_superuser: None,
+        images: None,
+        bio: None,
+        birth_date: None,
+        published_activities: Some(published),
+        enabled_notifications: None,
+        privacy: None,
+        explicit_content: None,
+    };
+    let updated = data.user_service.update(&user.id, dto, &user.id).await?;
+    Ok(HttpResponse::Ok().json(ActivitySettingsDto {
+        published: updated.published_activities,
+    }))
+}
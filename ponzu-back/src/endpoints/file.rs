@@ -0,0 +1,49 @@
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::openapi::Binary;
+use crate::utils::images::ImageFormat;
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::web::{scope, Data, Path};
+use actix_web::{get, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_file_scope() -> actix_web::Scope {
+    scope("/files").service(get_file)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_file))]
+pub struct FileApi;
+
+/// Serves a stored file. Keys are never reused, so the files can be cached indefinitely.
+#[utoipa::path(
+    responses(
+        (
+            status = 200,
+            description = "The file",
+            content_type = "application/octet-stream",
+            body = Binary
+        )
+    )
+)]
+#[get("{key:.*}")]
+pub async fn get_file(path: Path<String>, data: Data<AppState>) -> Result<HttpResponse, AppError> {
+    let key = path.into_inner();
+    let bytes = data
+        .storage
+        .get(&key)
+        .await?
+        .ok_or_else(|| AppError::NotFound("File not found".to_string()))?;
+    let mime = key
+        .rsplit_once('.')
+        .and_then(|(_, extension)| ImageFormat::from_extension(extension))
+        .map_or("application/octet-stream", |format| format.mime());
+    Ok(HttpResponse::Ok()
+        .content_type(mime)
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(31_536_000),
+            CacheDirective::Extension("immutable".to_string(), None),
+        ]))
+        .body(bytes))
+}
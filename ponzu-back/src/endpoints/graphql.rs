@@ -0,0 +1,72 @@
+use crate::graphql::loaders::request_loader;
+use crate::graphql::schema::PonzuSchema;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use actix_web::web::{scope, Data, Json};
+use actix_web::{get, post, HttpResponse};
+use async_graphql::http::GraphiQLSource;
+use async_graphql::Request;
+use utoipa::OpenApi;
+
+pub fn create_graphql_scope() -> actix_web::Scope {
+    scope("/graphql").service(execute_graphql).service(graphiql)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(execute_graphql, graphiql))]
+pub struct GraphqlApi;
+
+/// Runs a GraphQL query or mutation over the catalogue and the social graph.
+///
+/// The caller is authenticated the same way as on the REST endpoints, and sees the same
+/// documents. Queries deeper or more complex than the configured limits are rejected.
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    request_body(
+        content = Object,
+        description = "A GraphQL request, with its `query`, `operationName` and `variables`"
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The GraphQL response, with its `data` and `errors`",
+            body = Object
+        )
+    )
+)]
+#[post("")]
+pub async fn execute_graphql(
+    user: Option<AuthUser>,
+    body: Json<Request>,
+    schema: Data<PonzuSchema>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut request = body
+        .into_inner()
+        .data(request_loader(&data))
+        .data(data.clone());
+    if let Some(user) = user {
+        request = request.data(user);
+    }
+    let response = schema.execute(request).await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Serves GraphiQL, to explore the schema and run queries from a browser.
+#[utoipa::path(
+    responses(
+        (
+            status = 200,
+            description = "The GraphiQL page",
+            content_type = "text/html",
+            body = String
+        )
+    )
+)]
+#[get("")]
+pub async fn graphiql() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(GraphiQLSource::build().endpoint("/api/graphql").finish())
+}
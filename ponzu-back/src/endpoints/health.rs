@@ -0,0 +1,114 @@
+use crate::dto::health::{HealthStatus, LivenessDto, ReadinessDto, VersionDto};
+use crate::types::app_state::AppState;
+use actix_web::dev::ServerHandle;
+use actix_web::web::{Data, ServiceConfig};
+use actix_web::{get, HttpResponse};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use futures::future::{pending, select};
+use std::pin::pin;
+use std::time::Duration;
+use utoipa::OpenApi;
+
+/// Serves the probes and the version of the server, outside of the `/api` scope.
+pub fn configure_health(cfg: &mut ServiceConfig) {
+    cfg.service(get_liveness)
+        .service(get_readiness)
+        .service(get_version);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_liveness, get_readiness, get_version))]
+pub struct HealthApi;
+
+/// Tells that the server process runs. Its dependencies are not checked.
+#[utoipa::path(
+    responses((status = 200, description = "The server is alive", body = LivenessDto))
+)]
+#[get("/health/live")]
+pub async fn get_liveness() -> HttpResponse {
+    HttpResponse::Ok().json(LivenessDto {
+        status: HealthStatus::Up,
+    })
+}
+
+/// Tells whether the server can serve requests, checking each of its dependencies.
+#[utoipa::path(
+    responses(
+        (status = 200, description = "The server is ready", body = ReadinessDto),
+        (
+            status = 503,
+            description = "A dependency is down, or the server is shutting down",
+            body = ReadinessDto
+        )
+    )
+)]
+#[get("/health/ready")]
+pub async fn get_readiness(data: Data<AppState>) -> HttpResponse {
+    let readiness = data.health_service.readiness().await;
+    match readiness.status {
+        HealthStatus::Up => HttpResponse::Ok().json(readiness),
+        HealthStatus::Down => HttpResponse::ServiceUnavailable().json(readiness),
+    }
+}
+
+/// Reads the version of the server, and the commit and time it was built from.
+#[utoipa::path(
+    responses((status = 200, description = "The build of the server", body = VersionDto))
+)]
+#[get("/version")]
+pub async fn get_version() -> HttpResponse {
+    let build_time = env!("PONZU_BUILD_TIME")
+        .parse()
+        .ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default();
+    HttpResponse::Ok().json(VersionDto {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: env!("PONZU_GIT_SHA").to_string(),
+        build_time,
+    })
+}
+
+/// Stops the server gracefully on `SIGINT` or `SIGTERM`.
+///
+/// Readiness fails for the configured grace period first, so that load balancers stop routing
+/// requests to the server before it closes its sockets.
+pub async fn drain_on_shutdown(server: ServerHandle, data: Data<AppState>) {
+    shutdown_signal().await;
+    let grace = data.config.shutdown_grace_seconds;
+    println!("{} closing in {} seconds", "Shutting down:".yellow(), grace);
+    data.health_service.begin_shutdown();
+    actix_web::rt::time::sleep(Duration::from_secs(grace)).await;
+    server.stop(true).await;
+}
+
+/// Waits for `SIGINT` or `SIGTERM`.
+async fn shutdown_signal() {
+    let interrupt = pin!(async {
+        if actix_web::rt::signal::ctrl_c().await.is_err() {
+            pending::<()>().await;
+        }
+    });
+    select(interrupt, pin!(terminate_signal())).await;
+}
+
+/// Waits for `SIGTERM`, sent by process managers and orchestrators.
+#[cfg(unix)]
+async fn terminate_signal() {
+    use actix_web::rt::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            terminate.recv().await;
+        }
+        Err(_) => pending::<()>().await,
+    }
+}
+
+/// Never completes, as only `SIGINT` is delivered outside of Unix.
+#[cfg(not(unix))]
+async fn terminate_signal() {
+    pending::<()>().await;
+}
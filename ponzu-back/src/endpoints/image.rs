@@ -0,0 +1,101 @@
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::openapi::Binary;
+use actix_web::body::{to_bytes, BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{
+    CacheControl, CacheDirective, ETag, EntityTag, IfNoneMatch, CONTENT_LENGTH,
+};
+use actix_web::middleware::Next;
+use actix_web::web::{scope, Data, Header, Path};
+use actix_web::{get, Error, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_image_scope() -> actix_web::Scope {
+    scope("/images").service(get_mirrored_image)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_mirrored_image))]
+pub struct ImageApi;
+
+/// Serves the local copy of an image of the origin. Copies never change, so they can be cached
+/// indefinitely and revalidated through their ETag.
+#[utoipa::path(
+    responses(
+        (status = 200, description = "The image", content_type = "image/*", body = Binary),
+        (status = 304, description = "The cached copy is still valid")
+    )
+)]
+#[get("proxy/{hash}")]
+pub async fn get_mirrored_image(
+    path: Path<String>,
+    if_none_match: Option<Header<IfNoneMatch>>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (mirror, bytes) = data
+        .image_mirror_service
+        .get(&path.into_inner())
+        .await?
+        .ok_or_else(|| AppError::NotFound("Image not found".to_string()))?;
+    let etag = EntityTag::new_strong(mirror.etag);
+    let cache = CacheControl(vec![
+        CacheDirective::Public,
+        CacheDirective::MaxAge(31_536_000),
+        CacheDirective::Extension("immutable".to_string(), None),
+    ]);
+
+    let fresh = match if_none_match.map(Header::into_inner) {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        None => false,
+    };
+    if fresh {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .insert_header(cache)
+            .finish());
+    }
+    Ok(HttpResponse::Ok()
+        .content_type(mirror.content_type)
+        .insert_header(ETag(etag))
+        .insert_header(cache)
+        .body(bytes))
+}
+
+/// Rewrites the URLs of mirrored images in the JSON responses to the URLs of their copies.
+pub async fn rewrite_mirrored_images(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let mirrors = req
+        .app_data::<Data<AppState>>()
+        .map(|data| data.image_mirror_service.clone());
+    let res = next.call(req).await?;
+
+    let is_json = res
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let Some(mirrors) = mirrors.filter(|m| is_json && m.has_mirrors()) else {
+        return Ok(res.map_into_boxed_body());
+    };
+
+    let (req, res) = res.into_parts();
+    let (mut res, body) = res.into_parts();
+    let bytes = to_bytes(body)
+        .await
+        .map_err(|_| AppError::from("Failed to read the response"))?;
+    let body = match serde_json::from_slice(&bytes) {
+        Ok(mut value) => match mirrors.rewrite(&mut value) {
+            true => {
+                res.headers_mut().remove(CONTENT_LENGTH);
+                BoxBody::new(serde_json::to_vec(&value).unwrap_or_else(|_| bytes.to_vec()))
+            }
+            false => BoxBody::new(bytes),
+        },
+        Err(_) => BoxBody::new(bytes),
+    };
+    Ok(ServiceResponse::new(req, res.set_body(body)))
+}
@@ -0,0 +1,148 @@
+use crate::dto::list_entry::{CreateListEntryDto, ListEntryDto, UpdateListEntryDto};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::models::activity::ActivityKind;
+use crate::models::list_entry::ListStatus;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Path, Query};
+use actix_web::{delete, get, put, HttpResponse};
+use mongodb::bson::{doc, to_bson};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+
+pub fn create_list_scope() -> actix_web::Scope {
+    scope("/list")
+        .service(get_my_list)
+        .service(save_list_entry)
+        .service(delete_list_entry)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_my_list, save_list_entry, delete_list_entry))]
+pub struct ListApi;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListFilter {
+    pub status: Option<ListStatus>,
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery, ListFilter),
+    responses(
+        (
+            status = 200,
+            description = "A page of the list of the caller",
+            body = Pagination<ListEntryDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_my_list(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    filter: Query<ListFilter>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut criteria = doc! { "user": &user.id };
+    if let Some(status) = filter.status {
+        criteria.insert(
+            "status",
+            to_bson(&status).map_err(|e| AppError::from(e.to_string()))?,
+        );
+    }
+    let page = data
+        .list_service
+        .get_paginated(Some(criteria), query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateListEntryDto,
+    responses(
+        (status = 200, description = "The entry was updated", body = ListEntryDto),
+        (status = 201, description = "The entry was added", body = ListEntryDto)
+    )
+)]
+#[put("{entry}")]
+pub async fn save_list_entry(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<CreateListEntryDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut dto = body.into_inner();
+    if !matches!(dto.r#type.as_str(), "anime" | "manga") {
+        return Err(AppError::from((
+            ErrorCode::InvalidEntryType,
+            "List entries are anime or manga",
+        )));
+    }
+    dto.user = user.id.clone();
+    dto.entry = path.into_inner();
+
+    let existing = data
+        .list_service
+        .find(Some(doc! { "user": &dto.user, "entry": &dto.entry }), None)
+        .await?
+        .into_iter()
+        .next();
+    let previous = existing.as_ref().map(|e| e.status);
+    let (entry, created) = match existing.and_then(|e| e.id) {
+        Some(id) => {
+            let entry = data
+                .list_service
+                .update(&id, UpdateListEntryDto::from(dto), &user.id)
+                .await?;
+            (entry, false)
+        }
+        None => (data.list_service.create(dto).await?, true),
+    };
+
+    // Followers hear about new entries and status changes, not about progress
+    if previous != Some(entry.status) {
+        let status = to_bson(&entry.status).map_err(|e| AppError::from(e.to_string()))?;
+        data.activity_service
+            .record(
+                &user.id,
+                ActivityKind::ListUpdate,
+                &entry.entry,
+                status.as_str().map(str::to_string),
+            )
+            .await?;
+    }
+    match created {
+        true => Ok(HttpResponse::Created().json(entry)),
+        false => Ok(HttpResponse::Ok().json(entry)),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The entry was removed"))
+)]
+#[delete("{entry}")]
+pub async fn delete_list_entry(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let deleted = data
+        .list_service
+        .delete_by_criteria(
+            doc! { "user": &user.id, "entry": path.into_inner() },
+            &user.id,
+        )
+        .await?;
+    match deleted {
+        0 => Err(AppError::NotFound("Entry is not on your list".to_string())),
+        _ => Ok(HttpResponse::NoContent().finish()),
+    }
+}
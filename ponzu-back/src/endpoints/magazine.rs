@@ -0,0 +1,229 @@
+use crate::dto::magazine::{CreateMagazineDto, MagazineDto, UpdateMagazineDto};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_magazine_scope() -> actix_web::Scope {
+    scope("/magazines")
+        .service(get_magazine_trash)
+        .service(restore_magazine)
+        .service(purge_magazine)
+        .service(get_all_magazines)
+        .service(get_magazine)
+        .service(create_magazine)
+        .service(update_magazine)
+        .service(delete_magazine)
+        .service(get_magazine_history)
+        .service(revert_magazine)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_magazines,
+    get_magazine,
+    create_magazine,
+    update_magazine,
+    delete_magazine,
+    get_magazine_trash,
+    restore_magazine,
+    purge_magazine,
+    get_magazine_history,
+    revert_magazine
+))]
+pub struct MagazineApi;
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "A page of magazines", body = Pagination<MagazineDto>)
+    )
+)]
+#[get("")]
+pub async fn get_all_magazines(
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .magazine_service
+        .get_paginated(None, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    responses((status = 200, description = "The magazine", body = MagazineDto))
+)]
+#[get("{id}")]
+pub async fn get_magazine(
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    match data.magazine_service.get_by_id(&path.into_inner()).await? {
+        Some(magazine) => Ok(HttpResponse::Ok().json(magazine)),
+        None => Err(AppError::NotFound("Magazine not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateMagazineDto,
+    responses((status = 201, description = "The magazine was created", body = MagazineDto))
+)]
+#[post("")]
+pub async fn create_magazine(
+    user: AuthUser,
+    body: ValidJson<CreateMagazineDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let magazine = data.magazine_service.create(body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(magazine))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateMagazineDto,
+    responses((status = 200, description = "The magazine was updated", body = MagazineDto))
+)]
+#[patch("{id}")]
+pub async fn update_magazine(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateMagazineDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let magazine = data
+        .magazine_service
+        .update(&path.into_inner(), body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(magazine))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The magazine was moved to the trash"))
+)]
+#[delete("{id}")]
+pub async fn delete_magazine(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data
+        .magazine_service
+        .delete(&path.into_inner(), &user.id)
+        .await?
+    {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Magazine not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of trashed magazines",
+            body = Pagination<TrashedDto<MagazineDto>>
+        )
+    )
+)]
+#[get("trash")]
+pub async fn get_magazine_trash(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let trash = data
+        .magazine_service
+        .get_trash(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The magazine was restored", body = MagazineDto))
+)]
+#[post("trash/{id}/restore")]
+pub async fn restore_magazine(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let magazine = data.magazine_service.restore(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(magazine))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The magazine was deleted for good"))
+)]
+#[delete("trash/{id}")]
+pub async fn purge_magazine(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data.magazine_service.purge(&path.into_inner()).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound(
+            "Magazine not found in trash".to_string(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The revisions of the magazine",
+            body = Vec<RevisionDto>
+        )
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_magazine_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.magazine_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The magazine was reverted", body = MagazineDto))
+)]
+#[post("{id}/history/{revision_id}/revert")]
+pub async fn revert_magazine(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let (id, revision_id) = path.into_inner();
+    let magazine = data
+        .magazine_service
+        .revert(&id, &revision_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(magazine))
+}
@@ -0,0 +1,2 @@
+pub mod title;
+pub mod review;
@@ -0,0 +1,267 @@
+use crate::dto::manga::{CreateMangaDto, MangaDto, UpdateMangaDto};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::endpoints::club::get_manga_clubs;
+use crate::endpoints::manga::review::get_manga_reviews;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::openapi::Binary;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Bytes, Data, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_manga_scope() -> actix_web::Scope {
+    scope("/manga")
+        .service(get_manga_trash)
+        .service(restore_manga_title)
+        .service(purge_manga_title)
+        .service(get_all_manga_titles)
+        .service(get_manga_title)
+        .service(create_manga_title)
+        .service(update_manga_title)
+        .service(delete_manga_title)
+        .service(upload_manga_cover)
+        .service(get_manga_title_history)
+        .service(revert_manga_title)
+        .service(get_manga_reviews)
+        .service(get_manga_clubs)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_manga_titles,
+    get_manga_title,
+    create_manga_title,
+    update_manga_title,
+    delete_manga_title,
+    upload_manga_cover,
+    get_manga_trash,
+    restore_manga_title,
+    purge_manga_title,
+    get_manga_title_history,
+    revert_manga_title,
+    crate::endpoints::manga::review::get_manga_reviews,
+    crate::endpoints::club::get_manga_clubs
+))]
+pub struct MangaApi;
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses((status = 200, description = "A page of manga", body = Pagination<MangaDto>))
+)]
+#[get("")]
+pub async fn get_all_manga_titles(
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .manga_service
+        .get_paginated(None, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    responses((status = 200, description = "The manga", body = MangaDto))
+)]
+#[get("{id}")]
+pub async fn get_manga_title(
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    match data.manga_service.get_by_id(&path.into_inner()).await? {
+        Some(manga) => Ok(HttpResponse::Ok().json(manga)),
+        None => Err(AppError::NotFound("Manga not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateMangaDto,
+    responses((status = 201, description = "The manga was created", body = MangaDto))
+)]
+#[post("")]
+pub async fn create_manga_title(
+    user: AuthUser,
+    body: ValidJson<CreateMangaDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let manga = data.manga_service.create(body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(manga))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateMangaDto,
+    responses((status = 200, description = "The manga was updated", body = MangaDto))
+)]
+#[patch("{id}")]
+pub async fn update_manga_title(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateMangaDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let manga = data
+        .manga_service
+        .update(&path.into_inner(), body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(manga))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The manga was moved to the trash"))
+)]
+#[delete("{id}")]
+pub async fn delete_manga_title(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data
+        .manga_service
+        .delete(&path.into_inner(), &user.id)
+        .await?
+    {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Manga not found".to_string())),
+    }
+}
+
+/// Uploads the cover of a manga title, sent as the raw request body.
+///
+/// The previous cover is kept, as the edit history can still revert to it.
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body(content = Binary, content_type = "image/*"),
+    responses((status = 200, description = "The cover was replaced", body = MangaDto))
+)]
+#[put("{id}/cover")]
+pub async fn upload_manga_cover(
+    user: AuthUser,
+    path: Path<String>,
+    body: Bytes,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let id = path.into_inner();
+    if data.manga_service.get_by_id(&id).await?.is_none() {
+        return Err(AppError::NotFound("Manga not found".to_string()));
+    }
+    let images = data
+        .image_service
+        .upload(&format!("manga/{}", id), body)
+        .await?;
+    let update = UpdateMangaDto {
+        images: Some(images.clone()),
+        ..Default::default()
+    };
+    match data.manga_service.update(&id, update, &user.id).await {
+        Ok(manga) => Ok(HttpResponse::Ok().json(manga)),
+        Err(e) => {
+            data.image_service.remove(&images).await?;
+            Err(e)
+        }
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of trashed manga",
+            body = Pagination<TrashedDto<MangaDto>>
+        )
+    )
+)]
+#[get("trash")]
+pub async fn get_manga_trash(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let trash = data
+        .manga_service
+        .get_trash(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The manga was restored", body = MangaDto))
+)]
+#[post("trash/{id}/restore")]
+pub async fn restore_manga_title(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let manga = data.manga_service.restore(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(manga))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The manga was deleted for good"))
+)]
+#[delete("trash/{id}")]
+pub async fn purge_manga_title(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data.manga_service.purge(&path.into_inner()).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Manga not found in trash".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The revisions of the manga", body = Vec<RevisionDto>)
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_manga_title_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.manga_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The manga was reverted", body = MangaDto))
+)]
+#[post("{id}/history/{revision_id}/revert")]
+pub async fn revert_manga_title(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let (id, revision_id) = path.into_inner();
+    let manga = data
+        .manga_service
+        .revert(&id, &revision_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(manga))
+}
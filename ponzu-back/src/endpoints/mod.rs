@@ -1,3 +1,24 @@
 pub mod scope;
+pub mod admin;
 pub mod anime;
+pub mod auth;
+pub mod board;
+pub mod character;
+pub mod club;
+pub mod comment;
 pub mod default;
+pub mod docs;
+pub mod fan_work;
+pub mod feed;
+pub mod file;
+pub mod graphql;
+pub mod health;
+pub mod image;
+pub mod list;
+pub mod magazine;
+pub mod manga;
+pub mod notification;
+pub mod person;
+pub mod producer;
+pub mod review;
+pub mod user;
@@ -0,0 +1,234 @@
+use crate::dto::notification::{
+    NotificationDto, NotificationQuery, NotificationSettingsDto, UnreadCountDto,
+};
+use crate::dto::pagination::CursorPage;
+use crate::dto::user::UpdateUserDto;
+use crate::endpoints::user::find_user;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use actix_web::http::header::CACHE_CONTROL;
+use actix_web::web::{scope, Bytes, Data, Json, Path, Query};
+use actix_web::{get, post, put, Error, HttpResponse};
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use std::time::Duration;
+use utoipa::OpenApi;
+
+/// How often an idle notification stream sends a comment, to keep proxies from closing it.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+pub fn create_notification_scope() -> actix_web::Scope {
+    scope("/notifications")
+        .service(get_notifications)
+        .service(get_unread_count)
+        .service(stream_notifications)
+        .service(mark_all_notifications_read)
+        .service(mark_notification_read)
+        .service(get_notification_settings)
+        .service(update_notification_settings)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_notifications,
+    get_unread_count,
+    stream_notifications,
+    mark_all_notifications_read,
+    mark_notification_read,
+    get_notification_settings,
+    update_notification_settings
+))]
+pub struct NotificationApi;
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(NotificationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the notifications of the caller",
+            body = CursorPage<NotificationDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_notifications(
+    user: AuthUser,
+    query: Query<NotificationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .notification_service
+        .list(
+            &user.id,
+            query.before.as_deref(),
+            query.limit(),
+            query.unread,
+        )
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The number of unread notifications",
+            body = UnreadCountDto
+        )
+    )
+)]
+#[get("unread")]
+pub async fn get_unread_count(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let unread = data.notification_service.unread_count(&user.id).await?;
+    Ok(HttpResponse::Ok().json(UnreadCountDto { unread }))
+}
+
+/// Streams the notifications of the caller as Server-Sent Events.
+///
+/// The stream opens with an `unread` event holding the number of unread notifications, then
+/// sends a `notification` event for each new notification.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "Server-sent `unread` and `notification` events",
+            content_type = "text/event-stream",
+            body = String
+        )
+    )
+)]
+#[get("stream")]
+pub async fn stream_notifications(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let receiver = data.notification_service.subscribe(&user.id);
+    let unread = data.notification_service.unread_count(&user.id).await?;
+
+    let opening = stream::once(async move { event("unread", &UnreadCountDto { unread }) });
+    let notifications = receiver.map(|n: NotificationDto| event("notification", &n));
+    let keep_alive = stream::unfold(
+        actix_web::rt::time::interval(KEEP_ALIVE),
+        |mut interval| async move {
+            interval.tick().await;
+            Some((Ok(Bytes::from_static(b": keep-alive\n\n")), interval))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .streaming(opening.chain(stream::select(notifications, keep_alive))))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "Every notification was marked as read"))
+)]
+#[post("read")]
+pub async fn mark_all_notifications_read(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    data.notification_service.mark_all_read(&user.id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The notification was marked as read",
+            body = NotificationDto
+        )
+    )
+)]
+#[post("{id}/read")]
+pub async fn mark_notification_read(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let notification = data
+        .notification_service
+        .mark_read(&user.id, &path.into_inner())
+        .await?;
+    Ok(HttpResponse::Ok().json(notification))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The notification settings of the caller",
+            body = NotificationSettingsDto
+        )
+    )
+)]
+#[get("settings")]
+pub async fn get_notification_settings(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user(&user.id, &data).await?;
+    Ok(HttpResponse::Ok().json(NotificationSettingsDto {
+        enabled: found.enabled_notifications,
+    }))
+}
+
+/// Chooses which kinds of notification the caller receives.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The notification settings were updated",
+            body = NotificationSettingsDto
+        )
+    )
+)]
+#[put("settings")]
+pub async fn update_notification_settings(
+    user: AuthUser,
+    body: Json<NotificationSettingsDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut enabled = body.into_inner().enabled;
+    enabled.sort();
+    enabled.dedup();
+    let dto = UpdateUserDto {
+        username: None,
+        email: None,
+        password: None,
+        is_active: None,
+        is_staff: None,
+        is_superuser: None,
+        images: None,
+        bio: None,
+        birth_date: None,
+        published_activities: None,
+        enabled_notifications: Some(enabled),
+        privacy: None,
+        explicit_content: None,
+    };
+    let updated = data.user_service.update(&user.id, dto, &user.id).await?;
+    Ok(HttpResponse::Ok().json(NotificationSettingsDto {
+        enabled: updated.enabled_notifications,
+    }))
+}
+
+/// Encodes a Server-Sent Event holding a JSON payload.
+fn event<T: Serialize>(name: &str, payload: &T) -> Result<Bytes, Error> {
+    let data = serde_json::to_string(payload).map_err(|e| AppError::from(e.to_string()))?;
+    Ok(Bytes::from(format!("event: {}\ndata: {}\n\n", name, data)))
+}
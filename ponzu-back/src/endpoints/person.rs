@@ -0,0 +1,224 @@
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::person::{CreatePersonDto, PersonDto, UpdatePersonDto};
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::endpoints::club::get_person_clubs;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_person_scope() -> actix_web::Scope {
+    scope("/people")
+        .service(get_person_trash)
+        .service(restore_person)
+        .service(purge_person)
+        .service(get_all_people)
+        .service(get_person)
+        .service(create_person)
+        .service(update_person)
+        .service(delete_person)
+        .service(get_person_history)
+        .service(revert_person)
+        .service(get_person_clubs)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_people,
+    get_person,
+    create_person,
+    update_person,
+    delete_person,
+    get_person_trash,
+    restore_person,
+    purge_person,
+    get_person_history,
+    revert_person,
+    crate::endpoints::club::get_person_clubs
+))]
+pub struct PersonApi;
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses((status = 200, description = "A page of people", body = Pagination<PersonDto>))
+)]
+#[get("")]
+pub async fn get_all_people(
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .people_service
+        .get_paginated(None, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    responses((status = 200, description = "The person", body = PersonDto))
+)]
+#[get("{id}")]
+pub async fn get_person(
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    match data.people_service.get_by_id(&path.into_inner()).await? {
+        Some(person) => Ok(HttpResponse::Ok().json(person)),
+        None => Err(AppError::NotFound("Person not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreatePersonDto,
+    responses((status = 201, description = "The person was created", body = PersonDto))
+)]
+#[post("")]
+pub async fn create_person(
+    user: AuthUser,
+    body: ValidJson<CreatePersonDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let person = data.people_service.create(body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(person))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdatePersonDto,
+    responses((status = 200, description = "The person was updated", body = PersonDto))
+)]
+#[patch("{id}")]
+pub async fn update_person(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdatePersonDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let person = data
+        .people_service
+        .update(&path.into_inner(), body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(person))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The person was moved to the trash"))
+)]
+#[delete("{id}")]
+pub async fn delete_person(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data
+        .people_service
+        .delete(&path.into_inner(), &user.id)
+        .await?
+    {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Person not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of trashed people",
+            body = Pagination<TrashedDto<PersonDto>>
+        )
+    )
+)]
+#[get("trash")]
+pub async fn get_person_trash(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let trash = data
+        .people_service
+        .get_trash(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The person was restored", body = PersonDto))
+)]
+#[post("trash/{id}/restore")]
+pub async fn restore_person(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let person = data.people_service.restore(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(person))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The person was deleted for good"))
+)]
+#[delete("trash/{id}")]
+pub async fn purge_person(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data.people_service.purge(&path.into_inner()).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Person not found in trash".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The revisions of the person", body = Vec<RevisionDto>)
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_person_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.people_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The person was reverted", body = PersonDto))
+)]
+#[post("{id}/history/{revision_id}/revert")]
+pub async fn revert_person(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let (id, revision_id) = path.into_inner();
+    let person = data
+        .people_service
+        .revert(&id, &revision_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(person))
+}
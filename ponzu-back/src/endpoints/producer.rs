@@ -0,0 +1,229 @@
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::producer::{CreateProducerDto, ProducerDto, UpdateProducerDto};
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use utoipa::OpenApi;
+
+pub fn create_producer_scope() -> actix_web::Scope {
+    scope("/producers")
+        .service(get_producer_trash)
+        .service(restore_producer)
+        .service(purge_producer)
+        .service(get_all_producers)
+        .service(get_producer)
+        .service(create_producer)
+        .service(update_producer)
+        .service(delete_producer)
+        .service(get_producer_history)
+        .service(revert_producer)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_producers,
+    get_producer,
+    create_producer,
+    update_producer,
+    delete_producer,
+    get_producer_trash,
+    restore_producer,
+    purge_producer,
+    get_producer_history,
+    revert_producer
+))]
+pub struct ProducerApi;
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "A page of producers", body = Pagination<ProducerDto>)
+    )
+)]
+#[get("")]
+pub async fn get_all_producers(
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = data
+        .producer_service
+        .get_paginated(None, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    responses((status = 200, description = "The producer", body = ProducerDto))
+)]
+#[get("{id}")]
+pub async fn get_producer(
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    match data.producer_service.get_by_id(&path.into_inner()).await? {
+        Some(producer) => Ok(HttpResponse::Ok().json(producer)),
+        None => Err(AppError::NotFound("Producer not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateProducerDto,
+    responses((status = 201, description = "The producer was created", body = ProducerDto))
+)]
+#[post("")]
+pub async fn create_producer(
+    user: AuthUser,
+    body: ValidJson<CreateProducerDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let producer = data.producer_service.create(body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(producer))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateProducerDto,
+    responses((status = 200, description = "The producer was updated", body = ProducerDto))
+)]
+#[patch("{id}")]
+pub async fn update_producer(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateProducerDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let producer = data
+        .producer_service
+        .update(&path.into_inner(), body.into_inner(), &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(producer))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The producer was moved to the trash"))
+)]
+#[delete("{id}")]
+pub async fn delete_producer(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data
+        .producer_service
+        .delete(&path.into_inner(), &user.id)
+        .await?
+    {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Producer not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of trashed producers",
+            body = Pagination<TrashedDto<ProducerDto>>
+        )
+    )
+)]
+#[get("trash")]
+pub async fn get_producer_trash(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let trash = data
+        .producer_service
+        .get_trash(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(trash))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The producer was restored", body = ProducerDto))
+)]
+#[post("trash/{id}/restore")]
+pub async fn restore_producer(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let producer = data.producer_service.restore(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(producer))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The producer was deleted for good"))
+)]
+#[delete("trash/{id}")]
+pub async fn purge_producer(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    match data.producer_service.purge(&path.into_inner()).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound(
+            "Producer not found in trash".to_string(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The revisions of the producer",
+            body = Vec<RevisionDto>
+        )
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_producer_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.producer_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 200, description = "The producer was reverted", body = ProducerDto))
+)]
+#[post("{id}/history/{revision_id}/revert")]
+pub async fn revert_producer(
+    user: AuthUser,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let (id, revision_id) = path.into_inner();
+    let producer = data
+        .producer_service
+        .revert(&id, &revision_id, &user.id)
+        .await?;
+    Ok(HttpResponse::Ok().json(producer))
+}
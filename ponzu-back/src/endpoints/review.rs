@@ -0,0 +1,550 @@
+use crate::dto::moderation::{
+    CreateReportDto, ModerateReviewDto, ModerationActionDto, ModerationQueueItemDto, ReportDto,
+};
+use crate::dto::pagination::{Pagination, PaginationQuery};
+use crate::dto::reaction::{ReactedDto, ToggleReactionDto};
+use crate::dto::review::{CreateReviewDto, ReviewDto, ReviewQuery, ReviewViewDto, UpdateReviewDto};
+use crate::dto::revision::RevisionDto;
+use crate::endpoints::comment::{
+    create_comment, delete_comment, get_comments, get_my_comment_reactions,
+    toggle_comment_reaction, update_comment,
+};
+use crate::models::activity::ActivityKind;
+use crate::models::list_entry::ListStatus;
+use crate::models::notification::NotificationKind;
+use crate::models::reaction::ReactionType;
+use crate::models::review::ReviewStatus;
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::validation::ValidJson;
+use actix_web::web::{scope, Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, HttpResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, to_bson};
+use std::collections::{HashMap, HashSet};
+use utoipa::OpenApi;
+
+pub fn create_review_scope() -> actix_web::Scope {
+    scope("/reviews")
+        .service(get_moderation_queue)
+        .service(get_all_reviews)
+        .service(get_review)
+        .service(create_review)
+        .service(update_review)
+        .service(delete_review)
+        .service(get_review_history)
+        .service(get_my_reactions)
+        .service(toggle_reaction)
+        .service(report_review)
+        .service(moderate_review)
+        .service(get_comments)
+        .service(create_comment)
+        .service(update_comment)
+        .service(delete_comment)
+        .service(get_my_comment_reactions)
+        .service(toggle_comment_reaction)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_all_reviews,
+    get_review,
+    create_review,
+    update_review,
+    delete_review,
+    get_review_history,
+    get_my_reactions,
+    toggle_reaction,
+    report_review,
+    get_moderation_queue,
+    moderate_review,
+    crate::endpoints::comment::get_comments,
+    crate::endpoints::comment::create_comment,
+    crate::endpoints::comment::update_comment,
+    crate::endpoints::comment::delete_comment,
+    crate::endpoints::comment::get_my_comment_reactions,
+    crate::endpoints::comment::toggle_comment_reaction
+))]
+pub struct ReviewApi;
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery, ReviewQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of published reviews",
+            body = Pagination<ReviewViewDto>
+        )
+    )
+)]
+#[get("")]
+pub async fn get_all_reviews(
+    user: Option<AuthUser>,
+    page: Query<PaginationQuery>,
+    query: Query<ReviewQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let page = list_reviews(user.as_ref(), &page, &query, &data).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(ReviewQuery),
+    responses((status = 200, description = "The review", body = ReviewViewDto))
+)]
+#[get("{id}")]
+pub async fn get_review(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<ReviewQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let view = view_review(&path.into_inner(), user.as_ref(), &query, &data).await?;
+    Ok(HttpResponse::Ok().json(view))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateReviewDto,
+    responses((status = 201, description = "The review was created", body = ReviewDto))
+)]
+#[post("")]
+pub async fn create_review(
+    user: AuthUser,
+    body: ValidJson<CreateReviewDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let mut dto = body.into_inner();
+    dto.user = user.id;
+    data.moderation_service.screen(&mut dto).await?;
+    let review = data.review_service.create(dto).await?;
+    // Held reviews are announced once published, if ever
+    if review.status == ReviewStatus::Published {
+        let id = review.id.as_deref().unwrap_or_default();
+        data.activity_service
+            .record(&review.user, ActivityKind::Review, id, None)
+            .await?;
+    }
+    Ok(HttpResponse::Created().json(review))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = UpdateReviewDto,
+    responses((status = 200, description = "The review was updated", body = ReviewDto))
+)]
+#[patch("{id}")]
+pub async fn update_review(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<UpdateReviewDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    let review = find_review(&id, &data).await?;
+    ensure_author(&review, &user)?;
+    let mut dto = body.into_inner();
+    if !user.is_staff {
+        dto.user = None;
+    }
+    data.moderation_service
+        .screen_edit(&review, &mut dto, user.is_staff)?;
+    let review = data.review_service.update(&id, dto, &user.id).await?;
+    Ok(HttpResponse::Ok().json(review))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The review was deleted"))
+)]
+#[delete("{id}")]
+pub async fn delete_review(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    ensure_author(&find_review(&id, &data).await?, &user)?;
+    match data.review_service.delete(&id, &user.id).await? {
+        true => Ok(HttpResponse::NoContent().finish()),
+        false => Err(AppError::NotFound("Review not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The revisions of the review", body = Vec<RevisionDto>)
+    )
+)]
+#[get("{id}/history")]
+pub async fn get_review_history(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let history = data.review_service.history(&path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The reactions the caller gave the review",
+            body = Vec<ReactionType>
+        )
+    )
+)]
+#[get("{id}/reactions")]
+pub async fn get_my_reactions(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_visible_review(&id, Some(&user), &data).await?;
+    let reactions = data.reaction_service.of_user(&id, &user.id).await?;
+    Ok(HttpResponse::Ok().json(reactions))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The reaction was toggled",
+            body = ReactedDto<ReviewDto>
+        )
+    )
+)]
+#[post("{id}/reactions")]
+pub async fn toggle_reaction(
+    user: AuthUser,
+    path: Path<String>,
+    body: Json<ToggleReactionDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let review = react_to_review(&user, &path.into_inner(), body.reaction, &data).await?;
+    Ok(HttpResponse::Ok().json(review))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body = CreateReportDto,
+    responses((status = 201, description = "The review was reported", body = ReportDto))
+)]
+#[post("{id}/reports")]
+pub async fn report_review(
+    user: AuthUser,
+    path: Path<String>,
+    body: ValidJson<CreateReportDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let report = data
+        .moderation_service
+        .report(&path.into_inner(), &user.id, body.into_inner())
+        .await?;
+    Ok(HttpResponse::Created().json(report))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of reviews waiting for moderation",
+            body = Pagination<ModerationQueueItemDto>
+        )
+    )
+)]
+#[get("moderation/queue")]
+pub async fn get_moderation_queue(
+    user: AuthUser,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let queue = data
+        .moderation_service
+        .queue(query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(queue))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The review was moderated", body = ModerationActionDto)
+    )
+)]
+#[post("{id}/moderation")]
+pub async fn moderate_review(
+    user: AuthUser,
+    path: Path<String>,
+    body: Json<ModerateReviewDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    user.ensure_staff()?;
+    let id = path.into_inner();
+    let before = find_review(&id, &data).await?;
+    let action = data
+        .moderation_service
+        .moderate(
+            &data.review_service,
+            &data.notification_service,
+            &id,
+            &user.id,
+            body.into_inner(),
+        )
+        .await?;
+
+    // A held review is announced once released
+    if before.status == ReviewStatus::Held
+        && find_review(&id, &data).await?.status == ReviewStatus::Published
+    {
+        data.activity_service
+            .record(&before.user, ActivityKind::Review, &id, None)
+            .await?;
+    }
+    Ok(HttpResponse::Ok().json(action))
+}
+
+/// Loads a review as the caller may read it, along with the reactions they gave it.
+///
+/// # Parameters
+/// - `id`: The ID of the review.
+/// - `user`: The caller, if authenticated.
+/// - `query`: The filters of the listing, telling whether spoilers are revealed.
+/// - `data`: The application state.
+///
+/// # Returns
+/// The review, with its spoilers redacted when needed.
+pub async fn view_review(
+    id: &str,
+    user: Option<&AuthUser>,
+    query: &ReviewQuery,
+    data: &AppState,
+) -> Result<ReviewViewDto, AppError> {
+    let review = find_visible_review(id, user, data).await?;
+    let mut view = present_reviews(vec![review], user, query, data)
+        .await?
+        .remove(0);
+    if let Some(user) = user {
+        view.my_reactions = Some(data.reaction_service.of_user(id, &user.id).await?);
+    }
+    Ok(view)
+}
+
+/// Toggles a reaction of the caller to a review, and tells its author when it was given.
+///
+/// Held and hidden reviews can only be reacted to by the users who may read them.
+///
+/// # Parameters
+/// - `user`: The caller.
+/// - `id`: The ID of the review.
+/// - `reaction`: The reaction to toggle.
+/// - `data`: The application state.
+///
+/// # Returns
+/// The review, along with the reactions the caller gave it after the toggle.
+pub async fn react_to_review(
+    user: &AuthUser,
+    id: &str,
+    reaction: ReactionType,
+    data: &AppState,
+) -> Result<ReactedDto<ReviewDto>, AppError> {
+    find_visible_review(id, Some(user), data).await?;
+    let review = data.reaction_service.toggle(id, &user.id, reaction).await?;
+    if record_reaction(user, id, reaction, &review.my_reactions, data).await? {
+        data.notification_service
+            .notify(
+                &review.target.user,
+                NotificationKind::ReviewReaction,
+                Some(&user.id),
+                id,
+                Some(reaction.field().to_string()),
+            )
+            .await?;
+    }
+    Ok(review)
+}
+
+/// Lists a page of reviews as the caller may read them.
+///
+/// # Parameters
+/// - `user`: The caller, if authenticated.
+/// - `page`: The page to read.
+/// - `query`: The filters of the listing.
+/// - `data`: The application state.
+///
+/// # Returns
+/// A `Pagination` of reviews, with their spoilers redacted when needed.
+pub async fn list_reviews(
+    user: Option<&AuthUser>,
+    page: &PaginationQuery,
+    query: &ReviewQuery,
+    data: &AppState,
+) -> Result<Pagination<ReviewViewDto>, AppError> {
+    let page = data
+        .review_service
+        .get_paginated(Some(query.filter()), page.page(), page.limit())
+        .await?;
+    let reviews = present_reviews(page.payload.clone(), user, query, data).await?;
+    Ok(page.with_payload(reviews))
+}
+
+/// Prepares reviews for the caller.
+///
+/// Spoilers are revealed when the caller opted in, or completed the reviewed entry on their list.
+async fn present_reviews(
+    reviews: Vec<ReviewDto>,
+    user: Option<&AuthUser>,
+    query: &ReviewQuery,
+    data: &AppState,
+) -> Result<Vec<ReviewViewDto>, AppError> {
+    let entries = reviews.iter().map(|r| r.entry.as_str()).collect::<Vec<_>>();
+    let completed = match user {
+        Some(user) if !query.reveals_spoilers() => data
+            .list_service
+            .find(
+                Some(doc! {
+                    "user": &user.id,
+                    "entry": { "$in": entries },
+                    "status": to_bson(&ListStatus::Completed)
+                        .map_err(|e| AppError::from(e.to_string()))?,
+                }),
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(|e| e.entry)
+            .collect::<HashSet<_>>(),
+        _ => HashSet::new(),
+    };
+    let lengths = entry_lengths(&reviews, data).await?;
+
+    Ok(reviews
+        .into_iter()
+        .map(|review| {
+            let reveal = query.reveals_spoilers() || completed.contains(&review.entry);
+            let length = lengths.get(&review.entry).copied();
+            ReviewViewDto::new(review, reveal, length)
+        })
+        .collect())
+}
+
+/// Loads the number of episodes or chapters of the entries reviewed by preliminary reviews.
+async fn entry_lengths(
+    reviews: &[ReviewDto],
+    data: &AppState,
+) -> Result<HashMap<String, u32>, AppError> {
+    let ids = |kind: &str| {
+        reviews
+            .iter()
+            .filter(|r| r.is_preliminary && r.r#type == kind)
+            .filter_map(|r| ObjectId::parse_str(&r.entry).ok())
+            .collect::<Vec<_>>()
+    };
+
+    let mut lengths = HashMap::new();
+    let anime = ids("anime");
+    if !anime.is_empty() {
+        for anime in data
+            .anime_service
+            .find(Some(doc! { "_id": { "$in": anime } }), None)
+            .await?
+        {
+            if let (Some(id), Some(episodes)) = (anime.id, anime.episodes) {
+                lengths.insert(id, episodes);
+            }
+        }
+    }
+    let manga = ids("manga");
+    if !manga.is_empty() {
+        for manga in data
+            .manga_service
+            .find(Some(doc! { "_id": { "$in": manga } }), None)
+            .await?
+        {
+            if let (Some(id), Some(chapters)) = (manga.id, manga.chapters) {
+                lengths.insert(id, chapters);
+            }
+        }
+    }
+    Ok(lengths)
+}
+
+/// Loads a review, or fails with a 404.
+async fn find_review(id: &str, data: &AppState) -> Result<ReviewDto, AppError> {
+    data.review_service
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Review not found".to_string()))
+}
+
+/// Loads a review the caller may read, or fails with a 404.
+///
+/// Held and hidden reviews are only shown to their author and to staff.
+pub async fn find_visible_review(
+    id: &str,
+    user: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<ReviewDto, AppError> {
+    let review = find_review(id, data).await?;
+    if review.status != ReviewStatus::Published
+        && user.is_none_or(|u| ensure_author(&review, u).is_err())
+    {
+        return Err(AppError::NotFound("Review not found".to_string()));
+    }
+    Ok(review)
+}
+
+/// Records the reaction of a user as an activity, unless toggling it removed it.
+///
+/// # Parameters
+/// - `target`: The ID of the review or comment reacted to.
+/// - `mine`: The reactions of the user to the target after the toggle.
+///
+/// # Returns
+/// Whether the reaction was given, and thus recorded.
+pub async fn record_reaction(
+    user: &AuthUser,
+    target: &str,
+    reaction: ReactionType,
+    mine: &Option<Vec<ReactionType>>,
+    data: &AppState,
+) -> Result<bool, AppError> {
+    let given = mine.as_ref().is_some_and(|m| m.contains(&reaction));
+    if given {
+        data.activity_service
+            .record(
+                &user.id,
+                ActivityKind::Reaction,
+                target,
+                Some(reaction.field().to_string()),
+            )
+            .await?;
+    }
+    Ok(given)
+}
+
+/// Ensures the user wrote the review, or is a staff member.
+fn ensure_author(review: &ReviewDto, user: &AuthUser) -> Result<(), AppError> {
+    if user.is_staff || review.user == user.id {
+        Ok(())
+    } else {
+        Err(AppError::from((
+            ErrorCode::NotAuthor,
+            "Only the author can change this review",
+        )))
+    }
+}
@@ -1,7 +1,41 @@
 use actix_web::{web, Scope};
+use crate::endpoints::admin::create_admin_scope;
 use crate::endpoints::anime::title::create_anime_scope;
+use crate::endpoints::auth::create_auth_scope;
+use crate::endpoints::character::create_character_scope;
+use crate::endpoints::club::create_club_scope;
+use crate::endpoints::fan_work::create_fan_work_scope;
+use crate::endpoints::feed::create_feed_scope;
+use crate::endpoints::file::create_file_scope;
+use crate::endpoints::graphql::create_graphql_scope;
+use crate::endpoints::image::create_image_scope;
+use crate::endpoints::list::create_list_scope;
+use crate::endpoints::magazine::create_magazine_scope;
+use crate::endpoints::manga::title::create_manga_scope;
+use crate::endpoints::notification::create_notification_scope;
+use crate::endpoints::person::create_person_scope;
+use crate::endpoints::producer::create_producer_scope;
+use crate::endpoints::review::create_review_scope;
+use crate::endpoints::user::create_user_scope;
 
 pub fn create_app_scope() -> Scope {
     web::scope("/api")
+        .service(create_auth_scope())
         .service(create_anime_scope())
+        .service(create_manga_scope())
+        .service(create_character_scope())
+        .service(create_person_scope())
+        .service(create_producer_scope())
+        .service(create_magazine_scope())
+        .service(create_review_scope())
+        .service(create_list_scope())
+        .service(create_club_scope())
+        .service(create_fan_work_scope())
+        .service(create_file_scope())
+        .service(create_image_scope())
+        .service(create_user_scope())
+        .service(create_feed_scope())
+        .service(create_notification_scope())
+        .service(create_admin_scope())
+        .service(create_graphql_scope())
 }
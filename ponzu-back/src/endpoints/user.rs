@@ -0,0 +1,503 @@
+use crate::dto::activity::{ActivityDto, FeedQuery};
+use crate::dto::follow::FollowDto;
+use crate::dto::list_entry::ListEntryDto;
+use crate::dto::pagination::{CursorPage, Pagination, PaginationQuery};
+use crate::dto::profile::ProfileDto;
+use crate::dto::review::{ReviewQuery, ReviewViewDto};
+use crate::dto::user::{ContentSettingsDto, UpdateUserDto, UserDto};
+use crate::endpoints::list::ListFilter;
+use crate::endpoints::review::list_reviews;
+use crate::models::activity::ActivityKind;
+use crate::models::notification::NotificationKind;
+use crate::models::user::{PrivacySettings, Visibility};
+use crate::services::content_policy::{is_adult, ContentAccess};
+use crate::services::crud::CrudService;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use crate::types::links::Images;
+use crate::types::openapi::Binary;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::web::{scope, Bytes, Data, Json, Path, Query};
+use actix_web::{delete, get, post, put, Error, FromRequest, HttpResponse};
+use mongodb::bson::{doc, to_bson};
+use utoipa::OpenApi;
+
+pub fn create_user_scope() -> actix_web::Scope {
+    scope("/users")
+        .service(get_privacy_settings)
+        .service(update_privacy_settings)
+        .service(get_content_settings)
+        .service(update_content_settings)
+        .service(get_profile)
+        .service(get_user_list)
+        .service(get_user_reviews)
+        .service(get_followers)
+        .service(get_following)
+        .service(follow_user)
+        .service(unfollow_user)
+        .service(get_user_activity)
+        .service(upload_avatar)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_privacy_settings,
+    update_privacy_settings,
+    get_content_settings,
+    update_content_settings,
+    get_profile,
+    get_user_list,
+    get_user_reviews,
+    get_followers,
+    get_following,
+    follow_user,
+    unfollow_user,
+    get_user_activity,
+    upload_avatar
+))]
+pub struct UserApi;
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The privacy settings of the caller",
+            body = PrivacySettings
+        )
+    )
+)]
+#[get("me/privacy")]
+pub async fn get_privacy_settings(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user(&user.id, &data).await?;
+    Ok(HttpResponse::Ok().json(found.privacy))
+}
+
+/// Chooses who sees the list, reviews, birthday and activity of the caller.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The privacy settings were updated",
+            body = PrivacySettings
+        )
+    )
+)]
+#[put("me/privacy")]
+pub async fn update_privacy_settings(
+    user: AuthUser,
+    body: Json<PrivacySettings>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let update = UpdateUserDto {
+        privacy: Some(body.into_inner()),
+        ..Default::default()
+    };
+    let updated = data.user_service.update(&user.id, update, &user.id).await?;
+    Ok(HttpResponse::Ok().json(updated.privacy))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The content settings of the caller",
+            body = ContentSettingsDto
+        )
+    )
+)]
+#[get("me/content")]
+pub async fn get_content_settings(
+    user: AuthUser,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user(&user.id, &data).await?;
+    Ok(HttpResponse::Ok().json(ContentSettingsDto {
+        explicit_content: found.explicit_content,
+    }))
+}
+
+/// Chooses whether the caller is shown the explicit titles, which only adults may opt into.
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses(
+        (
+            status = 200,
+            description = "The content settings were updated",
+            body = ContentSettingsDto
+        )
+    )
+)]
+#[put("me/content")]
+pub async fn update_content_settings(
+    user: AuthUser,
+    body: Json<ContentSettingsDto>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let explicit_content = body.into_inner().explicit_content;
+    if explicit_content && !is_adult(find_user(&user.id, &data).await?.birth_date) {
+        return Err(AppError::from((
+            ErrorCode::ExplicitContentRestricted,
+            "Explicit content is restricted to adults",
+        )));
+    }
+    let update = UpdateUserDto {
+        explicit_content: Some(explicit_content),
+        ..Default::default()
+    };
+    let updated = data.user_service.update(&user.id, update, &user.id).await?;
+    Ok(HttpResponse::Ok().json(ContentSettingsDto {
+        explicit_content: updated.explicit_content,
+    }))
+}
+
+/// Reads the public profile of a user, with the statistics of their list.
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    responses((status = 200, description = "The profile of the user", body = ProfileDto))
+)]
+#[get("{username}")]
+pub async fn get_profile(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user_by_username(&path.into_inner(), &data).await?;
+    let show_birthday = can_see(found.privacy.birthday, &found, user.as_ref(), &data).await?;
+    let show_stats = can_see(found.privacy.list, &found, user.as_ref(), &data).await?;
+
+    let id = found.id.clone().unwrap_or_default();
+    let mut profile = ProfileDto::from(found);
+    if !show_birthday {
+        profile.birth_date = None;
+    }
+    if show_stats {
+        profile.stats = Some(data.profile_service.stats(&id).await?);
+    }
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery, ListFilter),
+    responses(
+        (
+            status = 200,
+            description = "A page of the list of the user",
+            body = Pagination<ListEntryDto>
+        )
+    )
+)]
+#[get("{username}/list")]
+pub async fn get_user_list(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    filter: Query<ListFilter>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user_by_username(&path.into_inner(), &data).await?;
+    ensure_visible(found.privacy.list, &found, user.as_ref(), &data).await?;
+    let mut criteria = doc! { "user": found.id.unwrap_or_default() };
+    if let Some(status) = filter.status {
+        criteria.insert(
+            "status",
+            to_bson(&status).map_err(|e| AppError::from(e.to_string()))?,
+        );
+    }
+    let page = data
+        .list_service
+        .get_paginated(Some(criteria), query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(PaginationQuery, ReviewQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the reviews of the user",
+            body = Pagination<ReviewViewDto>
+        )
+    )
+)]
+#[get("{username}/reviews")]
+pub async fn get_user_reviews(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    page: Query<PaginationQuery>,
+    query: Query<ReviewQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let found = find_user_by_username(&path.into_inner(), &data).await?;
+    ensure_visible(found.privacy.reviews, &found, user.as_ref(), &data).await?;
+    let mut query = query.into_inner();
+    query.user = found.id;
+    let reviews = list_reviews(user.as_ref(), &page, &query, &data).await?;
+    Ok(HttpResponse::Ok().json(reviews))
+}
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the followers of the user",
+            body = Pagination<FollowDto>
+        )
+    )
+)]
+#[get("{id}/followers")]
+pub async fn get_followers(
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_user(&id, &data).await?;
+    let followers = data
+        .follow_service
+        .followers(&id, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(followers))
+}
+
+#[utoipa::path(
+    params(PaginationQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the users followed by the user",
+            body = Pagination<FollowDto>
+        )
+    )
+)]
+#[get("{id}/following")]
+pub async fn get_following(
+    path: Path<String>,
+    query: Query<PaginationQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    find_user(&id, &data).await?;
+    let following = data
+        .follow_service
+        .following(&id, query.page(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(following))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 201, description = "The user was followed", body = FollowDto))
+)]
+#[post("{id}/follow")]
+pub async fn follow_user(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let follow = start_following(&user, &path.into_inner(), &data).await?;
+    Ok(HttpResponse::Created().json(follow))
+}
+
+#[utoipa::path(
+    security(("bearer" = [])),
+    responses((status = 204, description = "The user was unfollowed"))
+)]
+#[delete("{id}/follow")]
+pub async fn unfollow_user(
+    user: AuthUser,
+    path: Path<String>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    stop_following(&user, &path.into_inner(), &data).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Reads the activity of a user, newest first. Others only see the kinds the user publishes.
+#[utoipa::path(
+    security((), ("bearer" = [])),
+    params(FeedQuery),
+    responses(
+        (
+            status = 200,
+            description = "A page of the activity of the user",
+            body = CursorPage<ActivityDto>
+        )
+    )
+)]
+#[get("{id}/activity")]
+pub async fn get_user_activity(
+    user: Option<AuthUser>,
+    path: Path<String>,
+    query: Query<FeedQuery>,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    let found = find_user(&id, &data).await?;
+    ensure_visible(found.privacy.activity, &found, user.as_ref(), &data).await?;
+    let kinds = match user {
+        Some(user) if user.id == id || user.is_staff => ActivityKind::all(),
+        _ => found.published_activities,
+    };
+    let page = data
+        .activity_service
+        .stream(vec![(id, kinds)], query.before.as_deref(), query.limit())
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+/// Uploads the avatar of a user, sent as the raw request body. The previous avatar is removed.
+///
+/// Only the new images are answered, as staff members may upload for other users.
+#[utoipa::path(
+    security(("bearer" = [])),
+    request_body(content = Binary, content_type = "image/*"),
+    responses((status = 200, description = "The avatar was replaced", body = Images))
+)]
+#[put("{id}/avatar")]
+pub async fn upload_avatar(
+    user: AuthUser,
+    path: Path<String>,
+    body: Bytes,
+    data: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    if user.id != id {
+        user.ensure_staff()?;
+    }
+    let found = find_user(&id, &data).await?;
+    let images = data
+        .image_service
+        .upload(&format!("avatars/{}", id), body)
+        .await?;
+    let update = UpdateUserDto {
+        images: Some(images.clone()),
+        ..Default::default()
+    };
+    if let Err(e) = data.user_service.update(&id, update, &user.id).await {
+        data.image_service.remove(&images).await?;
+        return Err(e);
+    }
+    if let Some(previous) = &found.images {
+        data.image_service.remove(previous).await?;
+    }
+    Ok(HttpResponse::Ok().json(images))
+}
+
+/// Runs the request with the content access of the caller.
+///
+/// Anonymous callers are restricted. Staff members see everything, as they curate the catalog,
+/// and other users once they are adults who opted into explicit content.
+pub async fn resolve_content_access(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let user = AuthUser::extract(req.request()).await.ok();
+    let access = match (user, req.app_data::<Data<AppState>>()) {
+        (Some(user), _) if user.is_staff => ContentAccess::Unrestricted,
+        (Some(user), Some(data)) => match data.user_service.get_by_id(&user.id).await? {
+            Some(found) if found.explicit_content && is_adult(found.birth_date) => {
+                ContentAccess::Unrestricted
+            }
+            _ => ContentAccess::Restricted,
+        },
+        _ => ContentAccess::Restricted,
+    };
+    let res = access.scope(next.call(req)).await?;
+    Ok(res.map_into_boxed_body())
+}
+
+/// Makes the caller follow a user, and tells the followed user.
+pub async fn start_following(
+    user: &AuthUser,
+    id: &str,
+    data: &AppState,
+) -> Result<FollowDto, AppError> {
+    find_user(id, data).await?;
+    let follow = data.follow_service.follow(&user.id, id).await?;
+    data.notification_service
+        .notify(
+            id,
+            NotificationKind::NewFollower,
+            Some(&user.id),
+            &user.id,
+            None,
+        )
+        .await?;
+    Ok(follow)
+}
+
+/// Makes the caller stop following a user, or fails with a 404 if they did not follow them.
+pub async fn stop_following(user: &AuthUser, id: &str, data: &AppState) -> Result<(), AppError> {
+    match data.follow_service.unfollow(&user.id, id).await? {
+        true => Ok(()),
+        false => Err(AppError::NotFound(
+            "You do not follow this user".to_string(),
+        )),
+    }
+}
+
+/// Loads a user, or fails with a 404.
+pub async fn find_user(id: &str, data: &AppState) -> Result<UserDto, AppError> {
+    data.user_service
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+}
+
+/// Loads a user by username, or fails with a 404.
+async fn find_user_by_username(username: &str, data: &AppState) -> Result<UserDto, AppError> {
+    data.user_service
+        .find(Some(doc! { "username": username }), None)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+}
+
+/// Whether the caller sees a section of the profile of a user.
+async fn can_see(
+    visibility: Visibility,
+    owner: &UserDto,
+    viewer: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<bool, AppError> {
+    let owner_id = owner.id.as_deref().unwrap_or_default();
+    match (visibility, viewer) {
+        (_, Some(viewer)) if viewer.is_staff || viewer.id == owner_id => Ok(true),
+        (Visibility::Public, _) => Ok(true),
+        (Visibility::Followers, Some(viewer)) => {
+            data.follow_service.is_following(&viewer.id, owner_id).await
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Fails with a 403 when the caller may not see a section of the profile of a user.
+pub async fn ensure_visible(
+    visibility: Visibility,
+    owner: &UserDto,
+    viewer: Option<&AuthUser>,
+    data: &AppState,
+) -> Result<(), AppError> {
+    match can_see(visibility, owner, viewer, data).await? {
+        true => Ok(()),
+        false => Err(AppError::from((
+            ErrorCode::ProfilePrivate,
+            "This part of the profile is private",
+        ))),
+    }
+}
@@ -17,12 +17,12 @@ where
     // Get the unparsed value from the environment
     let unparsed = match default {
         Some(default) => std::env::var(key).unwrap_or(default.to_string()),
-        None => std::env::var(key).expect(&format!("{} environment variable not found", key)),
+        None => {
+            std::env::var(key).unwrap_or_else(|_| panic!("{} environment variable not found", key))
+        }
     };
     // Return the parsed value
-    unparsed.parse::<T>().expect(&format!(
-        "Failed to parse {} as {:?}",
-        key,
-        type_name::<T>()
-    ))
+    unparsed
+        .parse::<T>()
+        .unwrap_or_else(|_| panic!("Failed to parse {} as {:?}", key, type_name::<T>()))
 }
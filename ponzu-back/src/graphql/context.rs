@@ -0,0 +1,49 @@
+use crate::dto::pagination::PaginationQuery;
+use crate::graphql::loaders::StateLoader;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::auth::AuthUser;
+use crate::types::error_response::ErrorCode;
+use actix_web::web::Data;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::Context;
+
+/// The number of items a resolver listing credits or links is expected to return, when counting
+/// the complexity of a query.
+const LISTED_ITEMS: usize = 25;
+
+/// Reads the application state given to the request.
+pub fn state<'a>(ctx: &Context<'a>) -> &'a AppState {
+    ctx.data_unchecked::<Data<AppState>>()
+}
+
+/// Reads the caller, if authenticated.
+pub fn viewer<'a>(ctx: &Context<'a>) -> Option<&'a AuthUser> {
+    ctx.data_opt::<AuthUser>()
+}
+
+/// Reads the caller, or fails like the REST endpoints requiring a bearer token.
+pub fn signed_in<'a>(ctx: &Context<'a>) -> Result<&'a AuthUser, AppError> {
+    viewer(ctx).ok_or_else(|| AppError::from((ErrorCode::Unauthenticated, "Missing bearer token")))
+}
+
+/// Reads the loader of the request.
+pub fn loader<'a>(ctx: &Context<'a>) -> &'a DataLoader<StateLoader> {
+    ctx.data_unchecked::<DataLoader<StateLoader>>()
+}
+
+/// Builds the pagination of a listing from the arguments of its field.
+pub fn pagination(page: Option<u64>, limit: Option<u64>) -> PaginationQuery {
+    PaginationQuery { page, limit }
+}
+
+/// Counts the complexity of a paginated field, from the page size it asks for.
+pub fn paged(limit: Option<u64>, child: usize) -> usize {
+    let items = pagination(None, limit).limit() as usize;
+    items.saturating_mul(child).saturating_add(1)
+}
+
+/// Counts the complexity of a field listing credits or links, which is not paginated.
+pub fn listed(child: usize) -> usize {
+    LISTED_ITEMS.saturating_mul(child).saturating_add(1)
+}
@@ -0,0 +1,189 @@
+use crate::dto::anime::AnimeDto;
+use crate::dto::character::CharacterDto;
+use crate::dto::manga::MangaDto;
+use crate::dto::person::PersonDto;
+use crate::dto::user::UserDto;
+use crate::services::content_policy::ContentAccess;
+use crate::services::crud::CrudService;
+use crate::types::app_state::AppState;
+use actix_web::web::Data;
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::Error;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
+
+/// Generates the key of the documents of a service loaded by ID, and its loader.
+macro_rules! by_id {
+    ($(#[$meta:meta])* $key:ident, $service:ident, $dto:ty) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $key(pub String);
+
+        impl Loader<$key> for StateLoader {
+            type Value = $dto;
+            type Error = Error;
+
+            async fn load(&self, keys: &[$key]) -> Result<HashMap<$key, $dto>, Error> {
+                let ids = keys
+                    .iter()
+                    .filter_map(|key| ObjectId::parse_str(&key.0).ok())
+                    .collect::<Vec<_>>();
+                Ok(self
+                    .data
+                    .$service
+                    .find(Some(doc! { "_id": { "$in": ids } }), None)
+                    .await?
+                    .into_iter()
+                    .filter_map(|dto| Some(($key(dto.id.clone()?), dto)))
+                    .collect())
+            }
+        }
+    };
+}
+
+/// The kinds of title characters and people are credited on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TitleKind {
+    Anime,
+    Manga,
+}
+
+impl TitleKind {
+    /// Both kinds of title.
+    const ALL: [TitleKind; 2] = [TitleKind::Anime, TitleKind::Manga];
+
+    /// The name of the credits of this kind of title, on characters and people.
+    fn field(self) -> &'static str {
+        match self {
+            TitleKind::Anime => "anime",
+            TitleKind::Manga => "manga",
+        }
+    }
+}
+
+/// Loads the documents read by the GraphQL resolvers in batches, so that a field resolved for
+/// every item of a list costs one query instead of one per item.
+pub struct StateLoader {
+    data: Data<AppState>,
+}
+
+/// Creates the loader of a GraphQL request.
+///
+/// The batches run on their own tasks, which are given the content access of the request so
+/// that explicit titles stay hidden from restricted requests.
+pub fn request_loader(data: &Data<AppState>) -> DataLoader<StateLoader> {
+    let access = ContentAccess::current();
+    let loader = StateLoader { data: data.clone() };
+    DataLoader::new(loader, move |batch| tokio::spawn(access.scope(batch)))
+}
+
+by_id!(
+    /// The ID of a user.
+    UserId,
+    user_service,
+    UserDto
+);
+by_id!(
+    /// The ID of an anime.
+    AnimeId,
+    anime_service,
+    AnimeDto
+);
+by_id!(
+    /// The ID of a manga.
+    MangaId,
+    manga_service,
+    MangaDto
+);
+by_id!(
+    /// The ID of a character.
+    CharacterId,
+    character_service,
+    CharacterDto
+);
+by_id!(
+    /// The ID of a person.
+    PersonId,
+    people_service,
+    PersonDto
+);
+
+/// The ID of a title whose characters are loaded, each along with their role.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cast(pub TitleKind, pub String);
+
+impl Loader<Cast> for StateLoader {
+    type Value = Vec<(String, CharacterDto)>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[Cast]) -> Result<HashMap<Cast, Self::Value>, Error> {
+        let mut cast = HashMap::<Cast, Self::Value>::new();
+        for kind in TitleKind::ALL {
+            let titles = keys
+                .iter()
+                .filter(|key| key.0 == kind)
+                .map(|key| key.1.as_str())
+                .collect::<Vec<_>>();
+            if titles.is_empty() {
+                continue;
+            }
+            let filter = doc! { format!("{}.media", kind.field()): { "$in": &titles } };
+            for character in self.data.character_service.find(Some(filter), None).await? {
+                let credits = match kind {
+                    TitleKind::Anime => &character.anime,
+                    TitleKind::Manga => &character.manga,
+                };
+                for credit in credits
+                    .iter()
+                    .filter(|c| titles.contains(&c.media.as_str()))
+                {
+                    cast.entry(Cast(kind, credit.media.clone()))
+                        .or_default()
+                        .push((credit.role.clone(), character.clone()));
+                }
+            }
+        }
+        Ok(cast)
+    }
+}
+
+/// The ID of a title whose staff is loaded, each member along with their position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Staff(pub TitleKind, pub String);
+
+impl Loader<Staff> for StateLoader {
+    type Value = Vec<(String, PersonDto)>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[Staff]) -> Result<HashMap<Staff, Self::Value>, Error> {
+        let mut staff = HashMap::<Staff, Self::Value>::new();
+        for kind in TitleKind::ALL {
+            let titles = keys
+                .iter()
+                .filter(|key| key.0 == kind)
+                .map(|key| key.1.as_str())
+                .collect::<Vec<_>>();
+            if titles.is_empty() {
+                continue;
+            }
+            let filter = doc! { format!("{}.media", kind.field()): { "$in": &titles } };
+            for person in self.data.people_service.find(Some(filter), None).await? {
+                let credits = match kind {
+                    TitleKind::Anime => &person.anime,
+                    TitleKind::Manga => &person.manga,
+                };
+                for credit in credits
+                    .iter()
+                    .filter(|c| titles.contains(&c.media.as_str()))
+                {
+                    staff
+                        .entry(Staff(kind, credit.media.clone()))
+                        .or_default()
+                        .push((credit.position.clone(), person.clone()));
+                }
+            }
+        }
+        Ok(staff)
+    }
+}
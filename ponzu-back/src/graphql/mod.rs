@@ -0,0 +1,4 @@
+pub mod context;
+pub mod loaders;
+pub mod schema;
+pub mod types;
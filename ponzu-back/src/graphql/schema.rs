@@ -0,0 +1,214 @@
+use crate::dto::review::{FlagFilter, ReviewQuery};
+use crate::endpoints::club::{find_visible_club, list_clubs};
+use crate::endpoints::review::{list_reviews, react_to_review, view_review};
+use crate::endpoints::user::{find_user, start_following, stop_following};
+use crate::graphql::context::{paged, pagination, signed_in, state, viewer};
+use crate::graphql::types::{Anime, Character, Club, Manga, Page, Person, Review, User};
+use crate::models::reaction::ReactionType;
+use crate::services::crud::CrudService;
+use crate::types::app_config::AppConfig;
+use async_graphql::{Context, EmptySubscription, Object, Result, Schema, ID};
+
+/// The GraphQL schema served at `/api/graphql`.
+pub type PonzuSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Builds the GraphQL schema, limiting the depth and the complexity of the queries it runs.
+pub fn build_schema(config: &AppConfig) -> PonzuSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(config.graphql_max_depth)
+        .limit_complexity(config.graphql_max_complexity)
+        .finish()
+}
+
+/// Builds the filters of a review read alone.
+fn review_query(reveal_spoilers: bool) -> ReviewQuery {
+    ReviewQuery {
+        spoilers: reveal_spoilers.then_some(FlagFilter::Show),
+        ..Default::default()
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Reads an anime.
+    async fn anime(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Anime>> {
+        Ok(state(ctx).anime_service.get_by_id(&id).await?.map(Anime))
+    }
+
+    /// Reads a page of anime.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn anime_list(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Anime>> {
+        let page = pagination(page, limit);
+        let anime = state(ctx)
+            .anime_service
+            .get_paginated(None, page.page(), page.limit())
+            .await?;
+        Ok(Page::new(anime, Anime))
+    }
+
+    /// Reads a manga.
+    async fn manga(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Manga>> {
+        Ok(state(ctx).manga_service.get_by_id(&id).await?.map(Manga))
+    }
+
+    /// Reads a page of manga.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn manga_list(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Manga>> {
+        let page = pagination(page, limit);
+        let manga = state(ctx)
+            .manga_service
+            .get_paginated(None, page.page(), page.limit())
+            .await?;
+        Ok(Page::new(manga, Manga))
+    }
+
+    /// Reads a character.
+    async fn character(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Character>> {
+        Ok(state(ctx)
+            .character_service
+            .get_by_id(&id)
+            .await?
+            .map(Character))
+    }
+
+    /// Reads a page of characters.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn characters(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Character>> {
+        let page = pagination(page, limit);
+        let characters = state(ctx)
+            .character_service
+            .get_paginated(None, page.page(), page.limit())
+            .await?;
+        Ok(Page::new(characters, Character))
+    }
+
+    /// Reads a person.
+    async fn person(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Person>> {
+        Ok(state(ctx).people_service.get_by_id(&id).await?.map(Person))
+    }
+
+    /// Reads a page of people.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn people(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Person>> {
+        let page = pagination(page, limit);
+        let people = state(ctx)
+            .people_service
+            .get_paginated(None, page.page(), page.limit())
+            .await?;
+        Ok(Page::new(people, Person))
+    }
+
+    /// Reads a review. Its spoilers are redacted unless revealed or the caller completed the
+    /// reviewed entry.
+    async fn review(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        #[graphql(default)] reveal_spoilers: bool,
+    ) -> Result<Review> {
+        let query = review_query(reveal_spoilers);
+        let review = view_review(&id, viewer(ctx), &query, state(ctx)).await?;
+        Ok(Review(review))
+    }
+
+    /// Reads a page of reviews, optionally of a single anime or manga.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn reviews(
+        &self,
+        ctx: &Context<'_>,
+        entry: Option<ID>,
+        page: Option<u64>,
+        limit: Option<u64>,
+        #[graphql(default)] reveal_spoilers: bool,
+    ) -> Result<Page<Review>> {
+        let mut query = review_query(reveal_spoilers);
+        query.entry = entry.map(|e| e.0);
+        let page = pagination(page, limit);
+        let reviews = list_reviews(viewer(ctx), &page, &query, state(ctx)).await?;
+        Ok(Page::new(reviews, Review))
+    }
+
+    /// Reads a club. Secret clubs are only shown to their members and to staff.
+    async fn club(&self, ctx: &Context<'_>, id: ID) -> Result<Club> {
+        let club = find_visible_club(&id, viewer(ctx), state(ctx)).await?;
+        Ok(Club(club))
+    }
+
+    /// Reads a page of the clubs visible to the caller.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn clubs(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Club>> {
+        let page = pagination(page, limit);
+        let clubs = list_clubs(viewer(ctx), &page, state(ctx)).await?;
+        Ok(Page::new(clubs, Club))
+    }
+
+    /// Reads a user.
+    async fn user(&self, ctx: &Context<'_>, id: ID) -> Result<User> {
+        Ok(User(find_user(&id, state(ctx)).await?))
+    }
+
+    /// Reads the caller.
+    async fn me(&self, ctx: &Context<'_>) -> Result<User> {
+        let user = signed_in(ctx)?;
+        Ok(User(find_user(&user.id, state(ctx)).await?))
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Toggles a reaction of the caller to a review.
+    async fn react_to_review(
+        &self,
+        ctx: &Context<'_>,
+        review: ID,
+        reaction: ReactionType,
+    ) -> Result<Review> {
+        let user = signed_in(ctx)?;
+        react_to_review(user, &review, reaction, state(ctx)).await?;
+        let review = view_review(&review, Some(user), &review_query(false), state(ctx)).await?;
+        Ok(Review(review))
+    }
+
+    /// Makes the caller follow a user.
+    async fn follow_user(&self, ctx: &Context<'_>, user: ID) -> Result<User> {
+        let caller = signed_in(ctx)?;
+        start_following(caller, &user, state(ctx)).await?;
+        Ok(User(find_user(&user, state(ctx)).await?))
+    }
+
+    /// Makes the caller stop following a user.
+    async fn unfollow_user(&self, ctx: &Context<'_>, user: ID) -> Result<bool> {
+        let caller = signed_in(ctx)?;
+        stop_following(caller, &user, state(ctx)).await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,927 @@
+use crate::dto::anime::AnimeDto;
+use crate::dto::character::CharacterDto;
+use crate::dto::club::ClubDto;
+use crate::dto::manga::MangaDto;
+use crate::dto::pagination::Pagination;
+use crate::dto::person::PersonDto;
+use crate::dto::review::{FlagFilter, ReactionsDto, ReviewQuery, ReviewSegmentDto, ReviewViewDto};
+use crate::dto::user::UserDto;
+use crate::endpoints::club::list_linked_clubs;
+use crate::endpoints::review::list_reviews;
+use crate::endpoints::user::ensure_visible;
+use crate::graphql::context::{listed, loader, paged, pagination, state, viewer};
+use crate::graphql::loaders::{
+    AnimeId, Cast, CharacterId, MangaId, PersonId, Staff, StateLoader, TitleKind, UserId,
+};
+use crate::models::club::ClubAccess;
+use crate::models::reaction::ReactionType;
+use crate::models::review::ReviewStatus;
+use crate::types::links::Images;
+use async_graphql::dataloader::Loader;
+use async_graphql::{Context, Error, Object, OutputType, Result, SimpleObject, Union, ID};
+use mongodb::bson::DateTime;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A page of a listing.
+#[derive(SimpleObject)]
+#[graphql(concrete(name = "AnimePage", params(Anime)))]
+#[graphql(concrete(name = "MangaPage", params(Manga)))]
+#[graphql(concrete(name = "CharacterPage", params(Character)))]
+#[graphql(concrete(name = "PersonPage", params(Person)))]
+#[graphql(concrete(name = "ReviewPage", params(Review)))]
+#[graphql(concrete(name = "ClubPage", params(Club)))]
+#[graphql(concrete(name = "UserPage", params(User)))]
+pub struct Page<T: OutputType> {
+    pub current_page: u64,
+    pub last_page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    pub items: Vec<T>,
+}
+
+impl<T: OutputType> Page<T> {
+    /// Builds a page from a page of the REST API, wrapping each of its items.
+    pub fn new<D>(page: Pagination<D>, wrap: impl Fn(D) -> T) -> Self {
+        Self {
+            current_page: page.current_page,
+            last_page: page.last_page,
+            per_page: page.per_page,
+            total: page.total,
+            items: page.payload.into_iter().map(wrap).collect(),
+        }
+    }
+}
+
+/// The anime or the manga a review is about.
+#[derive(Union)]
+pub enum Entry {
+    Anime(Box<Anime>),
+    Manga(Box<Manga>),
+}
+
+/// A character of a title, along with their role.
+#[derive(SimpleObject)]
+pub struct CastCredit {
+    pub role: String,
+    pub character: Character,
+}
+
+/// A person who worked on a title, along with their position.
+#[derive(SimpleObject)]
+pub struct StaffCredit {
+    pub position: String,
+    pub person: Person,
+}
+
+/// An anime a character appears in or a person worked on.
+#[derive(SimpleObject)]
+pub struct AnimeCredit {
+    /// The role of the character, or the position of the person.
+    pub role: String,
+    pub anime: Anime,
+}
+
+/// A manga a character appears in or a person worked on.
+#[derive(SimpleObject)]
+pub struct MangaCredit {
+    /// The role of the character, or the position of the person.
+    pub role: String,
+    pub manga: Manga,
+}
+
+/// A person voicing a character.
+#[derive(SimpleObject)]
+pub struct VoiceCredit {
+    pub language: String,
+    pub person: Person,
+}
+
+/// A character voiced by a person, in an anime.
+#[derive(SimpleObject)]
+pub struct VoiceRole {
+    pub role: String,
+    pub anime: Option<Anime>,
+    pub character: Option<Character>,
+}
+
+/// Formats the ID of a document.
+fn id(id: &Option<String>) -> ID {
+    ID(id.clone().unwrap_or_default())
+}
+
+/// Formats a date as RFC 3339, the way the REST API serves it.
+fn rfc3339(date: &DateTime) -> String {
+    date.try_to_rfc3339_string().unwrap_or_default()
+}
+
+/// Loads documents by ID in batches, keyed by ID. Unknown IDs are left out.
+async fn load_all<'a, K>(
+    ctx: &Context<'_>,
+    ids: impl Iterator<Item = &'a String>,
+    key: fn(String) -> K,
+) -> Result<HashMap<K, <StateLoader as Loader<K>>::Value>>
+where
+    K: Send + Sync + Hash + Eq + Clone + 'static,
+    StateLoader: Loader<K, Error = Error>,
+{
+    loader(ctx).load_many(ids.cloned().map(key)).await
+}
+
+/// Lists a page of reviews.
+async fn reviews(
+    ctx: &Context<'_>,
+    mut query: ReviewQuery,
+    page: Option<u64>,
+    limit: Option<u64>,
+    reveal_spoilers: bool,
+) -> Result<Page<Review>> {
+    if reveal_spoilers {
+        query.spoilers = Some(FlagFilter::Show);
+    }
+    let page = pagination(page, limit);
+    let reviews = list_reviews(viewer(ctx), &page, &query, state(ctx)).await?;
+    Ok(Page::new(reviews, Review))
+}
+
+/// Lists a page of the clubs linked to a document.
+async fn clubs(
+    ctx: &Context<'_>,
+    field: &str,
+    id: &Option<String>,
+    page: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Page<Club>> {
+    let id = id.as_deref().unwrap_or_default();
+    let page = pagination(page, limit);
+    let clubs = list_linked_clubs(field, id, viewer(ctx), &page, state(ctx)).await?;
+    Ok(Page::new(clubs, Club))
+}
+
+/// An anime.
+pub struct Anime(pub AnimeDto);
+
+#[Object]
+impl Anime {
+    async fn id(&self) -> ID {
+        id(&self.0.id)
+    }
+
+    async fn mal_id(&self) -> u64 {
+        self.0.mal_id
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn title_english(&self) -> &str {
+        &self.0.title_english
+    }
+
+    async fn title_japanese(&self) -> &str {
+        &self.0.title_japanese
+    }
+
+    async fn title_synonyms(&self) -> &[String] {
+        &self.0.title_synonyms
+    }
+
+    async fn images(&self) -> &Images {
+        &self.0.images
+    }
+
+    /// The code of the type of the anime, such as `tv` or `movie`.
+    #[graphql(name = "type")]
+    async fn kind(&self) -> Option<&str> {
+        self.0.r#type.as_ref().map(|t| t.code())
+    }
+
+    /// The code of the airing status of the anime.
+    async fn status(&self) -> &str {
+        self.0.status.code()
+    }
+
+    /// The code of the age rating of the anime.
+    async fn rating(&self) -> Option<&str> {
+        self.0.rating.as_ref().map(|r| r.code())
+    }
+
+    async fn episodes(&self) -> Option<u32> {
+        self.0.episodes
+    }
+
+    async fn airing(&self) -> bool {
+        self.0.airing
+    }
+
+    async fn season(&self) -> &str {
+        &self.0.season
+    }
+
+    async fn year(&self) -> Option<u32> {
+        self.0.year
+    }
+
+    async fn synopsis(&self) -> &str {
+        &self.0.synopsis
+    }
+
+    async fn members(&self) -> u64 {
+        self.0.members
+    }
+
+    async fn favorites(&self) -> u64 {
+        self.0.favorites
+    }
+
+    async fn genres(&self) -> Vec<&str> {
+        self.0.genres.iter().map(|g| g.name.as_str()).collect()
+    }
+
+    /// The characters of the anime.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn characters(&self, ctx: &Context<'_>) -> Result<Vec<CastCredit>> {
+        cast(ctx, TitleKind::Anime, &self.0.id).await
+    }
+
+    /// The people who worked on the anime.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn staff(&self, ctx: &Context<'_>) -> Result<Vec<StaffCredit>> {
+        staff(ctx, TitleKind::Anime, &self.0.id).await
+    }
+
+    /// The reviews of the anime. Spoilers are redacted unless revealed.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn reviews(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+        #[graphql(default)] reveal_spoilers: bool,
+    ) -> Result<Page<Review>> {
+        let query = ReviewQuery {
+            entry: self.0.id.clone(),
+            ..Default::default()
+        };
+        reviews(ctx, query, page, limit, reveal_spoilers).await
+    }
+
+    /// The clubs about the anime, the largest first.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn clubs(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Club>> {
+        clubs(ctx, "anime", &self.0.id, page, limit).await
+    }
+}
+
+/// A manga.
+pub struct Manga(pub MangaDto);
+
+#[Object]
+impl Manga {
+    async fn id(&self) -> ID {
+        id(&self.0.id)
+    }
+
+    async fn mal_id(&self) -> u64 {
+        self.0.mal_id
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn title_english(&self) -> &str {
+        &self.0.title_english
+    }
+
+    async fn title_japanese(&self) -> &str {
+        &self.0.title_japanese
+    }
+
+    async fn title_synonyms(&self) -> &[String] {
+        &self.0.title_synonyms
+    }
+
+    async fn images(&self) -> &Images {
+        &self.0.images
+    }
+
+    /// The code of the type of the manga, such as `manga` or `light_novel`.
+    #[graphql(name = "type")]
+    async fn kind(&self) -> Option<&str> {
+        self.0.r#type.as_ref().map(|t| t.code())
+    }
+
+    /// The code of the publishing status of the manga.
+    async fn status(&self) -> &str {
+        self.0.status.code()
+    }
+
+    async fn chapters(&self) -> Option<u32> {
+        self.0.chapters
+    }
+
+    async fn volumes(&self) -> Option<u32> {
+        self.0.volumes
+    }
+
+    async fn publishing(&self) -> bool {
+        self.0.publishing
+    }
+
+    async fn synopsis(&self) -> &str {
+        &self.0.synopsis
+    }
+
+    async fn members(&self) -> u64 {
+        self.0.members
+    }
+
+    async fn favorites(&self) -> u64 {
+        self.0.favorites
+    }
+
+    async fn genres(&self) -> Vec<&str> {
+        self.0.genres.iter().map(|g| g.name.as_str()).collect()
+    }
+
+    /// The characters of the manga.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn characters(&self, ctx: &Context<'_>) -> Result<Vec<CastCredit>> {
+        cast(ctx, TitleKind::Manga, &self.0.id).await
+    }
+
+    /// The people who worked on the manga.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn staff(&self, ctx: &Context<'_>) -> Result<Vec<StaffCredit>> {
+        staff(ctx, TitleKind::Manga, &self.0.id).await
+    }
+
+    /// The reviews of the manga. Spoilers are redacted unless revealed.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn reviews(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+        #[graphql(default)] reveal_spoilers: bool,
+    ) -> Result<Page<Review>> {
+        let query = ReviewQuery {
+            entry: self.0.id.clone(),
+            ..Default::default()
+        };
+        reviews(ctx, query, page, limit, reveal_spoilers).await
+    }
+
+    /// The clubs about the manga, the largest first.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn clubs(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Club>> {
+        clubs(ctx, "manga", &self.0.id, page, limit).await
+    }
+}
+
+/// Loads the characters of a title.
+async fn cast(ctx: &Context<'_>, kind: TitleKind, id: &Option<String>) -> Result<Vec<CastCredit>> {
+    let Some(id) = id.clone() else {
+        return Ok(Vec::new());
+    };
+    let cast = loader(ctx).load_one(Cast(kind, id)).await?;
+    Ok(cast
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(role, character)| CastCredit {
+            role,
+            character: Character(character),
+        })
+        .collect())
+}
+
+/// Loads the people who worked on a title.
+async fn staff(
+    ctx: &Context<'_>,
+    kind: TitleKind,
+    id: &Option<String>,
+) -> Result<Vec<StaffCredit>> {
+    let Some(id) = id.clone() else {
+        return Ok(Vec::new());
+    };
+    let staff = loader(ctx).load_one(Staff(kind, id)).await?;
+    Ok(staff
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(position, person)| StaffCredit {
+            position,
+            person: Person(person),
+        })
+        .collect())
+}
+
+/// A character.
+pub struct Character(pub CharacterDto);
+
+#[Object]
+impl Character {
+    async fn id(&self) -> ID {
+        id(&self.0.id)
+    }
+
+    async fn mal_id(&self) -> u64 {
+        self.0.mal_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn name_kanji(&self) -> &str {
+        &self.0.name_kanji
+    }
+
+    async fn nicknames(&self) -> &[String] {
+        &self.0.nicknames
+    }
+
+    async fn images(&self) -> &Images {
+        &self.0.images
+    }
+
+    async fn about(&self) -> &str {
+        &self.0.about
+    }
+
+    async fn favorites(&self) -> u64 {
+        self.0.favorites
+    }
+
+    /// The anime the character appears in.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn anime(&self, ctx: &Context<'_>) -> Result<Vec<AnimeCredit>> {
+        let credits = &self.0.anime;
+        let anime = load_all(ctx, credits.iter().map(|c| &c.media), AnimeId).await?;
+        Ok(credits
+            .iter()
+            .filter_map(|c| {
+                let found = anime.get(&AnimeId(c.media.clone()))?;
+                Some(AnimeCredit {
+                    role: c.role.clone(),
+                    anime: Anime(found.clone()),
+                })
+            })
+            .collect())
+    }
+
+    /// The manga the character appears in.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn manga(&self, ctx: &Context<'_>) -> Result<Vec<MangaCredit>> {
+        let credits = &self.0.manga;
+        let manga = load_all(ctx, credits.iter().map(|c| &c.media), MangaId).await?;
+        Ok(credits
+            .iter()
+            .filter_map(|c| {
+                let found = manga.get(&MangaId(c.media.clone()))?;
+                Some(MangaCredit {
+                    role: c.role.clone(),
+                    manga: Manga(found.clone()),
+                })
+            })
+            .collect())
+    }
+
+    /// The people voicing the character.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn voices(&self, ctx: &Context<'_>) -> Result<Vec<VoiceCredit>> {
+        let voices = &self.0.voices;
+        let people = load_all(ctx, voices.iter().map(|v| &v.person), PersonId).await?;
+        Ok(voices
+            .iter()
+            .filter_map(|v| {
+                let person = people.get(&PersonId(v.person.clone()))?;
+                Some(VoiceCredit {
+                    language: v.language.clone(),
+                    person: Person(person.clone()),
+                })
+            })
+            .collect())
+    }
+
+    /// The clubs about the character, the largest first.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn clubs(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Club>> {
+        clubs(ctx, "characters", &self.0.id, page, limit).await
+    }
+}
+
+/// A person, such as a voice actor or a mangaka.
+pub struct Person(pub PersonDto);
+
+#[Object]
+impl Person {
+    async fn id(&self) -> ID {
+        id(&self.0.id)
+    }
+
+    async fn mal_id(&self) -> u64 {
+        self.0.mal_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn given_name(&self) -> &str {
+        &self.0.given_name
+    }
+
+    async fn family_name(&self) -> &str {
+        &self.0.family_name
+    }
+
+    async fn alternate_names(&self) -> &[String] {
+        &self.0.alternate_names
+    }
+
+    async fn images(&self) -> &Images {
+        &self.0.images
+    }
+
+    async fn website_url(&self) -> &str {
+        &self.0.website_url
+    }
+
+    async fn birthday(&self) -> String {
+        rfc3339(&self.0.birthday)
+    }
+
+    async fn about(&self) -> &str {
+        &self.0.about
+    }
+
+    async fn favorites(&self) -> u64 {
+        self.0.favorites
+    }
+
+    /// The anime the person worked on.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn anime(&self, ctx: &Context<'_>) -> Result<Vec<AnimeCredit>> {
+        let credits = &self.0.anime;
+        let anime = load_all(ctx, credits.iter().map(|c| &c.media), AnimeId).await?;
+        Ok(credits
+            .iter()
+            .filter_map(|c| {
+                let found = anime.get(&AnimeId(c.media.clone()))?;
+                Some(AnimeCredit {
+                    role: c.position.clone(),
+                    anime: Anime(found.clone()),
+                })
+            })
+            .collect())
+    }
+
+    /// The manga the person worked on.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn manga(&self, ctx: &Context<'_>) -> Result<Vec<MangaCredit>> {
+        let credits = &self.0.manga;
+        let manga = load_all(ctx, credits.iter().map(|c| &c.media), MangaId).await?;
+        Ok(credits
+            .iter()
+            .filter_map(|c| {
+                let found = manga.get(&MangaId(c.media.clone()))?;
+                Some(MangaCredit {
+                    role: c.position.clone(),
+                    manga: Manga(found.clone()),
+                })
+            })
+            .collect())
+    }
+
+    /// The characters the person voiced.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn voices(&self, ctx: &Context<'_>) -> Result<Vec<VoiceRole>> {
+        let voices = &self.0.voices;
+        let anime = load_all(ctx, voices.iter().map(|v| &v.anime), AnimeId).await?;
+        let characters = load_all(ctx, voices.iter().map(|v| &v.character), CharacterId).await?;
+        Ok(voices
+            .iter()
+            .map(|v| VoiceRole {
+                role: v.role.clone(),
+                anime: anime.get(&AnimeId(v.anime.clone())).cloned().map(Anime),
+                character: characters
+                    .get(&CharacterId(v.character.clone()))
+                    .cloned()
+                    .map(Character),
+            })
+            .collect())
+    }
+
+    /// The clubs about the person, the largest first.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn clubs(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<Club>> {
+        clubs(ctx, "people", &self.0.id, page, limit).await
+    }
+}
+
+/// A review, with its spoilers redacted when the caller may not read them.
+pub struct Review(pub ReviewViewDto);
+
+#[Object]
+impl Review {
+    async fn id(&self) -> ID {
+        id(&self.0.review.id)
+    }
+
+    /// Whether the review is about an `anime` or a `manga`.
+    #[graphql(name = "type")]
+    async fn kind(&self) -> &str {
+        &self.0.review.r#type
+    }
+
+    async fn score(&self) -> u8 {
+        self.0.review.score
+    }
+
+    /// The body of the review, with its spoilers replaced when redacted.
+    async fn review(&self) -> &str {
+        &self.0.review.review
+    }
+
+    async fn segments(&self) -> &[ReviewSegmentDto] {
+        &self.0.segments
+    }
+
+    async fn redacted(&self) -> bool {
+        self.0.redacted
+    }
+
+    async fn tags(&self) -> &[String] {
+        &self.0.review.tags
+    }
+
+    async fn is_spoiler(&self) -> bool {
+        self.0.review.is_spoiler
+    }
+
+    async fn is_preliminary(&self) -> bool {
+        self.0.review.is_preliminary
+    }
+
+    async fn episodes_watched(&self) -> Option<u64> {
+        self.0.review.episodes_watched
+    }
+
+    /// The fraction of the entry seen by the author of a preliminary review.
+    async fn progress(&self) -> Option<f64> {
+        self.0.progress
+    }
+
+    async fn status(&self) -> ReviewStatus {
+        self.0.review.status
+    }
+
+    async fn reactions(&self) -> &ReactionsDto {
+        &self.0.review.reactions
+    }
+
+    /// The reactions the caller gave the review, when read alone by an authenticated caller.
+    async fn my_reactions(&self) -> Option<&[ReactionType]> {
+        self.0.my_reactions.as_deref()
+    }
+
+    async fn comment_count(&self) -> u64 {
+        self.0.review.comment_count
+    }
+
+    async fn date(&self) -> String {
+        rfc3339(&self.0.review.date)
+    }
+
+    /// The reviewed anime or manga.
+    async fn entry(&self, ctx: &Context<'_>) -> Result<Option<Entry>> {
+        let id = self.0.review.entry.clone();
+        Ok(match self.0.review.r#type.as_str() {
+            "anime" => loader(ctx)
+                .load_one(AnimeId(id))
+                .await?
+                .map(|a| Entry::Anime(Box::new(Anime(a)))),
+            "manga" => loader(ctx)
+                .load_one(MangaId(id))
+                .await?
+                .map(|m| Entry::Manga(Box::new(Manga(m)))),
+            _ => None,
+        })
+    }
+
+    /// The author of the review.
+    async fn user(&self, ctx: &Context<'_>) -> Result<Option<User>> {
+        let user = loader(ctx)
+            .load_one(UserId(self.0.review.user.clone()))
+            .await?;
+        Ok(user.map(User))
+    }
+}
+
+/// The public part of the account of a user.
+pub struct User(pub UserDto);
+
+#[Object]
+impl User {
+    async fn id(&self) -> ID {
+        id(&self.0.id)
+    }
+
+    async fn username(&self) -> &str {
+        &self.0.username
+    }
+
+    async fn bio(&self) -> Option<&str> {
+        self.0.bio.as_deref()
+    }
+
+    async fn images(&self) -> Option<&Images> {
+        self.0.images.as_ref()
+    }
+
+    async fn follower_count(&self) -> u64 {
+        self.0.follower_count
+    }
+
+    async fn following_count(&self) -> u64 {
+        self.0.following_count
+    }
+
+    async fn created_at(&self) -> String {
+        rfc3339(&self.0.created_at)
+    }
+
+    /// The reviews of the user, unless their privacy settings hide them from the caller.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn reviews(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+        #[graphql(default)] reveal_spoilers: bool,
+    ) -> Result<Page<Review>> {
+        ensure_visible(self.0.privacy.reviews, &self.0, viewer(ctx), state(ctx)).await?;
+        let query = ReviewQuery {
+            user: self.0.id.clone(),
+            ..Default::default()
+        };
+        reviews(ctx, query, page, limit, reveal_spoilers).await
+    }
+
+    /// The users following the user, the latest first.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn followers(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<User>> {
+        let page = pagination(page, limit);
+        let id = self.0.id.as_deref().unwrap_or_default();
+        let follows = state(ctx)
+            .follow_service
+            .followers(id, page.page(), page.limit())
+            .await?;
+        let ids = follows.payload.iter().map(|f| &f.follower);
+        let users = load_all(ctx, ids, UserId).await?;
+        let users = follows
+            .payload
+            .iter()
+            .filter_map(|f| users.get(&UserId(f.follower.clone())).cloned())
+            .collect();
+        Ok(Page::new(follows.with_payload(users), User))
+    }
+
+    /// The users the user follows, the latest first.
+    #[graphql(complexity = "paged(limit, child_complexity)")]
+    async fn following(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Page<User>> {
+        let page = pagination(page, limit);
+        let id = self.0.id.as_deref().unwrap_or_default();
+        let follows = state(ctx)
+            .follow_service
+            .following(id, page.page(), page.limit())
+            .await?;
+        let ids = follows.payload.iter().map(|f| &f.followee);
+        let users = load_all(ctx, ids, UserId).await?;
+        let users = follows
+            .payload
+            .iter()
+            .filter_map(|f| users.get(&UserId(f.followee.clone())).cloned())
+            .collect();
+        Ok(Page::new(follows.with_payload(users), User))
+    }
+}
+
+/// A club of fans.
+pub struct Club(pub ClubDto);
+
+#[Object]
+impl Club {
+    async fn id(&self) -> ID {
+        id(&self.0.id)
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn access(&self) -> ClubAccess {
+        self.0.access
+    }
+
+    async fn category(&self) -> &str {
+        &self.0.category
+    }
+
+    async fn member_count(&self) -> u64 {
+        self.0.member_count
+    }
+
+    async fn created_at(&self) -> String {
+        rfc3339(&self.0.created_at)
+    }
+
+    /// The anime the club is about.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn anime(&self, ctx: &Context<'_>) -> Result<Vec<Anime>> {
+        let anime = load_all(ctx, self.0.anime.iter(), AnimeId).await?;
+        Ok(self
+            .0
+            .anime
+            .iter()
+            .filter_map(|id| anime.get(&AnimeId(id.clone())).cloned().map(Anime))
+            .collect())
+    }
+
+    /// The manga the club is about.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn manga(&self, ctx: &Context<'_>) -> Result<Vec<Manga>> {
+        let manga = load_all(ctx, self.0.manga.iter(), MangaId).await?;
+        Ok(self
+            .0
+            .manga
+            .iter()
+            .filter_map(|id| manga.get(&MangaId(id.clone())).cloned().map(Manga))
+            .collect())
+    }
+
+    /// The characters the club is about.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn characters(&self, ctx: &Context<'_>) -> Result<Vec<Character>> {
+        let characters = load_all(ctx, self.0.characters.iter(), CharacterId).await?;
+        Ok(self
+            .0
+            .characters
+            .iter()
+            .filter_map(|id| {
+                characters
+                    .get(&CharacterId(id.clone()))
+                    .cloned()
+                    .map(Character)
+            })
+            .collect())
+    }
+
+    /// The people the club is about.
+    #[graphql(complexity = "listed(child_complexity)")]
+    async fn people(&self, ctx: &Context<'_>) -> Result<Vec<Person>> {
+        let people = load_all(ctx, self.0.people.iter(), PersonId).await?;
+        Ok(self
+            .0
+            .people
+            .iter()
+            .filter_map(|id| people.get(&PersonId(id.clone())).cloned().map(Person))
+            .collect())
+    }
+}
@@ -1,21 +1,34 @@
 use crate::endpoints::default::default_responder;
+use crate::endpoints::docs::configure_docs;
+use crate::endpoints::health::{configure_health, drain_on_shutdown};
+use crate::endpoints::image::rewrite_mirrored_images;
 use crate::endpoints::scope::create_app_scope;
+use crate::endpoints::user::resolve_content_access;
 use crate::env::get_from_env;
-use actix_web::middleware::{Logger, NormalizePath, TrailingSlash};
+use actix_web::middleware::{from_fn, Logger, NormalizePath, TrailingSlash};
 use actix_web::{web, App, HttpServer};
+use colored::Colorize;
 use database::init_database;
+use graphql::schema::build_schema;
 use dotenv::dotenv;
+use std::time::Duration;
+use types::app_config::AppConfig;
 use types::app_state::AppState;
+use types::request_id::assign_request_id;
 
 mod database;
 mod dto;
 mod endpoints;
 mod env;
+mod graphql;
 mod models;
 mod services;
 mod types;
 mod utils;
 
+/// The default format of the logger, followed by the ID of the request.
+const LOG_FORMAT: &str = r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}i"#;
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Load the .env file
@@ -26,24 +39,95 @@ async fn main() -> std::io::Result<()> {
     let database: String = get_from_env("DATABASE_NAME", None);
     let port: u16 = get_from_env("PORT", Some("8080"));
     let workers_count: usize = get_from_env("WORKERS", Some("100"));
+    let config = AppConfig::from_env();
 
     // Initialize the app state
     let database = init_database(db_url, database)
         .await
         .expect("Failed to connect to the database");
-    let state = web::Data::new(AppState::new(database));
+    let schema = web::Data::new(build_schema(&config));
+    let state = web::Data::new(AppState::new(database, config));
+    let trashed = state
+        .ensure_indexes()
+        .await
+        .expect("Failed to create the database indexes");
+    for duplicate in trashed {
+        println!(
+            "{} {} {} moved to the trash, duplicating {} by {}",
+            "Deduplication:".yellow(),
+            duplicate.collection,
+            duplicate.id,
+            duplicate.kept,
+            duplicate.index
+        );
+    }
+
+    // Move the members still embedded in club documents into their own collection
+    state
+        .membership_service
+        .migrate_embedded_members()
+        .await
+        .expect("Failed to migrate the club members");
+
+    // Rewrite the Jikan strings still stored in anime and manga documents as typed values.
+    // The documents left untyped are still served, so a failure does not prevent the start.
+    if let Err(e) = state.typed_field_service.backfill().await {
+        eprintln!("{} the typed fields could not be backfilled: {}", "Backfill:".yellow(), e);
+    }
+
+    // Periodically reconcile the denormalized counters
+    if state.config.counter_reconcile_hours > 0 {
+        state
+            .counter_service
+            .clone()
+            .spawn_reconciliation(Duration::from_secs(
+                state.config.counter_reconcile_hours * 3600,
+            ));
+    }
+
+    // Deliver the notifications created by any server process to the streams open in this one
+    state
+        .notification_service
+        .clone()
+        .spawn_delivery(Duration::from_secs(state.config.notification_retry_seconds));
+
+    // Periodically mirror the external images referenced by the catalog
+    state
+        .image_mirror_service
+        .load()
+        .await
+        .expect("Failed to load the mirrored images");
+    if state.config.image_mirror_hours > 0 {
+        state
+            .image_mirror_service
+            .clone()
+            .spawn_mirroring(Duration::from_secs(state.config.image_mirror_hours * 3600));
+    }
 
     // Pass the app factory and boot the server
-    HttpServer::new(move || {
+    let upload_limit = web::PayloadConfig::new(state.config.upload_max_bytes);
+    let app_state = state.clone();
+    let server = HttpServer::new(move || {
         App::new()
             .wrap(NormalizePath::new(TrailingSlash::Trim))
-            .wrap(Logger::default())
-            .app_data(state.clone())
+            .wrap(from_fn(resolve_content_access))
+            .wrap(from_fn(rewrite_mirrored_images))
+            .wrap(Logger::new(LOG_FORMAT))
+            .wrap(from_fn(assign_request_id))
+            .app_data(app_state.clone())
+            .app_data(schema.clone())
+            .app_data(upload_limit.clone())
+            .configure(configure_docs)
+            .configure(configure_health)
             .service(create_app_scope())
             .default_service(web::route().to(default_responder))
     })
+    .disable_signals()
     .bind(("0.0.0.0", port))?
     .workers(workers_count)
-    .run()
-    .await
+    .run();
+
+    // Fail readiness on a shutdown signal, then stop once load balancers had time to notice
+    actix_web::rt::spawn(drain_on_shutdown(server.handle(), state));
+    server.await
 }
@@ -0,0 +1,63 @@
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Activity model, something a user did that their followers can see in their feed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Activity {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub user: String,
+    pub kind: ActivityKind,
+    /// The ID of the list entry, review, comment or club the activity is about.
+    pub target: String,
+    /// The new list status, or the reaction given.
+    pub detail: Option<String>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// The kinds of activity recorded, which users can publish or keep to themselves.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// An anime or manga added to the list, or its status changed.
+    ListUpdate,
+    /// A review written.
+    Review,
+    /// A reaction given to a review or a comment.
+    Reaction,
+    /// A club joined.
+    ClubJoin,
+}
+
+impl ActivityKind {
+    /// Every activity kind, all published by default.
+    pub const ALL: [ActivityKind; 4] = [
+        ActivityKind::ListUpdate,
+        ActivityKind::Review,
+        ActivityKind::Reaction,
+        ActivityKind::ClubJoin,
+    ];
+
+    /// Returns every activity kind.
+    pub fn all() -> Vec<ActivityKind> {
+        Self::ALL.to_vec()
+    }
+}
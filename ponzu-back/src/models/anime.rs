@@ -1,11 +1,17 @@
 use crate::models::genre::Genre;
 use crate::models::producer::Producer;
+use crate::types::jikan::{
+    deserialize_option_duration, AgeRating, AnimeSource, AnimeStatus, AnimeType, BroadcastDay,
+    EpisodeDuration,
+};
 use crate::types::links::{ExternalLink, Images, Trailer};
 use crate::types::title_meta::{MalEntity, Relation, Theme, Title};
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
 };
+use chrono::NaiveTime;
+use chrono_tz::Tz;
 use mongodb::bson::DateTime;
 use serde::{Deserialize, Serialize};
 
@@ -28,14 +34,16 @@ pub struct Anime {
     pub title_english: String,
     pub title_japanese: String,
     pub title_synonyms: Vec<String>,
-    pub r#type: String,
-    pub source: String,
+    pub r#type: Option<AnimeType>,
+    pub source: AnimeSource,
     pub episodes: Option<u32>,
-    pub status: String,
+    pub status: AnimeStatus,
     pub airing: bool,
     pub aired: Option<Aired>,
-    pub duration: String,
-    pub rating: String,
+    /// The length of an episode, in seconds.
+    #[serde(default, deserialize_with = "deserialize_option_duration")]
+    pub duration: Option<EpisodeDuration>,
+    pub rating: Option<AgeRating>,
     // pub score: f64,
     pub scored_by: u64,
     // pub rank: u64,
@@ -63,9 +71,11 @@ pub struct Anime {
 /// Broadcast information
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Broadcast {
-    pub day: String,
-    pub time: String,
-    pub timezone: String,
+    pub day: Option<BroadcastDay>,
+    /// The local time of the broadcast, in `timezone`.
+    pub time: Option<NaiveTime>,
+    /// The IANA name of the timezone, e.g. `Asia/Tokyo`.
+    pub timezone: Option<Tz>,
     pub string: String,
 }
 
@@ -0,0 +1,92 @@
+use crate::utils::bson::{
+    deserialize_option_bson_datetime_from_rfc3339_string,
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// Topic model, a discussion thread on the board of a club.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Topic {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub club: String,
+    pub user: String,
+    pub title: String,
+    /// Pinned topics are listed before the others.
+    pub pinned: bool,
+    /// Only officers can post in a locked topic.
+    pub locked: bool,
+    #[serde(default)]
+    pub post_count: u64,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub last_post_at: DateTime,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// Post model, a message in a topic.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Post {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub topic: String,
+    pub user: String,
+    pub body: String,
+    /// The IDs of the users mentioned with `@username`.
+    #[serde(default)]
+    pub mentions: Vec<String>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    #[serde(default)]
+    pub edit_count: u32,
+}
+
+/// Read marker model, when a member last read a topic.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TopicRead {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub topic: String,
+    pub user: String,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub read_at: DateTime,
+}
@@ -1,13 +1,17 @@
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
 };
+use async_graphql::Enum;
 use mongodb::bson::serde_helpers::{
     deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
 };
 use mongodb::bson::DateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Club model
+///
+/// The members live in the `club_members` collection, so large clubs don't bloat the document.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Club {
     #[serde(
@@ -19,9 +23,22 @@ pub struct Club {
     pub id: Option<String>,
     pub name: String,
     pub description: Option<String>,
-    pub members: Vec<String>,
-    pub access: String,
+    pub access: ClubAccess,
     pub category: String,
+    /// The anime the club is about.
+    #[serde(default)]
+    pub anime: Vec<String>,
+    /// The manga the club is about.
+    #[serde(default)]
+    pub manga: Vec<String>,
+    /// The characters the club is about.
+    #[serde(default)]
+    pub characters: Vec<String>,
+    /// The people the club is about.
+    #[serde(default)]
+    pub people: Vec<String>,
+    #[serde(default)]
+    pub member_count: u64,
     #[serde(
         serialize_with = "serialize_bson_datetime_as_rfc3339_string",
         deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
@@ -33,3 +50,113 @@ pub struct Club {
     )]
     pub updated_at: DateTime,
 }
+
+/// Who can find and join a club.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum ClubAccess {
+    /// Listed, and anyone can join.
+    Public,
+    /// Listed, but joining takes an approved request or an invitation.
+    Private,
+    /// Only visible to its members, and joining takes an invitation.
+    Secret,
+}
+
+/// The role of a member within a club.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClubRole {
+    Owner,
+    Officer,
+    Member,
+}
+
+impl ClubRole {
+    /// Returns the rank of the role. A member can only act on members of a lower rank.
+    pub fn rank(&self) -> u8 {
+        match self {
+            ClubRole::Owner => 2,
+            ClubRole::Officer => 1,
+            ClubRole::Member => 0,
+        }
+    }
+
+    /// Returns whether the role can manage the club: accept requests, invite, kick and ban.
+    pub fn manages(&self) -> bool {
+        self.rank() >= ClubRole::Officer.rank()
+    }
+}
+
+/// Club member model, the membership of a user in a club.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClubMember {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub club: String,
+    pub user: String,
+    pub role: ClubRole,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub joined_at: DateTime,
+}
+
+/// Club request model, a pending join request or invitation.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClubRequest {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub club: String,
+    pub user: String,
+    pub kind: ClubRequestKind,
+    /// The member who sent the invitation.
+    pub invited_by: Option<String>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// Whether a pending request comes from the user or from the club.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClubRequestKind {
+    /// The user asked to join, and waits for an officer.
+    Request,
+    /// An officer invited the user, who can join right away.
+    Invitation,
+}
+
+/// Club ban model, a user barred from a club.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClubBan {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub club: String,
+    pub user: String,
+    pub banned_by: String,
+    pub reason: Option<String>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
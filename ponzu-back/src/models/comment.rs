@@ -0,0 +1,50 @@
+use crate::models::review::Reactions;
+use crate::utils::bson::{
+    deserialize_option_bson_datetime_from_rfc3339_string,
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// Comment model, a message in the discussion thread of a review.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Comment {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub review: String,
+    /// The comment replied to, `None` for a top-level comment.
+    pub parent: Option<String>,
+    /// How deep the comment is nested, `0` for a top-level comment.
+    pub depth: u32,
+    pub user: String,
+    pub body: String,
+
+    #[serde(default)]
+    pub reactions: Reactions,
+    #[serde(default)]
+    pub reply_count: u64,
+
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    #[serde(default)]
+    pub edit_count: u32,
+}
@@ -0,0 +1,237 @@
+use crate::models::moderation::ReportReason;
+use crate::types::links::Images;
+use crate::utils::bson::{
+    deserialize_option_bson_datetime_from_rfc3339_string,
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Fan work model, a fanart or a fanfiction posted by a user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FanWork {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub user: String,
+    pub kind: FanWorkKind,
+    pub title: String,
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub rating: ContentRating,
+    #[serde(default)]
+    pub warnings: Vec<ContentWarning>,
+
+    #[serde(default)]
+    pub anime: Vec<String>,
+    #[serde(default)]
+    pub manga: Vec<String>,
+    #[serde(default)]
+    pub characters: Vec<String>,
+
+    /// The image of a fanart, once uploaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<Images>,
+    #[serde(default)]
+    pub chapter_count: u64,
+    /// The total number of words of the chapters of a fanfiction.
+    #[serde(default)]
+    pub word_count: u64,
+    #[serde(default)]
+    pub like_count: u64,
+
+    #[serde(default)]
+    pub status: FanWorkStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold_reason: Option<String>,
+
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    #[serde(default)]
+    pub edit_count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FanWorkKind {
+    Fanart,
+    Fanfiction,
+}
+
+/// The content level of a fan work, from suitable for everyone to adults only.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentRating {
+    General,
+    Teen,
+    Mature,
+    Explicit,
+}
+
+impl ContentRating {
+    /// Every content rating, from the lowest to the highest.
+    pub const ALL: [ContentRating; 4] = [
+        ContentRating::General,
+        ContentRating::Teen,
+        ContentRating::Mature,
+        ContentRating::Explicit,
+    ];
+}
+
+/// The content a fan work warns its readers about.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentWarning {
+    GraphicViolence,
+    MajorCharacterDeath,
+    SexualContent,
+    SelfHarm,
+    Abuse,
+}
+
+/// Whether a fan work is shown to other users.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FanWorkStatus {
+    #[default]
+    Published,
+    /// Waiting for a staff member to review it.
+    Held,
+    /// Hidden by a staff member.
+    Hidden,
+}
+
+/// Chapter model, a part of a fanfiction.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Chapter {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub work: String,
+    /// The position of the chapter in the work, from `1`.
+    pub number: u32,
+    pub title: Option<String>,
+    pub body: String,
+    pub word_count: u64,
+
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    #[serde(default)]
+    pub edit_count: u32,
+}
+
+/// Like model, a user liking a fan work.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FanWorkLike {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub work: String,
+    pub user: String,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// Report model, a complaint of a user about a fan work.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FanWorkReport {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub work: String,
+    pub user: String,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub weight: u64,
+    pub resolved: bool,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// Fan work action model, the log of what staff did to a fan work.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FanWorkAction {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub work: String,
+    /// The author of the fan work.
+    pub user: String,
+    pub actor: String,
+    pub action: FanWorkActionType,
+    /// The rating set by a `Rerate` action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<ContentRating>,
+    pub note: Option<String>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// What a staff member can do to a fan work. Every action resolves the open reports.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FanWorkActionType {
+    /// Shows the fan work again, releasing a hold.
+    Publish,
+    Hide,
+    Delete,
+    /// Warns the author, leaving the fan work as is.
+    Warn,
+    /// Changes the content rating of a fan work that was rated too low.
+    Rerate,
+}
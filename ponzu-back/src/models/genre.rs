@@ -1,10 +1,12 @@
+use crate::types::openapi::ObjectIdJson;
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Genre object with extra count field.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Genre {
     #[serde(
         rename = "_id",
@@ -12,6 +14,7 @@ pub struct Genre {
         serialize_with = "serialize_option_hex_string_as_object_id",
         deserialize_with = "deserialize_option_hex_string_from_object_id"
     )]
+    #[schema(value_type = Option<ObjectIdJson>)]
     pub id: Option<String>,
     pub mal_id: u64,
     pub r#type: String,
@@ -0,0 +1,35 @@
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// MirroredImage model, a local copy of an image hosted by the external origin.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MirroredImage {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    /// The SHA-256 of the original URL, in hexadecimal. The image is served under it.
+    pub hash: String,
+    /// The original URL.
+    pub url: String,
+    /// The storage key of the copy.
+    pub key: String,
+    pub content_type: String,
+    /// The SHA-256 of the content, in hexadecimal.
+    pub etag: String,
+    pub size: u64,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
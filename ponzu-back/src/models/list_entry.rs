@@ -0,0 +1,43 @@
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// List entry model, an anime or manga on the list of a user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListEntry {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub user: String,
+    pub entry: String,
+    pub r#type: String,
+    pub status: ListStatus,
+    pub progress: u32,
+    pub score: Option<u8>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub updated_at: DateTime,
+}
+
+/// Where a user stands with an entry of their list.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListStatus {
+    Current,
+    Completed,
+    OnHold,
+    Dropped,
+    Planned,
+}
@@ -1,4 +1,5 @@
 use crate::models::genre::Genre;
+use crate::types::jikan::{MangaStatus, MangaType};
 use crate::types::links::{ExternalLink, Images};
 use crate::types::title_meta::{MalEntity, Relation, Title};
 use crate::utils::bson::{
@@ -26,10 +27,10 @@ pub struct Manga {
     pub title_english: String,
     pub title_japanese: String,
     pub title_synonyms: Vec<String>,
-    pub r#type: String,
+    pub r#type: Option<MangaType>,
     pub chapters: Option<u32>,
     pub volumes: Option<u32>,
-    pub status: String,
+    pub status: MangaStatus,
     pub publishing: bool,
     pub published: Published,
     // pub score: f64,
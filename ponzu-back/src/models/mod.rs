@@ -1,11 +1,22 @@
 // Exposed modules
+pub mod activity;
 pub mod anime;
+pub mod board;
 pub mod character;
 pub mod club;
+pub mod comment;
+pub mod fan_work;
+pub mod follow;
 pub mod genre;
+pub mod image_mirror;
+pub mod list_entry;
 pub mod magazine;
 pub mod manga;
+pub mod moderation;
+pub mod notification;
 pub mod person;
 pub mod producer;
+pub mod reaction;
 pub mod review;
+pub mod revision;
 pub mod user;
@@ -0,0 +1,89 @@
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Report model, a complaint of a user about a review.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewReport {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub review: String,
+    pub user: String,
+    pub reason: ReportReason,
+    pub comment: Option<String>,
+    pub weight: u64,
+    pub resolved: bool,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// Why a review is reported.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    UntaggedSpoiler,
+    Other,
+}
+
+impl ReportReason {
+    /// Returns how much a report for this reason weighs in the moderation queue.
+    pub fn weight(&self) -> u64 {
+        match self {
+            ReportReason::Harassment => 3,
+            ReportReason::Spam => 2,
+            ReportReason::UntaggedSpoiler | ReportReason::Other => 1,
+        }
+    }
+}
+
+/// Moderation action model, the log of what staff did to a review.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModerationAction {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub review: String,
+    /// The author of the review.
+    pub user: String,
+    pub actor: String,
+    pub action: ModerationActionType,
+    pub note: Option<String>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// What a staff member can do to a review. Every action resolves the open reports.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationActionType {
+    /// Shows the review again, releasing a hold.
+    Publish,
+    Hide,
+    Delete,
+    /// Warns the author, leaving the review as is.
+    Warn,
+    MarkSpoiler,
+}
@@ -0,0 +1,85 @@
+use crate::utils::bson::{
+    deserialize_option_bson_datetime_from_rfc3339_string,
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Notification model, an event addressed to a single user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notification {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    /// The ID of the notified user.
+    pub user: String,
+    pub kind: NotificationKind,
+    /// The ID of the user who caused the notification, if any.
+    pub actor: Option<String>,
+    /// The ID of the user, review, comment, club or anime the notification is about.
+    pub target: String,
+    /// The reaction given, the number of episodes aired, or the note of a warning.
+    pub detail: Option<String>,
+    #[serde(
+        default,
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub read_at: Option<DateTime>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// The kinds of notification, which users can turn off one by one.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Someone followed the user. The target is the follower.
+    NewFollower,
+    /// Someone reacted to a review of the user.
+    ReviewReaction,
+    /// Someone replied to a comment of the user. The target is the reply.
+    CommentReply,
+    /// An officer invited the user to a club.
+    ClubInvitation,
+    /// A new episode of an anime the user is watching aired.
+    NewEpisode,
+    /// A staff member warned the user about a review. The detail is the note of the staff member.
+    Warning,
+}
+
+impl NotificationKind {
+    /// Every notification kind, all enabled by default.
+    pub const ALL: [NotificationKind; 6] = [
+        NotificationKind::NewFollower,
+        NotificationKind::ReviewReaction,
+        NotificationKind::CommentReply,
+        NotificationKind::ClubInvitation,
+        NotificationKind::NewEpisode,
+        NotificationKind::Warning,
+    ];
+
+    /// Returns every notification kind.
+    pub fn all() -> Vec<NotificationKind> {
+        Self::ALL.to_vec()
+    }
+
+    /// Returns whether users can turn this kind of notification off. Warnings always reach them.
+    pub fn optional(&self) -> bool {
+        *self != NotificationKind::Warning
+    }
+}
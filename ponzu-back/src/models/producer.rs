@@ -1,10 +1,24 @@
 use crate::types::links::{ExternalLink, Images};
+use crate::types::openapi::ObjectIdJson;
 use crate::types::title_meta::MalEntity;
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Commonly-used types for title metadata with type, name, and URL fields.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Producer {
+    #[serde(
+        rename = "_id",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    #[schema(value_type = Option<ObjectIdJson>)]
+    pub id: Option<String>,
     pub mal_id: u64,
     pub titles: Vec<MalEntity>,
     pub images: Option<Images>,
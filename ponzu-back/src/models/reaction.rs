@@ -0,0 +1,83 @@
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+};
+use async_graphql::Enum;
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Reaction model, a single reaction of a user to a review or a comment.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Reaction {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    /// The ID of the review or comment reacted to.
+    pub target: String,
+    pub user: String,
+    pub reaction: ReactionType,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// The kinds of reaction a review or a comment can receive, one per counter of `Reactions`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionType {
+    Nice,
+    LoveIt,
+    Funny,
+    Confusing,
+    Informative,
+    WellWritten,
+    Creative,
+}
+
+impl ReactionType {
+    /// Every reaction type.
+    pub const ALL: [ReactionType; 7] = [
+        ReactionType::Nice,
+        ReactionType::LoveIt,
+        ReactionType::Funny,
+        ReactionType::Confusing,
+        ReactionType::Informative,
+        ReactionType::WellWritten,
+        ReactionType::Creative,
+    ];
+
+    /// Returns the name of the `Reactions` field counting this reaction type.
+    pub fn field(&self) -> &'static str {
+        match self {
+            ReactionType::Nice => "nice",
+            ReactionType::LoveIt => "love_it",
+            ReactionType::Funny => "funny",
+            ReactionType::Confusing => "confusing",
+            ReactionType::Informative => "informative",
+            ReactionType::WellWritten => "well_written",
+            ReactionType::Creative => "creative",
+        }
+    }
+
+    /// Returns the path of the counter of this reaction type within a target document.
+    pub fn counter(&self) -> &'static str {
+        match self {
+            ReactionType::Nice => "reactions.nice",
+            ReactionType::LoveIt => "reactions.love_it",
+            ReactionType::Funny => "reactions.funny",
+            ReactionType::Confusing => "reactions.confusing",
+            ReactionType::Informative => "reactions.informative",
+            ReactionType::WellWritten => "reactions.well_written",
+            ReactionType::Creative => "reactions.creative",
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use crate::utils::bson::{
-    deserialize_option_hex_string_from_object_id, serialize_option_hex_string_as_object_id,
+    deserialize_option_bson_datetime_from_rfc3339_string,
+    deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
+    serialize_option_hex_string_as_object_id,
 };
+use async_graphql::Enum;
 use mongodb::bson::serde_helpers::{
     deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
 };
 use mongodb::bson::DateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Review model
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,6 +37,9 @@ pub struct Review {
     pub score: u8,
     pub tags: Vec<String>,
     pub is_spoiler: bool,
+    /// Set once the staff marked the review as a spoiler, which its author can no longer undo.
+    #[serde(default)]
+    pub spoiler_locked: bool,
     pub is_preliminary: bool,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -40,9 +47,45 @@ pub struct Review {
 
     pub entry: String,
     pub user: String,
+
+    #[serde(default)]
+    pub status: ReviewStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold_reason: Option<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: Option<DateTime>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_option_bson_datetime_from_rfc3339_string"
+    )]
+    pub edited_at: Option<DateTime>,
+    #[serde(default)]
+    pub edit_count: u32,
+    #[serde(default)]
+    pub comment_count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Whether a review is shown to readers.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema, Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    #[default]
+    Published,
+    /// Waiting for a staff member to review it.
+    Held,
+    /// Hidden by a staff member.
+    Hidden,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Reactions {
     pub overall: u64,
     pub nice: u64,
@@ -53,3 +96,16 @@ pub struct Reactions {
     pub well_written: u64,
     pub creative: u64,
 }
+
+impl Reactions {
+    /// Returns the sum of the individual reactions, which `overall` must always equal.
+    pub fn total(&self) -> u64 {
+        self.nice
+            + self.love_it
+            + self.funny
+            + self.confusing
+            + self.informative
+            + self.well_written
+            + self.creative
+    }
+}
@@ -0,0 +1,61 @@
+use crate::utils::bson::{
+    deserialize_option_hex_string_from_object_id, deserialize_present_bson,
+    serialize_option_hex_string_as_object_id,
+};
+use mongodb::bson::serde_helpers::{
+    deserialize_bson_datetime_from_rfc3339_string, serialize_bson_datetime_as_rfc3339_string,
+};
+use mongodb::bson::{Bson, DateTime};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Revision model, a single recorded edit of a catalogue document.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Revision {
+    #[serde(
+        rename = "_id",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_option_hex_string_as_object_id",
+        deserialize_with = "deserialize_option_hex_string_from_object_id"
+    )]
+    pub id: Option<String>,
+    pub kind: String,
+    pub document_id: String,
+    pub actor: String,
+    pub action: RevisionAction,
+    pub reverted_to: Option<String>,
+    pub changes: Vec<FieldChange>,
+    #[serde(
+        serialize_with = "serialize_bson_datetime_as_rfc3339_string",
+        deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
+    )]
+    pub created_at: DateTime,
+}
+
+/// The kind of edit a revision records.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionAction {
+    Update,
+    Revert,
+}
+
+/// A single top-level field changed by a revision.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    /// The value before the change, or `None` when the field was not set.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present_bson"
+    )]
+    pub before: Option<Bson>,
+    /// The value after the change, or `None` when the field was removed.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present_bson"
+    )]
+    pub after: Option<Bson>,
+}
@@ -1,3 +1,6 @@
+use crate::models::activity::ActivityKind;
+use crate::models::notification::NotificationKind;
+use crate::types::links::{deserialize_option_images, Images};
 use crate::utils::bson::{
     deserialize_option_hex_string_from_object_id, serialize_option_bson_datetime_as_rfc3339_string,
     serialize_option_hex_string_as_object_id,
@@ -7,6 +10,7 @@ use mongodb::bson::serde_helpers::{
 };
 use mongodb::bson::DateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// User model
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,13 +28,30 @@ pub struct User {
     pub is_active: bool,
     pub is_staff: bool,
     pub is_superuser: bool,
-    pub images: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_images")]
+    pub images: Option<Images>,
     pub bio: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "serialize_option_bson_datetime_as_rfc3339_string"
     )]
     pub birth_date: Option<DateTime>,
+    /// The activity kinds shown to followers.
+    #[serde(default = "ActivityKind::all")]
+    pub published_activities: Vec<ActivityKind>,
+    /// The notification kinds the user receives.
+    #[serde(default = "NotificationKind::all")]
+    pub enabled_notifications: Vec<NotificationKind>,
+    /// Who sees the sections of the profile.
+    #[serde(default)]
+    pub privacy: PrivacySettings,
+    /// Whether the user opted into explicit titles, honoured once they are adults.
+    #[serde(default)]
+    pub explicit_content: bool,
+    #[serde(default)]
+    pub follower_count: u64,
+    #[serde(default)]
+    pub following_count: u64,
     #[serde(
         serialize_with = "serialize_bson_datetime_as_rfc3339_string",
         deserialize_with = "deserialize_bson_datetime_from_rfc3339_string"
@@ -49,6 +70,7 @@ pub struct User {
 }
 
 impl User {
+    #[allow(dead_code, clippy::too_many_arguments)]
     pub fn new(
         username: String,
         email: String,
@@ -56,7 +78,7 @@ impl User {
         is_active: bool,
         is_staff: bool,
         is_superuser: bool,
-        images: Option<String>,
+        images: Option<Images>,
         bio: Option<String>,
         birth_date: Option<DateTime>,
     ) -> Self {
@@ -71,9 +93,55 @@ impl User {
             images,
             bio,
             birth_date,
+            published_activities: ActivityKind::all(),
+            enabled_notifications: NotificationKind::all(),
+            privacy: PrivacySettings::default(),
+            explicit_content: false,
+            follower_count: 0,
+            following_count: 0,
             created_at: DateTime::now(),
             updated_at: DateTime::now(),
             last_online: DateTime::now(),
         }
     }
 }
+
+/// Who sees the sections of a profile. The user and the staff always see them all.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PrivacySettings {
+    #[serde(default)]
+    pub list: Visibility,
+    #[serde(default)]
+    pub reviews: Visibility,
+    #[serde(default = "Visibility::private")]
+    pub birthday: Visibility,
+    #[serde(default)]
+    pub activity: Visibility,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            list: Visibility::Public,
+            reviews: Visibility::Public,
+            birthday: Visibility::Private,
+            activity: Visibility::Public,
+        }
+    }
+}
+
+/// Who sees a section of a profile.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Followers,
+    Private,
+}
+
+impl Visibility {
+    pub fn private() -> Self {
+        Visibility::Private
+    }
+}
@@ -0,0 +1,129 @@
+use crate::dto::activity::ActivityDto;
+use crate::dto::pagination::CursorPage;
+use crate::models::activity::{Activity, ActivityKind};
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::utils::bson::parse_object_id;
+use mongodb::bson::{doc, to_bson, Bson, DateTime};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use std::collections::BTreeMap;
+
+/// Records the activity of users and reads it back as feeds.
+///
+/// Feeds are assembled on read from the activity of the followed users, so following someone
+/// brings their past activity into the feed, and changing which kinds are published applies to
+/// past activity as well.
+pub struct ActivityService {
+    activities: DatabaseRepository<Activity>,
+}
+
+impl ActivityService {
+    /// Creates a new instance of the `ActivityService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `activities` collection.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            activities: DatabaseRepository::new(db.collection("activities")),
+        }
+    }
+
+    /// Creates the index reading the activity of users, newest first.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.activities
+            .ensure_index("user_id", doc! { "user": 1, "_id": -1 })
+            .await
+    }
+
+    /// Records an activity of a user.
+    ///
+    /// # Parameters
+    /// - `user`: The ID of the acting user.
+    /// - `kind`: What the user did.
+    /// - `target`: The ID of the list entry, review, comment or club acted upon.
+    /// - `detail`: The new list status, or the reaction given.
+    pub async fn record(
+        &self,
+        user: &str,
+        kind: ActivityKind,
+        target: &str,
+        detail: Option<String>,
+    ) -> Result<(), AppError> {
+        self.activities
+            .insert_one(Activity {
+                id: None,
+                user: user.to_string(),
+                kind,
+                target: target.to_string(),
+                detail,
+                created_at: DateTime::now(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Reads a slice of the activity of some users, newest first.
+    ///
+    /// # Parameters
+    /// - `authors`: The IDs of the users, each with the activity kinds they publish.
+    /// - `before`: The cursor returned with the previous slice.
+    /// - `limit`: The size of the slice.
+    pub async fn stream(
+        &self,
+        authors: Vec<(String, Vec<ActivityKind>)>,
+        before: Option<&str>,
+        limit: u64,
+    ) -> Result<CursorPage<ActivityDto>, AppError> {
+        // Users publishing the same kinds share a clause, which keeps the filter short
+        let mut groups = BTreeMap::<Vec<ActivityKind>, Vec<String>>::new();
+        for (user, mut kinds) in authors {
+            kinds.sort();
+            kinds.dedup();
+            if !kinds.is_empty() {
+                groups.entry(kinds).or_default().push(user);
+            }
+        }
+        if groups.is_empty() {
+            return Ok(CursorPage {
+                payload: Vec::new(),
+                next: None,
+            });
+        }
+        let clauses = groups
+            .into_iter()
+            .map(|(kinds, users)| {
+                Ok(doc! {
+                    "user": { "$in": users },
+                    "kind": { "$in": to_bson(&kinds).map_err(|e| AppError::from(e.to_string()))? },
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        let mut filter = doc! { "$or": clauses, "deleted_at": Bson::Null };
+        if let Some(before) = before {
+            filter.insert("_id", doc! { "$lt": parse_object_id(before)? });
+        }
+        // Read one more activity than requested to know whether a next slice exists
+        let options = FindOptions::builder()
+            .sort(doc! { "_id": -1 })
+            .limit((limit + 1) as i64)
+            .build();
+        let mut payload = self
+            .activities
+            .find(Some(filter), Some(options))
+            .await?
+            .into_iter()
+            .map(ActivityDto::from)
+            .collect::<Vec<_>>();
+
+        let next = match payload.len() as u64 > limit {
+            true => {
+                payload.truncate(limit as usize);
+                payload.last().and_then(|a| a.id.clone())
+            }
+            false => None,
+        };
+        Ok(CursorPage { payload, next })
+    }
+}
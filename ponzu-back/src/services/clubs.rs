@@ -0,0 +1,543 @@
+use crate::dto::club::{ClubBanDto, ClubDto, ClubMemberDto, ClubRequestDto, JoinClubDto};
+use crate::dto::pagination::Pagination;
+use crate::models::club::{
+    ClubAccess, ClubBan, ClubMember, ClubRequest, ClubRequestKind, ClubRole,
+};
+use crate::services::counters::CounterService;
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use mongodb::bson::{doc, to_bson, to_document, Bson, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Handles the members of clubs, their roles, join requests, invitations and bans.
+///
+/// Memberships live in their own collection, and `Club.member_count` is kept in step through
+/// the `CounterService`.
+pub struct MembershipService {
+    clubs: DatabaseRepository<Document>,
+    members: DatabaseRepository<ClubMember>,
+    requests: DatabaseRepository<ClubRequest>,
+    bans: DatabaseRepository<ClubBan>,
+    counters: Arc<CounterService>,
+}
+
+impl MembershipService {
+    /// Creates a new instance of the `MembershipService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the clubs and the membership collections.
+    /// - `counters`: The counter service maintaining `Club.member_count`.
+    pub fn new(db: &Database, counters: Arc<CounterService>) -> Self {
+        Self {
+            clubs: DatabaseRepository::new(db.collection("clubs")),
+            members: DatabaseRepository::new(db.collection("club_members")),
+            requests: DatabaseRepository::new(db.collection("club_requests")),
+            bans: DatabaseRepository::new(db.collection("club_bans")),
+            counters,
+        }
+    }
+
+    /// Creates the indexes allowing a single membership, request and ban per user and club.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        let keys = doc! { "club": 1, "user": 1 };
+        self.members
+            .ensure_live_unique_index("club_user", keys.clone())
+            .await?;
+        self.requests
+            .ensure_live_unique_index("club_user", keys.clone())
+            .await?;
+        self.bans.ensure_live_unique_index("club_user", keys).await
+    }
+
+    /// Moves the members embedded in club documents into the `club_members` collection.
+    ///
+    /// The first embedded member becomes the owner. Access values that are not a known
+    /// `ClubAccess` fall back to public.
+    ///
+    /// # Returns
+    /// The number of migrated clubs.
+    pub async fn migrate_embedded_members(&self) -> Result<u64, AppError> {
+        let clubs = self
+            .clubs
+            .find_documents(doc! { "members": { "$exists": true } }, None)
+            .await?;
+
+        for club in &clubs {
+            let Ok(id) = club.get_object_id("_id") else {
+                continue;
+            };
+            let club_id = id.to_hex();
+            let users = club
+                .get_array("members")
+                .map(|m| m.iter().filter_map(Bson::as_str).collect::<Vec<_>>())
+                .unwrap_or_default();
+            for (i, user) in users.into_iter().enumerate() {
+                let role = match i {
+                    0 => ClubRole::Owner,
+                    _ => ClubRole::Member,
+                };
+                match self.add(&club_id, user, role).await {
+                    Err(e) if e.is_duplicate_key() => {}
+                    result => {
+                        result?;
+                    }
+                }
+            }
+
+            let access = club.get_str("access").unwrap_or_default().to_lowercase();
+            let access = match access.as_str() {
+                "private" => ClubAccess::Private,
+                "secret" => ClubAccess::Secret,
+                _ => ClubAccess::Public,
+            };
+            self.clubs
+                .update_one(
+                    doc! { "_id": id },
+                    doc! {
+                        "$set": { "access": to_bson(&access).map_err(|e| AppError::from(e.to_string()))? },
+                        "$unset": { "members": "" },
+                    },
+                )
+                .await?;
+        }
+        Ok(clubs.len() as u64)
+    }
+
+    /// Returns the role of a user in a club, `None` if the user is not a member.
+    pub async fn role(&self, club: &str, user: &str) -> Result<Option<ClubRole>, AppError> {
+        Ok(self
+            .members
+            .find_one(live(doc! { "club": club, "user": user }))
+            .await?
+            .map(|m| m.role))
+    }
+
+    /// Returns the IDs of the clubs a user is a member of.
+    pub async fn clubs_of(&self, user: &str) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .members
+            .find(Some(live(doc! { "user": user })), None)
+            .await?
+            .into_iter()
+            .map(|m| m.club)
+            .collect())
+    }
+
+    /// Makes the creator of a new club its owner.
+    ///
+    /// # Parameters
+    /// - `club`: The ID of the club.
+    /// - `owner`: The ID of the creator.
+    pub async fn found(&self, club: &str, owner: &str) -> Result<ClubMemberDto, AppError> {
+        self.add(club, owner, ClubRole::Owner).await
+    }
+
+    /// Lets a user join a club, according to its access.
+    ///
+    /// A pending invitation is accepted. Otherwise public clubs are joined right away, private
+    /// clubs record a join request, and secret clubs cannot be joined.
+    ///
+    /// # Parameters
+    /// - `club`: The club.
+    /// - `user`: The ID of the joining user.
+    ///
+    /// # Returns
+    /// The new membership or the pending request, or an `AppError` with status 403 if the user is
+    /// banned, 404 for a secret club without invitation, or 409 if the user is already a member.
+    pub async fn join(&self, club: &ClubDto, user: &str) -> Result<JoinClubDto, AppError> {
+        let club_id = club.id.as_deref().unwrap_or_default();
+        if self.banned(club_id, user).await? {
+            return Err(AppError::from((
+                ErrorCode::ClubBanned,
+                "You are banned from this club",
+            )));
+        }
+        if self.role(club_id, user).await?.is_some() {
+            return Err(AppError::from((
+                ErrorCode::AlreadyMember,
+                "You are already a member of this club",
+            )));
+        }
+
+        if self
+            .take_request(club_id, user, ClubRequestKind::Invitation)
+            .await?
+        {
+            let member = self.add(club_id, user, ClubRole::Member).await?;
+            return Ok(JoinClubDto::Joined { member });
+        }
+        match club.access {
+            ClubAccess::Public => {
+                let member = self.add(club_id, user, ClubRole::Member).await?;
+                Ok(JoinClubDto::Joined { member })
+            }
+            ClubAccess::Private => {
+                let request = self
+                    .request(club_id, user, ClubRequestKind::Request, None)
+                    .await?;
+                Ok(JoinClubDto::Pending { request })
+            }
+            ClubAccess::Secret => Err(AppError::NotFound("Club not found".to_string())),
+        }
+    }
+
+    /// Removes a user from a club. The owner has to hand the club over first.
+    pub async fn leave(&self, club: &str, user: &str) -> Result<(), AppError> {
+        match self.role(club, user).await? {
+            None => Err(AppError::NotFound(
+                "You are not a member of this club".to_string(),
+            )),
+            Some(ClubRole::Owner) => Err(AppError::from((
+                ErrorCode::OwnerMustTransfer,
+                "The owner has to hand the club over before leaving",
+            ))),
+            Some(_) => self.remove(club, user).await,
+        }
+    }
+
+    /// Retrieves a page of the members of a club, by seniority.
+    pub async fn members(
+        &self,
+        club: &str,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<ClubMemberDto>, AppError> {
+        paginate(&self.members, live(doc! { "club": club }), page, limit).await
+    }
+
+    /// Retrieves a page of the pending join requests and invitations of a club.
+    pub async fn requests(
+        &self,
+        club: &str,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<ClubRequestDto>, AppError> {
+        paginate(&self.requests, live(doc! { "club": club }), page, limit).await
+    }
+
+    /// Retrieves a page of the users banned from a club.
+    pub async fn bans(
+        &self,
+        club: &str,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<ClubBanDto>, AppError> {
+        paginate(&self.bans, live(doc! { "club": club }), page, limit).await
+    }
+
+    /// Accepts the join request of a user.
+    pub async fn accept(&self, club: &str, user: &str) -> Result<ClubMemberDto, AppError> {
+        if !self
+            .take_request(club, user, ClubRequestKind::Request)
+            .await?
+        {
+            return Err(AppError::NotFound("Join request not found".to_string()));
+        }
+        self.add(club, user, ClubRole::Member).await
+    }
+
+    /// Declines a join request, or withdraws an invitation.
+    ///
+    /// # Returns
+    /// Whether a pending request existed.
+    pub async fn dismiss(&self, club: &str, user: &str) -> Result<bool, AppError> {
+        Ok(self
+            .requests
+            .delete_one(live(doc! { "club": club, "user": user }))
+            .await?
+            .deleted_count
+            > 0)
+    }
+
+    /// Invites a user to a club. A pending join request of the user is accepted instead.
+    ///
+    /// # Parameters
+    /// - `club`: The ID of the club.
+    /// - `user`: The ID of the invited user.
+    /// - `by`: The ID of the inviting member.
+    pub async fn invite(&self, club: &str, user: &str, by: &str) -> Result<JoinClubDto, AppError> {
+        if self.banned(club, user).await? {
+            return Err(AppError::from((
+                ErrorCode::MemberBanned,
+                "This user is banned from the club",
+            )));
+        }
+        if self.role(club, user).await?.is_some() {
+            return Err(AppError::from((
+                ErrorCode::AlreadyMember,
+                "This user is already a member of the club",
+            )));
+        }
+
+        if self
+            .take_request(club, user, ClubRequestKind::Request)
+            .await?
+        {
+            let member = self.add(club, user, ClubRole::Member).await?;
+            return Ok(JoinClubDto::Joined { member });
+        }
+        let request = self
+            .request(club, user, ClubRequestKind::Invitation, Some(by))
+            .await?;
+        Ok(JoinClubDto::Pending { request })
+    }
+
+    /// Removes a member from a club.
+    ///
+    /// # Parameters
+    /// - `club`: The ID of the club.
+    /// - `actor`: The role of the member acting.
+    /// - `user`: The ID of the removed member, who must rank lower than the actor.
+    pub async fn kick(&self, club: &str, actor: ClubRole, user: &str) -> Result<(), AppError> {
+        let role = self.member_role(club, user).await?;
+        ensure_outranks(actor, role)?;
+        self.remove(club, user).await
+    }
+
+    /// Bans a user from a club, removing their membership and pending requests.
+    ///
+    /// # Parameters
+    /// - `club`: The ID of the club.
+    /// - `actor`: The role of the member acting.
+    /// - `actor_id`: The ID of the member acting.
+    /// - `user`: The ID of the banned user, who must rank lower than the actor if a member.
+    /// - `reason`: Why the user is banned.
+    pub async fn ban(
+        &self,
+        club: &str,
+        actor: ClubRole,
+        actor_id: &str,
+        user: &str,
+        reason: Option<String>,
+    ) -> Result<ClubBanDto, AppError> {
+        if let Some(role) = self.role(club, user).await? {
+            ensure_outranks(actor, role)?;
+            self.remove(club, user).await?;
+        }
+        self.dismiss(club, user).await?;
+
+        let ban = self
+            .bans
+            .insert_one(ClubBan {
+                id: None,
+                club: club.to_string(),
+                user: user.to_string(),
+                banned_by: actor_id.to_string(),
+                reason,
+                created_at: DateTime::now(),
+            })
+            .await
+            .map_err(|e| match e.is_duplicate_key() {
+                true => AppError::from((
+                    ErrorCode::MemberBanned,
+                    "This user is already banned from the club",
+                )),
+                false => e,
+            })?;
+        Ok(ClubBanDto::from(ban))
+    }
+
+    /// Lifts the ban of a user.
+    ///
+    /// # Returns
+    /// Whether the user was banned.
+    pub async fn unban(&self, club: &str, user: &str) -> Result<bool, AppError> {
+        Ok(self
+            .bans
+            .delete_one(live(doc! { "club": club, "user": user }))
+            .await?
+            .deleted_count
+            > 0)
+    }
+
+    /// Changes the role of a member. Only the owner can change roles.
+    ///
+    /// Making a member the owner hands the club over, and the previous owner becomes an officer.
+    ///
+    /// # Parameters
+    /// - `club`: The ID of the club.
+    /// - `actor`: The role of the member acting.
+    /// - `user`: The ID of the member.
+    /// - `role`: The new role.
+    pub async fn set_role(
+        &self,
+        club: &str,
+        actor: ClubRole,
+        user: &str,
+        role: ClubRole,
+    ) -> Result<ClubMemberDto, AppError> {
+        if actor != ClubRole::Owner {
+            return Err(AppError::from((
+                ErrorCode::NotOwner,
+                "Only the owner can change roles",
+            )));
+        }
+        let current = self.member_role(club, user).await?;
+        if current == ClubRole::Owner && role != ClubRole::Owner {
+            return Err(AppError::from((
+                ErrorCode::OwnerMustTransfer,
+                "Hand the club over to another member first",
+            )));
+        }
+
+        if role == ClubRole::Owner {
+            self.members
+                .update_many(
+                    live(doc! { "club": club, "role": "owner" }),
+                    doc! { "$set": { "role": "officer" } },
+                )
+                .await?;
+        }
+        let member = self
+            .members
+            .update_one(
+                live(doc! { "club": club, "user": user }),
+                doc! { "$set": { "role": to_bson(&role).map_err(|e| AppError::from(e.to_string()))? } },
+            )
+            .await?;
+        Ok(ClubMemberDto::from(member))
+    }
+
+    /// Returns the role of a member, or fails with a 404 if the user is not a member.
+    async fn member_role(&self, club: &str, user: &str) -> Result<ClubRole, AppError> {
+        self.role(club, user)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Member not found".to_string()))
+    }
+
+    /// Returns whether a user is banned from a club.
+    async fn banned(&self, club: &str, user: &str) -> Result<bool, AppError> {
+        Ok(self
+            .bans
+            .count_documents(Some(live(doc! { "club": club, "user": user })))
+            .await?
+            > 0)
+    }
+
+    /// Records a pending request or invitation.
+    async fn request(
+        &self,
+        club: &str,
+        user: &str,
+        kind: ClubRequestKind,
+        invited_by: Option<&str>,
+    ) -> Result<ClubRequestDto, AppError> {
+        let request = self
+            .requests
+            .insert_one(ClubRequest {
+                id: None,
+                club: club.to_string(),
+                user: user.to_string(),
+                kind,
+                invited_by: invited_by.map(str::to_string),
+                created_at: DateTime::now(),
+            })
+            .await
+            .map_err(|e| match e.is_duplicate_key() {
+                true => AppError::from((
+                    ErrorCode::JoinRequestPending,
+                    "A request to join is already pending",
+                )),
+                false => e,
+            })?;
+        Ok(ClubRequestDto::from(request))
+    }
+
+    /// Deletes the pending request of the given kind.
+    ///
+    /// # Returns
+    /// Whether such a request existed.
+    async fn take_request(
+        &self,
+        club: &str,
+        user: &str,
+        kind: ClubRequestKind,
+    ) -> Result<bool, AppError> {
+        let kind = to_bson(&kind).map_err(|e| AppError::from(e.to_string()))?;
+        Ok(self
+            .requests
+            .delete_one(live(doc! { "club": club, "user": user, "kind": kind }))
+            .await?
+            .deleted_count
+            > 0)
+    }
+
+    /// Adds a member to a club.
+    async fn add(&self, club: &str, user: &str, role: ClubRole) -> Result<ClubMemberDto, AppError> {
+        let member = self
+            .members
+            .insert_one(ClubMember {
+                id: None,
+                club: club.to_string(),
+                user: user.to_string(),
+                role,
+                joined_at: DateTime::now(),
+            })
+            .await?;
+        let document = to_document(&member).map_err(|e| AppError::from(e.to_string()))?;
+        self.counters
+            .apply("club_members", None, Some(&document))
+            .await?;
+        Ok(ClubMemberDto::from(member))
+    }
+
+    /// Removes a member from a club.
+    async fn remove(&self, club: &str, user: &str) -> Result<(), AppError> {
+        let filter = live(doc! { "club": club, "user": user });
+        let Some(document) = self.members.find_one_document(filter.clone()).await? else {
+            return Ok(());
+        };
+        self.members.delete_one(filter).await?;
+        self.counters
+            .apply("club_members", Some(&document), None)
+            .await
+    }
+}
+
+/// Fails with a 403 unless the actor ranks higher than the member acted upon.
+fn ensure_outranks(actor: ClubRole, member: ClubRole) -> Result<(), AppError> {
+    match actor.rank() > member.rank() {
+        true => Ok(()),
+        false => Err(AppError::from((
+            ErrorCode::RankTooLow,
+            "You can only act on members of a lower rank",
+        ))),
+    }
+}
+
+/// Restricts a filter to the records that are not in the trash.
+fn live(mut filter: Document) -> Document {
+    filter.insert("deleted_at", Bson::Null);
+    filter
+}
+
+/// Reads a page of records, oldest first.
+async fn paginate<T, R>(
+    repository: &DatabaseRepository<T>,
+    filter: Document,
+    page: u64,
+    limit: u64,
+) -> Result<Pagination<R>, AppError>
+where
+    T: Send + Sync + DeserializeOwned + Serialize,
+    R: From<T>,
+{
+    let total = repository.count_documents(Some(filter.clone())).await?;
+    let options = FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .skip((page.saturating_sub(1)) * limit)
+        .limit(limit as i64)
+        .build();
+    let payload = repository
+        .find(Some(filter), Some(options))
+        .await?
+        .into_iter()
+        .map(R::from)
+        .collect();
+    Ok(Pagination::new(payload, page, limit, total))
+}
@@ -0,0 +1,167 @@
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::types::jikan::AgeRating;
+use chrono::{Months, Utc};
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use std::collections::HashSet;
+use std::future::Future;
+
+/// The age from which a user may opt into explicit content.
+pub const ADULT_AGE: u8 = 18;
+
+/// The collections holding titles, which are also the kinds used by the relations.
+const TITLE_COLLECTIONS: [&str; 2] = ["anime", "manga"];
+
+tokio::task_local! {
+    static ACCESS: ContentAccess;
+}
+
+/// What the current request may be shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentAccess {
+    /// Explicit titles are hidden, for anonymous users, minors and adults who did not opt in.
+    Restricted,
+    /// Everything is shown, for adults who opted in and for the staff.
+    Unrestricted,
+}
+
+impl ContentAccess {
+    /// The access of the current request.
+    ///
+    /// Work done outside of a request, such as the background jobs, is unrestricted.
+    pub fn current() -> Self {
+        ACCESS
+            .try_with(|access| *access)
+            .unwrap_or(ContentAccess::Unrestricted)
+    }
+
+    /// Runs a future with this access.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        ACCESS.scope(self, f).await
+    }
+}
+
+/// Whether a user born on the given date may opt into explicit content. Users without a birth
+/// date never may.
+pub fn is_adult(birth_date: Option<DateTime>) -> bool {
+    let Some(birth) = birth_date
+        .and_then(|d| chrono::DateTime::from_timestamp_millis(d.timestamp_millis()))
+        .map(|d| d.date_naive())
+    else {
+        return false;
+    };
+    birth
+        .checked_add_months(Months::new(12 * ADULT_AGE as u32))
+        .is_some_and(|adult| adult <= Utc::now().date_naive())
+}
+
+/// Hides the explicit titles, those rated for adults or carrying explicit genres, from the
+/// restricted requests.
+pub struct ContentPolicy {
+    db: Database,
+}
+
+impl ContentPolicy {
+    /// Creates a new instance of the `ContentPolicy`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `anime` and `manga` collections.
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+
+    /// The filter matching the explicit titles.
+    pub fn explicit() -> Document {
+        let ratings = AgeRating::ALL
+            .iter()
+            .filter(|r| r.min_age() >= ADULT_AGE)
+            .map(|r| r.code())
+            .collect::<Vec<_>>();
+        doc! {
+            "$or": [
+                { "rating": { "$in": ratings } },
+                { "explicit_genres.0": { "$exists": true } },
+            ]
+        }
+    }
+
+    /// The filter matching the titles shown to everyone.
+    pub fn safe() -> Document {
+        doc! { "$nor": [Self::explicit()] }
+    }
+
+    /// Removes the explicit titles from the relations of title documents, and the relations
+    /// left without any title.
+    ///
+    /// Relations only name the related titles, so the explicit ones are matched by title.
+    pub async fn hide_relations(&self, documents: &mut [Document]) -> Result<(), AppError> {
+        let mut hidden = HashSet::new();
+        for kind in TITLE_COLLECTIONS {
+            let names = documents
+                .iter()
+                .flat_map(|d| related(d, kind))
+                .collect::<HashSet<_>>();
+            if names.is_empty() {
+                continue;
+            }
+            let filter = doc! {
+                "$and": [
+                    { "title": { "$in": names.into_iter().collect::<Vec<_>>() } },
+                    Self::explicit(),
+                ]
+            };
+            let options = FindOptions::builder()
+                .projection(doc! { "title": 1 })
+                .build();
+            for title in DatabaseRepository::<Document>::new(self.db.collection(kind))
+                .find_documents(filter, Some(options))
+                .await?
+            {
+                if let Ok(name) = title.get_str("title") {
+                    hidden.insert((kind.to_string(), name.to_string()));
+                }
+            }
+        }
+        if hidden.is_empty() {
+            return Ok(());
+        }
+
+        for document in documents.iter_mut() {
+            let Ok(relations) = document.get_array_mut("relations") else {
+                continue;
+            };
+            relations.retain_mut(|relation| {
+                let Some(entries) = relation
+                    .as_document_mut()
+                    .and_then(|r| r.get_array_mut("entry").ok())
+                else {
+                    return true;
+                };
+                entries.retain(|entry| {
+                    let entry = entry.as_document();
+                    let kind = entry.and_then(|e| e.get_str("type").ok()).unwrap_or("");
+                    let name = entry.and_then(|e| e.get_str("name").ok()).unwrap_or("");
+                    !hidden.contains(&(kind.to_string(), name.to_string()))
+                });
+                !entries.is_empty()
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The names of the titles of a kind related to a title document.
+fn related<'a>(document: &'a Document, kind: &'a str) -> impl Iterator<Item = String> + 'a {
+    document
+        .get_array("relations")
+        .into_iter()
+        .flatten()
+        .filter_map(Bson::as_document)
+        .filter_map(|r| r.get_array("entry").ok())
+        .flatten()
+        .filter_map(Bson::as_document)
+        .filter(move |e| e.get_str("type").is_ok_and(|t| t == kind))
+        .filter_map(|e| e.get_str("name").ok().map(str::to_string))
+}
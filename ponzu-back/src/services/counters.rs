@@ -0,0 +1,325 @@
+use crate::dto::counters::CounterDriftDto;
+use crate::types::app_error::AppError;
+use colored::Colorize;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A denormalized counter field, counting the documents of a collection that are tagged with the
+/// counting document.
+///
+/// For instance, `genres.count` counts the anime whose `genres` array holds an entry with the
+/// genre's `mal_id`, and `reviews.comment_count` counts the comments whose `review` field holds
+/// the review's ID.
+#[derive(Debug, Clone)]
+pub struct Counter {
+    /// The collection holding the `count` field.
+    pub target: &'static str,
+    /// The field of the target documents identifying them in the tags.
+    pub target_key: &'static str,
+    /// A field and value the target documents must have for the counter to apply.
+    pub scope: Option<(&'static str, &'static str)>,
+    /// The counted collection.
+    pub source: &'static str,
+    /// A field and value the source documents must have to be counted.
+    pub source_scope: Option<(&'static str, &'static str)>,
+    /// The field of the source documents holding the tags, either an array or a single value.
+    pub field: &'static str,
+    /// The field of each tag matching `target_key`, or `None` when the tags are the keys themselves.
+    pub key: Option<&'static str>,
+    /// The field of the target documents holding the count.
+    pub count: &'static str,
+}
+
+/// Keeps the declared counters in step with the documents they count.
+pub struct CounterService {
+    db: Database,
+    counters: Vec<Counter>,
+}
+
+impl CounterService {
+    /// Creates a new instance of the `CounterService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database the collections live in.
+    /// - `counters`: The declared counters.
+    pub fn new(db: Database, counters: Vec<Counter>) -> Self {
+        Self { db, counters }
+    }
+
+    /// Returns whether the documents of `source` are counted by any counter.
+    pub fn counts(&self, source: &str) -> bool {
+        self.counters.iter().any(|c| c.source == source)
+    }
+
+    /// Adjusts the counters after a document of `source` changed.
+    ///
+    /// The tags only present `before` are decremented and the ones only present `after` are
+    /// incremented, so creations, deletions and re-tagging are all covered.
+    ///
+    /// # Parameters
+    /// - `source`: The collection of the changed document.
+    /// - `before`: The document before the change, `None` if it was created or restored.
+    /// - `after`: The document after the change, `None` if it was deleted.
+    pub async fn apply(
+        &self,
+        source: &str,
+        before: Option<&Document>,
+        after: Option<&Document>,
+    ) -> Result<(), AppError> {
+        for counter in self.counters.iter().filter(|c| c.source == source) {
+            let before = before.map(|d| tags(d, counter)).unwrap_or_default();
+            let after = after.map(|d| tags(d, counter)).unwrap_or_default();
+            let removed = before
+                .iter()
+                .filter(|k| !after.contains(k))
+                .cloned()
+                .collect::<Vec<_>>();
+            let added = after
+                .iter()
+                .filter(|k| !before.contains(k))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            self.increment(counter, removed, -1).await?;
+            self.increment(counter, added, 1).await?;
+        }
+        Ok(())
+    }
+
+    /// Recomputes every counter from the counted documents and reports the ones that drifted.
+    ///
+    /// # Parameters
+    /// - `fix`: Whether the drifted counters are overwritten with the recomputed value.
+    ///
+    /// # Returns
+    /// A `Vec` with an entry for each drifted counter.
+    pub async fn reconcile(&self, fix: bool) -> Result<Vec<CounterDriftDto>, AppError> {
+        let mut expected = Vec::with_capacity(self.counters.len());
+        for counter in &self.counters {
+            expected.push(self.recount(counter).await?);
+        }
+
+        let mut targets = self
+            .counters
+            .iter()
+            .map(|c| (c.target, c.count))
+            .collect::<Vec<_>>();
+        targets.sort();
+        targets.dedup();
+
+        let mut drift = Vec::new();
+        for (target, field) in targets {
+            let collection = self.db.collection::<Document>(target);
+            let documents = collection
+                .find(doc! { "deleted_at": null })
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            for document in documents {
+                let count = self
+                    .counters
+                    .iter()
+                    .zip(&expected)
+                    .filter(|(c, _)| {
+                        c.target == target && c.count == field && in_scope(&document, c)
+                    })
+                    .filter_map(|(c, counts)| {
+                        document
+                            .get(c.target_key)
+                            .and_then(key_of)
+                            .and_then(|k| counts.get(&k))
+                    })
+                    .sum::<u64>();
+                let stored = stored_count(&document, field);
+                if stored == count {
+                    continue;
+                }
+
+                if fix {
+                    collection
+                        .update_one(
+                            doc! { "_id": document.get("_id") },
+                            doc! { "$set": { field: count as i64 } },
+                        )
+                        .await?;
+                }
+                drift.push(CounterDriftDto::new(
+                    target, &document, field, stored, count,
+                ));
+            }
+        }
+        Ok(drift)
+    }
+
+    /// Starts a background job reconciling the counters at a fixed interval.
+    ///
+    /// Drifted counters are fixed and reported on the standard error output.
+    ///
+    /// # Parameters
+    /// - `every`: The interval between two runs. The first run happens right away.
+    pub fn spawn_reconciliation(self: Arc<Self>, every: Duration) {
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                match self.reconcile(true).await {
+                    Ok(drift) if drift.is_empty() => {}
+                    Ok(drift) => {
+                        eprintln!(
+                            "{} {} drifted counter(s) fixed",
+                            "Reconciliation:".yellow(),
+                            drift.len()
+                        );
+                        for d in drift {
+                            eprintln!(
+                                "  {}.{} of {}: stored {}, expected {}",
+                                d.collection,
+                                d.field,
+                                d.id.unwrap_or_default(),
+                                d.stored,
+                                d.expected
+                            );
+                        }
+                    }
+                    Err(e) => eprintln!("{} {}", "Reconciliation failed:".red(), e),
+                }
+            }
+        });
+    }
+
+    /// Counts the live source documents tagged with each key of a counter.
+    async fn recount(&self, counter: &Counter) -> Result<HashMap<String, u64>, AppError> {
+        let mut filter = doc! { "deleted_at": null };
+        if let Some((field, value)) = counter.source_scope {
+            filter.insert(field, value);
+        }
+        let pipeline = vec![
+            doc! { "$match": filter },
+            doc! { "$unwind": format!("${}", counter.field) },
+            // Group per document first, so a tag repeated within a document counts once
+            doc! { "$group": { "_id": {
+                "key": match counter.key {
+                    Some(key) => format!("${}.{}", counter.field, key),
+                    None => format!("${}", counter.field),
+                },
+                "document": "$_id",
+            } } },
+            doc! { "$group": { "_id": "$_id.key", "count": { "$sum": 1 } } },
+        ];
+
+        Ok(self
+            .db
+            .collection::<Document>(counter.source)
+            .aggregate(pipeline)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .iter()
+            .filter_map(|d| Some((key_of(d.get("_id")?)?, stored_count(d, "count"))))
+            .collect())
+    }
+
+    /// Increments the counter of the targets identified by `keys`. Counters never go below zero.
+    async fn increment(&self, counter: &Counter, keys: Vec<Bson>, by: i64) -> Result<(), AppError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        // References to other documents hold hex IDs, while `_id` holds an `ObjectId`
+        let keys = match counter.target_key {
+            "_id" => keys
+                .into_iter()
+                .map(
+                    |k| match k.as_str().and_then(|s| ObjectId::parse_str(s).ok()) {
+                        Some(oid) => Bson::ObjectId(oid),
+                        None => k,
+                    },
+                )
+                .collect(),
+            _ => keys,
+        };
+        let mut filter = doc! { counter.target_key: { "$in": keys }, "deleted_at": null };
+        if let Some((field, value)) = counter.scope {
+            filter.insert(field, value);
+        }
+        if by < 0 {
+            filter.insert(counter.count, doc! { "$gte": -by });
+        }
+
+        self.db
+            .collection::<Document>(counter.target)
+            .update_many(filter, doc! { "$inc": { counter.count: by } })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Returns the distinct tag keys a document holds for a counter.
+fn tags(document: &Document, counter: &Counter) -> Vec<Bson> {
+    if let Some((field, value)) = counter.source_scope {
+        if document.get_str(field) != Ok(value) {
+            return Vec::new();
+        }
+    }
+
+    let tags = match document.get(counter.field) {
+        Some(Bson::Array(tags)) => tags.iter().collect::<Vec<_>>(),
+        Some(Bson::Null) | None => Vec::new(),
+        Some(tag) => vec![tag],
+    };
+
+    let mut keys = Vec::new();
+    for tag in tags {
+        let key = match counter.key {
+            Some(key) => tag.as_document().and_then(|t| t.get(key)),
+            None => Some(tag),
+        };
+        if let Some(key) = key.filter(|k| !keys.contains(*k)) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+/// Returns whether a target document is in the scope of a counter.
+fn in_scope(document: &Document, counter: &Counter) -> bool {
+    match counter.scope {
+        Some((field, value)) => document.get_str(field) == Ok(value),
+        None => true,
+    }
+}
+
+/// Normalizes a tag key, so numeric keys match regardless of their integer width.
+fn key_of(value: &Bson) -> Option<String> {
+    match value {
+        Bson::String(s) => Some(s.clone()),
+        Bson::Int32(i) => Some(i.to_string()),
+        Bson::Int64(i) => Some(i.to_string()),
+        Bson::ObjectId(oid) => Some(oid.to_hex()),
+        _ => None,
+    }
+}
+
+/// Reads a numeric counter field, which may be a dotted path into embedded documents, treating
+/// missing or negative values as zero.
+fn stored_count(document: &Document, field: &str) -> u64 {
+    if let Some((parent, field)) = field.split_once('.') {
+        return document
+            .get_document(parent)
+            .map(|d| stored_count(d, field))
+            .unwrap_or(0);
+    }
+
+    match document.get(field) {
+        Some(Bson::Int32(i)) => (*i).max(0) as u64,
+        Some(Bson::Int64(i)) => (*i).max(0) as u64,
+        Some(Bson::Double(f)) => f.max(0.0) as u64,
+        _ => 0,
+    }
+}
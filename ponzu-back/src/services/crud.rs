@@ -1,12 +1,21 @@
 use crate::dto::pagination::Pagination;
-use crate::services::db_repo::DatabaseRepository;
+use crate::dto::revision::RevisionDto;
+use crate::dto::trash::TrashedDto;
+use crate::models::revision::{FieldChange, Revision, RevisionAction};
+use crate::services::content_policy::{ContentAccess, ContentPolicy};
+use crate::services::counters::CounterService;
+use crate::services::db_repo::{trash, untrash, DatabaseRepository};
+use crate::services::integrity::IntegrityService;
 use crate::types::app_error::AppError;
-use crate::utils::bson::get_object_id;
-use mongodb::bson::{doc, Document};
+use crate::types::error_response::ErrorCode;
+use crate::utils::bson::parse_object_id;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, from_document, to_document, Bson, DateTime, Document};
 use mongodb::options::{AggregateOptions, FindOptions, UpdateModifications};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// A service that provides CRUD operations for a given entity.
 ///
@@ -15,6 +24,7 @@ use std::sync::Arc;
 /// - `R`: The type of the entity's read operation (Read DTO).
 /// - `C`: The type of the entity's create operation (Create DTO).
 /// - `U`: The type of the entity's update operation (Update DTO).
+#[allow(dead_code)]
 pub trait CrudService<E, R, C, U>
 where
     E: Clone + Send + Sync + DeserializeOwned + Serialize,
@@ -109,31 +119,94 @@ where
 
     /// Updates an entity by its ID.
     ///
+    /// When the service tracks history, a revision with the changed fields is recorded.
+    ///
     /// # Parameters
     /// - `id`: The ID of the entity.
     /// - `update`: The update to apply.
+    /// - `actor`: The ID of the user making the change.
     ///
     /// # Returns
     /// The updated entity.
-    async fn update(&self, id: &str, update: U) -> Result<R, AppError>;
+    async fn update(&self, id: &str, update: U, actor: &str) -> Result<R, AppError>;
+
+    /// Retrieves the edit history of an entity, newest first.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the entity.
+    ///
+    /// # Returns
+    /// A `Vec` of revisions.
+    async fn history(&self, id: &str) -> Result<Vec<RevisionDto>, AppError>;
+
+    /// Reverts an entity to the state it had right after the given revision.
+    ///
+    /// The revert itself is recorded as a new revision.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the entity.
+    /// - `revision_id`: The ID of the revision to revert to.
+    /// - `actor`: The ID of the user making the change.
+    ///
+    /// # Returns
+    /// The reverted entity.
+    async fn revert(&self, id: &str, revision_id: &str, actor: &str) -> Result<R, AppError>;
 
     /// Deletes an entity by its ID.
     ///
+    /// In soft-delete mode the entity is moved to the trash instead of being removed.
+    ///
     /// # Parameters
     /// - `id`: The ID of the entity.
+    /// - `actor`: The ID of the user deleting the entity.
     ///
     /// # Returns
-    /// The deleted entity.
-    async fn delete(&self, id: &str) -> Result<bool, AppError>;
+    /// Whether an entity was deleted.
+    async fn delete(&self, id: &str, actor: &str) -> Result<bool, AppError>;
 
     /// Deletes entities by a criteria.
     ///
+    /// In soft-delete mode the entities are moved to the trash instead of being removed.
+    ///
     /// # Parameters
     /// - `criteria`: The criteria to apply.
+    /// - `actor`: The ID of the user deleting the entities.
     ///
     /// # Returns
     /// The number of deleted entities.
-    async fn delete_by_criteria(&self, criteria: Document) -> Result<u64, AppError>;
+    async fn delete_by_criteria(&self, criteria: Document, actor: &str) -> Result<u64, AppError>;
+
+    /// Retrieves the trashed entities, most recently deleted first.
+    ///
+    /// # Parameters
+    /// - `page`: The page number to read.
+    /// - `limit`: The number of entities to read per page.
+    ///
+    /// # Returns
+    /// A `Pagination` of trashed entities.
+    async fn get_trash(&self, page: u64, limit: u64)
+        -> Result<Pagination<TrashedDto<R>>, AppError>;
+
+    /// Restores a trashed entity.
+    ///
+    /// The restore is shallow: the documents trashed along with the entity stay in the trash.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the entity.
+    ///
+    /// # Returns
+    /// The restored entity, or an `AppError` with status 422 if it references a trashed
+    /// document, or 409 if a live entity holds its unique values.
+    async fn restore(&self, id: &str) -> Result<R, AppError>;
+
+    /// Permanently removes a trashed entity.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the entity.
+    ///
+    /// # Returns
+    /// Whether an entity was removed.
+    async fn purge(&self, id: &str) -> Result<bool, AppError>;
 }
 
 pub struct CrudServiceImpl<E, R, C, U>
@@ -144,9 +217,455 @@ where
     U: Clone + Into<UpdateModifications>,
 {
     repository: Arc<DatabaseRepository<E>>,
+    history: Option<History>,
+    soft_delete: bool,
+    integrity: Option<Arc<IntegrityService>>,
+    counters: Option<Arc<CounterService>>,
+    unique: Vec<UniqueKey>,
+    edit_tracking: bool,
+    content_policy: Option<Arc<ContentPolicy>>,
     _phantom: std::marker::PhantomData<(E, R, C, U)>,
 }
 
+/// The actor recorded on the entities moved to the trash for breaking a unique rule.
+const DEDUPLICATION_ACTOR: &str = "deduplication";
+
+/// A set of fields whose values must be unique among the entities of a `CrudServiceImpl`.
+struct UniqueKey {
+    name: &'static str,
+    fields: &'static [&'static str],
+    code: ErrorCode,
+    message: &'static str,
+}
+
+/// An entity moved to the trash by `ensure_unique_indexes`, as it duplicated another one.
+#[derive(Debug)]
+pub struct TrashedDuplicate {
+    /// The collection of the entity.
+    pub collection: String,
+    /// The ID of the trashed entity.
+    pub id: ObjectId,
+    /// The ID of the entity kept in its place.
+    pub kept: ObjectId,
+    /// The name of the unique index.
+    pub index: &'static str,
+}
+
+/// Revision tracking settings of a `CrudServiceImpl`.
+struct History {
+    kind: &'static str,
+    revisions: Arc<DatabaseRepository<Revision>>,
+}
+
+impl<E, R, C, U> CrudServiceImpl<E, R, C, U>
+where
+    E: Clone + Send + Sync + DeserializeOwned + Serialize + 'static,
+    R: Clone + From<E>,
+    C: Clone + Into<E>,
+    U: Clone + Into<UpdateModifications>,
+{
+    /// Enables revision tracking for the updates made through this service.
+    ///
+    /// # Parameters
+    /// - `kind`: The kind of entity the revisions belong to, e.g. `anime`.
+    /// - `revisions`: The repository the revisions are stored in.
+    pub fn with_history(
+        mut self,
+        kind: &'static str,
+        revisions: Arc<DatabaseRepository<Revision>>,
+    ) -> Self {
+        self.history = Some(History { kind, revisions });
+        self
+    }
+
+    /// Enables soft-delete mode, in which deleted entities are moved to the trash.
+    pub fn with_soft_delete(mut self) -> Self {
+        self.soft_delete = true;
+        self
+    }
+
+    /// Enforces the declared references on the writes and deletions made through this service.
+    ///
+    /// # Parameters
+    /// - `integrity`: The service holding the declared references.
+    pub fn with_integrity(mut self, integrity: Arc<IntegrityService>) -> Self {
+        self.integrity = Some(integrity);
+        self
+    }
+
+    /// Keeps the declared counters in step with the entities created, edited and deleted
+    /// through this service.
+    ///
+    /// # Parameters
+    /// - `counters`: The service holding the declared counters.
+    pub fn with_counters(mut self, counters: Arc<CounterService>) -> Self {
+        self.counters = Some(counters);
+        self
+    }
+
+    /// Requires the values of the given fields to be unique among the entities that are not in the trash.
+    ///
+    /// The rule is checked on create and update, and backed by a unique index created by
+    /// `ensure_unique_indexes`.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the index.
+    /// - `fields`: The fields whose combined values must be unique.
+    /// - `code`: The code of the 409 error returned on violation.
+    /// - `message`: The message of that error.
+    pub fn with_unique(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        code: ErrorCode,
+        message: &'static str,
+    ) -> Self {
+        self.unique.push(UniqueKey {
+            name,
+            fields,
+            code,
+            message,
+        });
+        self
+    }
+
+    /// Stamps `edited_at` and increments `edit_count` on the entities whenever an update changes them.
+    pub fn with_edit_tracking(mut self) -> Self {
+        self.edit_tracking = true;
+        self
+    }
+
+    /// Hides the explicit entities, and the explicit titles of their relations, from the reads
+    /// made on behalf of restricted requests.
+    ///
+    /// # Parameters
+    /// - `policy`: The policy matching the explicit titles.
+    pub fn with_content_policy(mut self, policy: Arc<ContentPolicy>) -> Self {
+        self.content_policy = Some(policy);
+        self
+    }
+
+    /// Ensures trashed entities are purged once the retention period has passed.
+    ///
+    /// # Parameters
+    /// - `retention`: How long an entity stays in the trash.
+    pub async fn ensure_trash_retention(&self, retention: Duration) -> Result<(), AppError> {
+        if !self.soft_delete {
+            return Ok(());
+        }
+        self.repository
+            .ensure_ttl_index("deleted_at", retention)
+            .await
+    }
+
+    /// Creates the unique indexes backing the rules declared with `with_unique`.
+    ///
+    /// In soft-delete mode the rules only apply to the entities that are not in the trash, and
+    /// the duplicates written before a rule existed are moved to the trash before its index is
+    /// first built.
+    ///
+    /// # Returns
+    /// The entities moved to the trash, which the staff can restore in place of the kept ones.
+    pub async fn ensure_unique_indexes(&self) -> Result<Vec<TrashedDuplicate>, AppError> {
+        let mut trashed = Vec::new();
+        for key in &self.unique {
+            let keys = key
+                .fields
+                .iter()
+                .map(|field| (field.to_string(), Bson::Int32(1)))
+                .collect::<Document>();
+            if !self.soft_delete {
+                self.repository.ensure_unique_index(key.name, keys).await?;
+                continue;
+            }
+
+            if !self.repository.has_index(key.name).await? {
+                trashed.extend(self.trash_duplicates(key).await?);
+            }
+            self.repository
+                .ensure_live_unique_index(key.name, keys)
+                .await?;
+        }
+        Ok(trashed)
+    }
+
+    /// Moves to the trash the entities holding the same values for a unique rule, keeping the
+    /// newest of each group.
+    ///
+    /// # Returns
+    /// The entities moved to the trash.
+    async fn trash_duplicates(&self, key: &UniqueKey) -> Result<Vec<TrashedDuplicate>, AppError> {
+        let values = key
+            .fields
+            .iter()
+            .map(|field| (field.to_string(), Bson::String(format!("${}", field))))
+            .collect::<Document>();
+        let pipeline = vec![
+            doc! { "$match": self.live(None) },
+            doc! { "$sort": { "_id": -1 } },
+            doc! { "$group": { "_id": values, "ids": { "$push": "$_id" } } },
+            doc! { "$match": { "ids.1": { "$exists": true } } },
+        ];
+
+        let mut trashed = Vec::new();
+        for group in self.repository.aggregate(pipeline, None).await? {
+            let ids = group
+                .get_array("ids")
+                .map_err(|e| AppError::from(e.to_string()))?
+                .iter()
+                .filter_map(Bson::as_object_id)
+                .collect::<Vec<_>>();
+            let Some((&kept, duplicates)) = ids.split_first() else {
+                continue;
+            };
+            for &id in duplicates {
+                if self.delete(&id.to_hex(), DEDUPLICATION_ACTOR).await? {
+                    trashed.push(TrashedDuplicate {
+                        collection: self.repository.name().to_string(),
+                        id,
+                        kept,
+                        index: key.name,
+                    });
+                }
+            }
+        }
+        Ok(trashed)
+    }
+
+    /// Checks that no other entity holds the same values as a document for a unique rule.
+    ///
+    /// # Parameters
+    /// - `document`: The document being written.
+    /// - `exclude`: The ID of the entity being updated, if any.
+    async fn check_unique(
+        &self,
+        document: &Document,
+        exclude: Option<ObjectId>,
+    ) -> Result<(), AppError> {
+        for key in &self.unique {
+            let mut filter = key
+                .fields
+                .iter()
+                .map(|field| {
+                    let value = document.get(*field).cloned().unwrap_or(Bson::Null);
+                    (field.to_string(), value)
+                })
+                .collect::<Document>();
+            if let Some(oid) = exclude {
+                filter.insert("_id", doc! { "$ne": oid });
+            }
+            let taken = self
+                .repository
+                .count_documents(Some(self.live(Some(filter))))
+                .await?;
+            if taken > 0 {
+                return Err(AppError::from((key.code, key.message)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Turns the duplicate key error of a unique index into the 409 of its rule.
+    fn unique_violation(&self, error: AppError) -> AppError {
+        match (error.is_duplicate_key(), self.unique.first()) {
+            (true, Some(key)) => AppError::from((key.code, key.message)),
+            _ => error,
+        }
+    }
+
+    /// Restricts a filter to the entities that are not in the trash.
+    fn live(&self, filter: Option<Document>) -> Document {
+        let filter = filter.unwrap_or_default();
+        match (self.soft_delete, filter.is_empty()) {
+            (false, _) => filter,
+            (true, true) => doc! { "deleted_at": null },
+            (true, false) => doc! { "$and": [filter, { "deleted_at": null }] },
+        }
+    }
+
+    /// Returns the content policy to enforce on the current request, if it is restricted.
+    fn restriction(&self) -> Option<&ContentPolicy> {
+        self.content_policy
+            .as_deref()
+            .filter(|_| ContentAccess::current() == ContentAccess::Restricted)
+    }
+
+    /// Restricts a filter to the entities that are not in the trash and that the current request
+    /// may see.
+    fn visible(&self, filter: Option<Document>) -> Document {
+        let filter = self.live(filter);
+        match (self.restriction(), filter.is_empty()) {
+            (None, _) => filter,
+            (Some(_), true) => ContentPolicy::safe(),
+            (Some(_), false) => doc! { "$and": [filter, ContentPolicy::safe()] },
+        }
+    }
+
+    /// Reads the entities the current request may see.
+    async fn read(
+        &self,
+        filter: Option<Document>,
+        options: Option<FindOptions>,
+    ) -> Result<Vec<E>, AppError> {
+        let filter = self.visible(filter);
+        let Some(policy) = self.restriction() else {
+            return self.repository.find(Some(filter), options).await;
+        };
+        let mut documents = self.repository.find_documents(filter, options).await?;
+        policy.hide_relations(&mut documents).await?;
+        documents
+            .into_iter()
+            .map(|d| from_document::<E>(d).map_err(|e| AppError::from(e.to_string())))
+            .collect()
+    }
+
+    /// Checks that the references held by a document or a set of fields point at existing documents.
+    async fn validate_references(&self, document: &Document) -> Result<(), AppError> {
+        match &self.integrity {
+            Some(integrity) => integrity.validate(self.repository.name(), document).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Adjusts the counters after an entity changed. `None` stands for a missing entity.
+    async fn adjust_counters(
+        &self,
+        before: Option<&Document>,
+        after: Option<&Document>,
+    ) -> Result<(), AppError> {
+        match &self.counters {
+            Some(counters) => counters.apply(self.repository.name(), before, after).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the filter matching trashed entities, or an error if soft delete is disabled.
+    fn trashed(&self) -> Result<Document, AppError> {
+        match self.soft_delete {
+            true => Ok(doc! { "deleted_at": { "$ne": null } }),
+            false => Err(AppError::from((
+                ErrorCode::TrashDisabled,
+                "Trash is not enabled for this resource",
+            ))),
+        }
+    }
+
+    /// Returns the revision tracking settings, or an error if history is not tracked.
+    fn tracked_history(&self) -> Result<&History, AppError> {
+        self.history.as_ref().ok_or_else(|| {
+            AppError::from((
+                ErrorCode::HistoryNotTracked,
+                "History is not tracked for this resource",
+            ))
+        })
+    }
+
+    /// Sets and removes the given fields on an entity and records the changed ones as a revision.
+    ///
+    /// # Parameters
+    /// - `oid`: The ID of the entity.
+    /// - `fields`: The top-level fields to set.
+    /// - `removed`: The top-level fields to remove.
+    /// - `actor`: The ID of the user making the change.
+    /// - `reverted_to`: The revision being reverted to, if this is a revert.
+    ///
+    /// # Returns
+    /// The updated entity.
+    async fn set_fields(
+        &self,
+        oid: ObjectId,
+        fields: Document,
+        removed: Vec<String>,
+        actor: &str,
+        reverted_to: Option<String>,
+    ) -> Result<R, AppError> {
+        let before = self
+            .repository
+            .find_one_document(self.live(Some(doc! { "_id": oid })))
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Document {} not found", oid)))?;
+        if fields.is_empty() && removed.is_empty() {
+            return from_document::<E>(before)
+                .map(R::from)
+                .map_err(|e| AppError::from(e.to_string()));
+        }
+        self.validate_references(&fields).await?;
+        let mut after = before.clone();
+        after.extend(fields.clone());
+        for field in &removed {
+            after.remove(field);
+        }
+        if self.unique.iter().any(|key| {
+            key.fields
+                .iter()
+                .any(|f| fields.contains_key(*f) || removed.iter().any(|r| r == f))
+        }) {
+            self.check_unique(&after, Some(oid)).await?;
+        }
+
+        let changes = field_changes(&before, &fields, &removed);
+
+        let mut set = fields;
+        let mut update = Document::new();
+        if self.edit_tracking && !changes.is_empty() {
+            let now = DateTime::now()
+                .try_to_rfc3339_string()
+                .map_err(|e| AppError::from(e.to_string()))?;
+            set.insert("edited_at", now);
+            update.insert("$inc", doc! { "edit_count": 1 });
+        }
+        if !set.is_empty() {
+            update.insert("$set", set);
+        }
+        if !removed.is_empty() {
+            let unset = removed
+                .iter()
+                .map(|field| (field.clone(), Bson::String(String::new())))
+                .collect::<Document>();
+            update.insert("$unset", unset);
+        }
+        let updated = self
+            .repository
+            .update_one(self.live(Some(doc! { "_id": oid })), update)
+            .await
+            .map_err(|e| self.unique_violation(e))?;
+        self.adjust_counters(Some(&before), Some(&after)).await?;
+
+        if let Some(history) = self.history.as_ref().filter(|_| !changes.is_empty()) {
+            history
+                .revisions
+                .insert_one(Revision {
+                    id: None,
+                    kind: history.kind.to_string(),
+                    document_id: oid.to_hex(),
+                    actor: actor.to_string(),
+                    action: match reverted_to {
+                        Some(_) => RevisionAction::Revert,
+                        None => RevisionAction::Update,
+                    },
+                    reverted_to,
+                    changes,
+                    created_at: DateTime::now(),
+                })
+                .await?;
+        }
+
+        Ok(R::from(updated))
+    }
+
+    /// Loads the revisions of an entity, newest first.
+    async fn revisions(&self, history: &History, oid: ObjectId) -> Result<Vec<Revision>, AppError> {
+        let options = FindOptions::builder().sort(doc! { "_id": -1 }).build();
+        history
+            .revisions
+            .find(
+                Some(doc! { "kind": history.kind, "document_id": oid.to_hex() }),
+                Some(options),
+            )
+            .await
+    }
+}
+
 impl<E, R, C, U> CrudService<E, R, C, U> for CrudServiceImpl<E, R, C, U>
 where
     E: Clone + Send + Sync + DeserializeOwned + Serialize + 'static,
@@ -157,6 +676,13 @@ where
     fn new(repository: Arc<DatabaseRepository<E>>) -> Self {
         Self {
             repository,
+            history: None,
+            soft_delete: false,
+            integrity: None,
+            counters: None,
+            unique: Vec::new(),
+            edit_tracking: false,
+            content_policy: None,
             _phantom: Default::default(),
         }
     }
@@ -172,18 +698,12 @@ where
         limit: u64,
     ) -> Result<Pagination<R>, AppError> {
         let options = FindOptions::builder()
-            .skip((page - 1) * limit)
+            .skip(page.saturating_sub(1) * limit)
             .limit(limit as i64)
             .build();
         let vec = self.find(filter.clone(), Some(options)).await?;
-        let total = self.count(filter.clone()).await;
-        Ok(Pagination {
-            current_page: page,
-            last_page: (total as f64 / limit as f64).ceil() as u64,
-            per_page: limit,
-            total,
-            payload: vec,
-        })
+        let total = self.count(filter).await;
+        Ok(Pagination::new(vec, page, limit, total))
     }
 
     async fn find(
@@ -192,22 +712,21 @@ where
         options: Option<FindOptions>,
     ) -> Result<Vec<R>, AppError> {
         Ok(self
-            .repository
-            .find(filter, options)
+            .read(filter, options)
             .await?
-            .iter()
-            .map(|e| R::from(e.clone()))
+            .into_iter()
+            .map(R::from)
             .collect::<Vec<_>>())
     }
 
     async fn get_by_id(&self, id: &str) -> Result<Option<R>, AppError> {
-        let oid =
-            get_object_id(id).or_else(|_| Err(AppError::from(("Ill-formed MongoId", 400))))?;
+        let oid = parse_object_id(id)?;
         Ok(self
-            .repository
-            .find_one(doc! { "_id": oid })
+            .read(Some(doc! { "_id": oid }), None)
             .await?
-            .map(|e| R::from(e)))
+            .into_iter()
+            .next()
+            .map(R::from))
     }
 
     async fn aggregate(
@@ -215,36 +734,792 @@ where
         filter: Vec<Document>,
         options: Option<AggregateOptions>,
     ) -> Result<Vec<Document>, AppError> {
-        self.repository.aggregate(filter, options).await
+        let visible = self.visible(None);
+        let pipeline = match visible.is_empty() {
+            false => std::iter::once(doc! { "$match": visible })
+                .chain(filter)
+                .collect(),
+            true => filter,
+        };
+        self.repository.aggregate(pipeline, options).await
     }
 
     async fn count(&self, filter: Option<Document>) -> u64 {
+        let filter = Some(self.visible(filter)).filter(|f| !f.is_empty());
         self.repository.count_documents(filter).await.unwrap_or(0)
     }
 
     async fn create(&self, entity: C) -> Result<R, AppError> {
-        Ok(R::from(self.repository.insert_one(entity.into()).await?))
+        let entity: E = entity.into();
+        let document = to_document(&entity).map_err(|e| AppError::from(e.to_string()))?;
+        self.validate_references(&document).await?;
+        self.check_unique(&document, None).await?;
+        let created = self
+            .repository
+            .insert_one(entity)
+            .await
+            .map_err(|e| self.unique_violation(e))?;
+        self.adjust_counters(None, Some(&document)).await?;
+        Ok(R::from(created))
     }
 
-    async fn update(&self, id: &str, update: U) -> Result<R, AppError> {
-        let oid =
-            get_object_id(id).or_else(|_| Err(AppError::from(("Ill-formed MongoId", 400))))?;
-        self.repository
-            .update_one(doc! { "_id": oid }, update.into())
-            .await
-            .map(|e| R::from(e))
+    async fn update(&self, id: &str, update: U, actor: &str) -> Result<R, AppError> {
+        let oid = parse_object_id(id)?;
+        match update.into() {
+            UpdateModifications::Document(fields) => {
+                self.set_fields(oid, fields, Vec::new(), actor, None).await
+            }
+            pipeline => self
+                .repository
+                .update_one(self.live(Some(doc! { "_id": oid })), pipeline)
+                .await
+                .map(R::from),
+        }
     }
 
-    async fn delete(&self, id: &str) -> Result<bool, AppError> {
+    async fn history(&self, id: &str) -> Result<Vec<RevisionDto>, AppError> {
+        let oid = parse_object_id(id)?;
+        let history = self.tracked_history()?;
         Ok(self
+            .revisions(history, oid)
+            .await?
+            .into_iter()
+            .map(RevisionDto::from)
+            .collect())
+    }
+
+    async fn revert(&self, id: &str, revision_id: &str, actor: &str) -> Result<R, AppError> {
+        let oid = parse_object_id(id)?;
+        let history = self.tracked_history()?;
+        let revisions = self.revisions(history, oid).await?;
+        let position = revisions
+            .iter()
+            .position(|r| r.id.as_deref() == Some(revision_id))
+            .ok_or_else(|| AppError::NotFound(format!("Revision {} not found", revision_id)))?;
+
+        let (fields, removed) = undo_revisions(&revisions[..position]);
+        self.set_fields(oid, fields, removed, actor, Some(revision_id.to_string()))
+            .await
+    }
+
+    async fn delete(&self, id: &str, actor: &str) -> Result<bool, AppError> {
+        let oid = parse_object_id(id)?;
+        Ok(self.delete_by_criteria(doc! { "_id": oid }, actor).await? > 0)
+    }
+
+    async fn delete_by_criteria(&self, criteria: Document, actor: &str) -> Result<u64, AppError> {
+        let filter = self.live(Some(criteria));
+        if let Some(integrity) = &self.integrity {
+            let deletion = integrity
+                .delete(self.repository.name(), filter, actor, self.soft_delete)
+                .await?;
+            // The cascaded documents may be counted even when this entity is not
+            if let Some(counters) = &self.counters {
+                for (collection, document) in &deletion.documents {
+                    counters.apply(collection, Some(document), None).await?;
+                }
+            }
+            return Ok(deletion.count);
+        }
+
+        let counted = match &self.counters {
+            Some(counters) if counters.counts(self.repository.name()) => {
+                self.repository.find_documents(filter.clone(), None).await?
+            }
+            _ => Vec::new(),
+        };
+
+        let deleted = if !self.soft_delete {
+            self.repository.delete_many(filter).await?.deleted_count
+        } else {
+            self.repository
+                .update_many(filter, trash(actor))
+                .await?
+                .modified_count
+        };
+
+        for document in &counted {
+            self.adjust_counters(Some(document), None).await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn get_trash(
+        &self,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<TrashedDto<R>>, AppError> {
+        let filter = self.trashed()?;
+        let options = FindOptions::builder()
+            .sort(doc! { "deleted_at": -1 })
+            .skip(page.saturating_sub(1) * limit)
+            .limit(limit as i64)
+            .build();
+
+        let payload = self
             .repository
-            .delete_one(doc! { "_id": get_object_id(id)? })
+            .find_documents(filter.clone(), Some(options))
             .await?
-            .deleted_count
-            > 0)
+            .into_iter()
+            .map(|document| {
+                Ok(TrashedDto {
+                    deleted_at: *document
+                        .get_datetime("deleted_at")
+                        .map_err(|e| AppError::from(e.to_string()))?,
+                    deleted_by: document.get_str("deleted_by").ok().map(str::to_string),
+                    item: from_document::<E>(document)
+                        .map(R::from)
+                        .map_err(|e| AppError::from(e.to_string()))?,
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+        let total = self.repository.count_documents(Some(filter)).await?;
+        Ok(Pagination::new(payload, page, limit, total))
     }
 
-    async fn delete_by_criteria(&self, criteria: Document) -> Result<u64, AppError> {
-        Ok(self.repository.delete_many(criteria).await?.deleted_count)
+    async fn restore(&self, id: &str) -> Result<R, AppError> {
+        let oid = parse_object_id(id)?;
+        let mut filter = self.trashed()?;
+        filter.insert("_id", oid);
+        let trashed = self
+            .repository
+            .find_documents(filter.clone(), None)
+            .await?
+            .pop()
+            .ok_or_else(|| AppError::NotFound(format!("Document {} not found", oid)))?;
+        self.validate_references(&trashed).await?;
+        self.check_unique(&trashed, Some(oid)).await?;
+
+        let restored = self
+            .repository
+            .update_one(filter, untrash())
+            .await
+            .map_err(|e| self.unique_violation(e))?;
+        let document = to_document(&restored).map_err(|e| AppError::from(e.to_string()))?;
+        self.adjust_counters(None, Some(&document)).await?;
+        Ok(R::from(restored))
+    }
+
+    async fn purge(&self, id: &str) -> Result<bool, AppError> {
+        let mut filter = self.trashed()?;
+        filter.insert("_id", parse_object_id(id)?);
+        Ok(self.repository.delete_one(filter).await?.deleted_count > 0)
+    }
+}
+
+/// Lists the fields whose value differs once `fields` are set and `removed` are removed.
+///
+/// # Parameters
+/// - `before`: The stored document.
+/// - `fields`: The top-level fields to set.
+/// - `removed`: The top-level fields to remove.
+///
+/// # Returns
+/// A change for each field whose value, or presence, differs.
+fn field_changes(before: &Document, fields: &Document, removed: &[String]) -> Vec<FieldChange> {
+    let set = fields.iter().map(|(field, value)| (field, Some(value)));
+    let unset = removed.iter().map(|field| (field, None));
+    set.chain(unset)
+        .filter_map(|(field, after)| {
+            let before = before.get(field);
+            (before != after).then(|| FieldChange {
+                field: field.clone(),
+                before: before.cloned(),
+                after: after.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Computes the update undoing the given revisions.
+///
+/// # Parameters
+/// - `revisions`: The revisions to undo, newest first.
+///
+/// # Returns
+/// The fields to set, and the fields to remove as they were not set before the oldest revision.
+fn undo_revisions(revisions: &[Revision]) -> (Document, Vec<String>) {
+    let mut fields = Document::new();
+    let mut removed = Vec::new();
+    // Walk from newest to oldest, so the value prior to the oldest revision wins
+    for change in revisions.iter().flat_map(|revision| &revision.changes) {
+        fields.remove(&change.field);
+        removed.retain(|field| *field != change.field);
+        match &change.before {
+            Some(value) => {
+                fields.insert(change.field.clone(), value.clone());
+            }
+            None => removed.push(change.field.clone()),
+        }
+    }
+    (fields, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_changes, undo_revisions, CrudService, CrudServiceImpl};
+    use crate::dto::genre::{CreateGenreDto, GenreDto, UpdateGenreDto};
+    use crate::dto::review::{CreateReviewDto, ReviewDto, UpdateReviewDto};
+    use crate::dto::user::{RegisterUserDto, UpdateUserDto, UserDto};
+    use crate::models::genre::Genre;
+    use crate::models::review::Review;
+    use crate::models::revision::{FieldChange, Revision, RevisionAction};
+    use crate::models::user::User;
+    use crate::services::counters::{Counter, CounterService};
+    use crate::services::db_repo::DatabaseRepository;
+    use crate::services::integrity::{IntegrityService, OnDelete, Reference};
+    use crate::types::app_error::AppError;
+    use crate::types::error_response::ErrorCode;
+    use crate::utils::testing::test_database;
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::{doc, from_document, to_document, Bson, DateTime, Document};
+    use mongodb::Database;
+    use std::sync::Arc;
+
+    type GenreService = CrudServiceImpl<Genre, GenreDto, CreateGenreDto, UpdateGenreDto>;
+    type ReviewService = CrudServiceImpl<Review, ReviewDto, CreateReviewDto, UpdateReviewDto>;
+    type UserService = CrudServiceImpl<User, UserDto, RegisterUserDto, UpdateUserDto>;
+
+    fn genres(db: &Database) -> GenreService {
+        GenreService::new(Arc::new(DatabaseRepository::new(db.collection("genres"))))
+            .with_soft_delete()
+    }
+
+    /// Genres unique by MAL ID and media type.
+    fn unique_genres(db: &Database) -> GenreService {
+        genres(db).with_unique(
+            "mal_id_type",
+            &["mal_id", "type"],
+            ErrorCode::Duplicate,
+            "This genre already exists",
+        )
+    }
+
+    fn is_duplicate(result: Result<GenreDto, AppError>) -> bool {
+        matches!(result, Err(AppError::HttpError(ErrorCode::Duplicate, _)))
+    }
+
+    /// The references and counters between users, their follows, reviews, comments and
+    /// reactions, as declared in `AppState`.
+    fn social(db: &Database) -> (Arc<IntegrityService>, Arc<CounterService>) {
+        let cascade = |source, field, target| Reference {
+            source,
+            field,
+            targets: match target {
+                "reviews" => &["reviews"],
+                "comments" => &["comments"],
+                _ => &["users"],
+            },
+            many: false,
+            on_delete: OnDelete::Cascade,
+        };
+        let references = vec![
+            cascade("reviews", "user", "users"),
+            cascade("comments", "review", "reviews"),
+            cascade("comments", "parent", "comments"),
+            cascade("comments", "user", "users"),
+            cascade("follows", "follower", "users"),
+            cascade("follows", "followee", "users"),
+            cascade("review_reactions", "target", "reviews"),
+            cascade("review_reactions", "user", "users"),
+        ];
+
+        let counter = |target, source, field, count| Counter {
+            target,
+            target_key: "_id",
+            scope: None,
+            source,
+            source_scope: None,
+            field,
+            key: None,
+            count,
+        };
+        let counters = vec![
+            counter("reviews", "comments", "review", "comment_count"),
+            counter("comments", "comments", "parent", "reply_count"),
+            counter("users", "follows", "followee", "follower_count"),
+            counter("users", "follows", "follower", "following_count"),
+            counter("reviews", "review_reactions", "target", "reactions.overall"),
+            Counter {
+                source_scope: Some(("reaction", "nice")),
+                ..counter("reviews", "review_reactions", "target", "reactions.nice")
+            },
+        ];
+
+        (
+            Arc::new(IntegrityService::new(db.clone(), references, false)),
+            Arc::new(CounterService::new(db.clone(), counters)),
+        )
+    }
+
+    /// Inserts a document with a new ID and returns the ID.
+    async fn insert(db: &Database, collection: &str, mut document: Document) -> ObjectId {
+        let id = ObjectId::new();
+        document.insert("_id", id);
+        db.collection::<Document>(collection)
+            .insert_one(document)
+            .await
+            .unwrap();
+        id
+    }
+
+    /// Reads a stored counter, trashed documents included. The field may be a dotted path.
+    async fn stored(db: &Database, collection: &str, id: ObjectId, field: &str) -> i64 {
+        let mut document = db
+            .collection::<Document>(collection)
+            .find_one(doc! { "_id": id })
+            .await
+            .unwrap()
+            .unwrap();
+        let mut path = field.split('.').collect::<Vec<_>>();
+        let last = path.pop().unwrap();
+        for parent in path {
+            document = document.get_document(parent).unwrap().clone();
+        }
+        match document.get(last) {
+            Some(Bson::Int32(count)) => *count as i64,
+            Some(Bson::Int64(count)) => *count,
+            other => panic!("{} is not a count: {:?}", field, other),
+        }
+    }
+
+    fn genre(mal_id: u64, name: &str) -> CreateGenreDto {
+        CreateGenreDto {
+            mal_id,
+            r#type: "anime".to_string(),
+            name: name.to_string(),
+            count: 0,
+        }
+    }
+
+    fn change(field: &str, before: Option<Bson>, after: Option<Bson>) -> FieldChange {
+        FieldChange {
+            field: field.to_string(),
+            before,
+            after,
+        }
+    }
+
+    fn revision(changes: Vec<FieldChange>) -> Revision {
+        Revision {
+            id: None,
+            kind: "anime".to_string(),
+            document_id: "0".repeat(24),
+            actor: "staff".to_string(),
+            action: RevisionAction::Update,
+            reverted_to: None,
+            changes,
+            created_at: DateTime::now(),
+        }
+    }
+
+    #[test]
+    fn setting_an_absent_field_records_no_prior_value() {
+        let before = doc! { "title": "Bebop" };
+        let changes = field_changes(&before, &doc! { "synopsis": "Space" }, &[]);
+        assert_eq!(
+            changes,
+            vec![change("synopsis", None, Some(Bson::from("Space")))]
+        );
+    }
+
+    #[test]
+    fn setting_a_null_field_keeps_the_null() {
+        let before = doc! { "synopsis": Bson::Null };
+        let changes = field_changes(&before, &doc! { "synopsis": "Space" }, &[]);
+        assert_eq!(
+            changes,
+            vec![change(
+                "synopsis",
+                Some(Bson::Null),
+                Some(Bson::from("Space"))
+            )]
+        );
+    }
+
+    #[test]
+    fn unchanged_and_already_absent_fields_are_not_recorded() {
+        let before = doc! { "title": "Bebop" };
+        let removed = ["synopsis".to_string()];
+        assert!(field_changes(&before, &doc! { "title": "Bebop" }, &removed).is_empty());
+    }
+
+    #[test]
+    fn removing_a_field_records_no_new_value() {
+        let before = doc! { "synopsis": "Space" };
+        let changes = field_changes(&before, &doc! {}, &["synopsis".to_string()]);
+        assert_eq!(
+            changes,
+            vec![change("synopsis", Some(Bson::from("Space")), None)]
+        );
+    }
+
+    #[test]
+    fn undoing_the_addition_of_a_field_removes_it() {
+        let revisions = [revision(vec![
+            change("synopsis", None, Some(Bson::from("Space"))),
+            change(
+                "title",
+                Some(Bson::from("Bebop")),
+                Some(Bson::from("Cowboy")),
+            ),
+        ])];
+        let (fields, removed) = undo_revisions(&revisions);
+        assert_eq!(fields, doc! { "title": "Bebop" });
+        assert_eq!(removed, vec!["synopsis".to_string()]);
+    }
+
+    #[test]
+    fn undoing_several_revisions_restores_the_oldest_prior_value() {
+        // Newest first: the field was added, then removed, then set again
+        let revisions = [
+            revision(vec![change("synopsis", None, Some(Bson::from("Later")))]),
+            revision(vec![change("synopsis", Some(Bson::from("First")), None)]),
+            revision(vec![change("synopsis", None, Some(Bson::from("First")))]),
+        ];
+        let (fields, removed) = undo_revisions(&revisions);
+        assert!(fields.is_empty());
+        assert_eq!(removed, vec!["synopsis".to_string()]);
+
+        let (fields, removed) = undo_revisions(&revisions[..2]);
+        assert_eq!(fields, doc! { "synopsis": "First" });
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn stored_changes_tell_null_apart_from_absent() {
+        let absent = change("synopsis", None, Some(Bson::from("Space")));
+        let stored = to_document(&absent).unwrap();
+        assert!(!stored.contains_key("before"));
+        assert_eq!(from_document::<FieldChange>(stored).unwrap(), absent);
+
+        let null = change("synopsis", Some(Bson::Null), Some(Bson::from("Space")));
+        let stored = to_document(&null).unwrap();
+        assert_eq!(stored.get("before"), Some(&Bson::Null));
+        assert_eq!(from_document::<FieldChange>(stored).unwrap(), null);
+    }
+
+    #[actix_web::test]
+    async fn deleted_entities_move_to_the_trash_until_restored() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let genres = genres(&db);
+        let id = genres.create(genre(1, "Action")).await.unwrap().id.unwrap();
+        genres.create(genre(2, "Comedy")).await.unwrap();
+
+        assert!(genres.delete(&id, "staff").await.unwrap());
+        assert!(genres.get_by_id(&id).await.unwrap().is_none());
+        assert_eq!(genres.count(None).await, 1);
+        assert!(!genres.delete(&id, "staff").await.unwrap());
+
+        let trash = genres.get_trash(1, 10).await.unwrap();
+        assert_eq!(trash.total, 1);
+        assert_eq!(trash.payload[0].item.id.as_deref(), Some(id.as_str()));
+        assert_eq!(trash.payload[0].deleted_by.as_deref(), Some("staff"));
+
+        genres.restore(&id).await.unwrap();
+        assert!(genres.get_by_id(&id).await.unwrap().is_some());
+        assert_eq!(genres.count(None).await, 2);
+        assert_eq!(genres.get_trash(1, 10).await.unwrap().total, 0);
+        assert!(genres.restore(&id).await.is_err());
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn only_trashed_entities_can_be_purged() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let genres = genres(&db);
+        let id = genres.create(genre(1, "Action")).await.unwrap().id.unwrap();
+
+        assert!(!genres.purge(&id).await.unwrap());
+        genres.delete(&id, "staff").await.unwrap();
+        assert!(genres.purge(&id).await.unwrap());
+        assert_eq!(genres.get_trash(1, 10).await.unwrap().total, 0);
+        assert!(genres.restore(&id).await.is_err());
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn trash_is_refused_without_soft_delete() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let genres = GenreService::new(Arc::new(DatabaseRepository::new(db.collection("genres"))));
+        let id = genres.create(genre(1, "Action")).await.unwrap().id.unwrap();
+
+        assert!(genres.get_trash(1, 10).await.is_err());
+        assert!(genres.delete(&id, "staff").await.unwrap());
+        assert!(genres.restore(&id).await.is_err());
+        assert_eq!(
+            db.collection::<Genre>("genres")
+                .count_documents(doc! {})
+                .await
+                .unwrap(),
+            0
+        );
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn duplicates_are_refused_until_the_original_is_trashed() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let genres = unique_genres(&db);
+        genres.ensure_unique_indexes().await.unwrap();
+        let id = genres.create(genre(1, "Action")).await.unwrap().id.unwrap();
+        let other = genres.create(genre(2, "Comedy")).await.unwrap().id.unwrap();
+
+        assert!(is_duplicate(genres.create(genre(1, "Again")).await));
+        let update = UpdateGenreDto {
+            mal_id: Some(1),
+            r#type: None,
+            name: None,
+            count: None,
+        };
+        assert!(is_duplicate(genres.update(&other, update, "staff").await));
+
+        genres.delete(&id, "staff").await.unwrap();
+        let again = genres.create(genre(1, "Again")).await.unwrap();
+        assert_eq!(again.name, "Again");
+        // Trashed duplicates never conflict
+        genres
+            .delete(again.id.as_deref().unwrap(), "staff")
+            .await
+            .unwrap();
+        genres.create(genre(1, "Third")).await.unwrap();
+        assert_eq!(genres.get_trash(1, 10).await.unwrap().total, 2);
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn duplicates_written_before_the_rule_are_trashed_but_the_newest() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let collection = db.collection::<Document>("genres");
+        let ids = (0..3).map(|_| ObjectId::new()).collect::<Vec<_>>();
+        for (i, id) in ids.iter().enumerate() {
+            let name = format!("Action {}", i);
+            collection
+                .insert_one(
+                    doc! { "_id": id, "mal_id": 1, "type": "anime", "name": name, "count": 0 },
+                )
+                .await
+                .unwrap();
+        }
+        collection
+            .insert_one(doc! { "mal_id": 1, "type": "manga", "name": "Action", "count": 0 })
+            .await
+            .unwrap();
+
+        let genres = unique_genres(&db);
+        let trashed = genres.ensure_unique_indexes().await.unwrap();
+        assert_eq!(
+            trashed.iter().map(|t| t.id).collect::<Vec<_>>(),
+            [ids[1], ids[0]]
+        );
+        assert!(trashed
+            .iter()
+            .all(|t| t.kept == ids[2] && t.collection == "genres" && t.index == "mal_id_type"));
+        assert_eq!(genres.count(None).await, 2);
+        assert!(genres.get_by_id(&ids[2].to_hex()).await.unwrap().is_some());
+        let trash = genres.get_trash(1, 10).await.unwrap();
+        assert_eq!(trash.total, 2);
+        assert!(trash
+            .payload
+            .iter()
+            .all(|t| t.deleted_by.as_deref() == Some("deduplication")));
+
+        // A duplicate is only restored once the kept entity is trashed
+        assert!(is_duplicate(genres.restore(&ids[0].to_hex()).await));
+        genres.delete(&ids[2].to_hex(), "staff").await.unwrap();
+        genres.restore(&ids[0].to_hex()).await.unwrap();
+
+        // Once the index exists, the collection is no longer scanned
+        assert!(genres.ensure_unique_indexes().await.unwrap().is_empty());
+        assert_eq!(genres.get_trash(1, 10).await.unwrap().total, 2);
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn deleting_a_review_uncounts_its_comments_and_their_replies() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let (integrity, counters) = social(&db);
+        let reviews =
+            ReviewService::new(Arc::new(DatabaseRepository::new(db.collection("reviews"))))
+                .with_soft_delete()
+                .with_integrity(integrity)
+                .with_counters(counters.clone());
+
+        let deleted = insert(&db, "reviews", doc! { "comment_count": 2_i64 }).await;
+        let kept = insert(&db, "reviews", doc! { "comment_count": 1_i64 }).await;
+        let comment = insert(
+            &db,
+            "comments",
+            doc! { "review": deleted.to_hex(), "parent": null, "reply_count": 1_i64 },
+        )
+        .await;
+        let reply =
+            doc! { "review": deleted.to_hex(), "parent": comment.to_hex(), "reply_count": 0_i64 };
+        insert(&db, "comments", reply).await;
+        let other = doc! { "review": kept.to_hex(), "parent": null, "reply_count": 0_i64 };
+        insert(&db, "comments", other).await;
+
+        assert!(reviews.delete(&deleted.to_hex(), "staff").await.unwrap());
+        assert_eq!(stored(&db, "reviews", deleted, "comment_count").await, 0);
+        assert_eq!(stored(&db, "comments", comment, "reply_count").await, 0);
+        assert_eq!(stored(&db, "reviews", kept, "comment_count").await, 1);
+        assert!(counters.reconcile(false).await.unwrap().is_empty());
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn deleting_a_user_uncounts_their_follows_and_comments() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let (integrity, counters) = social(&db);
+        let users = UserService::new(Arc::new(DatabaseRepository::new(db.collection("users"))))
+            .with_soft_delete()
+            .with_integrity(integrity)
+            .with_counters(counters.clone());
+
+        let counts = doc! { "follower_count": 1_i64, "following_count": 1_i64 };
+        let deleted = insert(&db, "users", counts.clone()).await;
+        let kept = insert(&db, "users", counts).await;
+        for (follower, followee) in [(deleted, kept), (kept, deleted)] {
+            let follow = doc! { "follower": follower.to_hex(), "followee": followee.to_hex() };
+            insert(&db, "follows", follow).await;
+        }
+        let review = insert(&db, "reviews", doc! { "comment_count": 2_i64 }).await;
+        let comment = doc! {
+            "review": review.to_hex(),
+            "parent": null,
+            "user": kept.to_hex(),
+            "reply_count": 1_i64,
+        };
+        let comment = insert(&db, "comments", comment).await;
+        let reply = doc! {
+            "review": review.to_hex(),
+            "parent": comment.to_hex(),
+            "user": deleted.to_hex(),
+            "reply_count": 0_i64,
+        };
+        insert(&db, "comments", reply).await;
+
+        assert!(users.delete(&deleted.to_hex(), "staff").await.unwrap());
+        assert_eq!(stored(&db, "users", kept, "follower_count").await, 0);
+        assert_eq!(stored(&db, "users", kept, "following_count").await, 0);
+        assert_eq!(stored(&db, "reviews", review, "comment_count").await, 1);
+        assert_eq!(stored(&db, "comments", comment, "reply_count").await, 0);
+        assert!(counters.reconcile(false).await.unwrap().is_empty());
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn a_restore_is_refused_while_a_referenced_document_is_trashed() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let (integrity, counters) = social(&db);
+        let users = UserService::new(Arc::new(DatabaseRepository::new(db.collection("users"))))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone());
+        let reviews =
+            ReviewService::new(Arc::new(DatabaseRepository::new(db.collection("reviews"))))
+                .with_soft_delete()
+                .with_integrity(integrity)
+                .with_counters(counters);
+
+        let user = User::new(
+            "author".to_string(),
+            "author@example.com".to_string(),
+            "hash".to_string(),
+            true,
+            false,
+            false,
+            None,
+            None,
+            None,
+        );
+        let user = insert(&db, "users", to_document(&user).unwrap()).await;
+        let review = CreateReviewDto {
+            mal_id: 1,
+            url: String::new(),
+            r#type: "anime".to_string(),
+            date: DateTime::now(),
+            review: "A fine show".to_string(),
+            score: 8,
+            tags: Vec::new(),
+            is_spoiler: false,
+            is_preliminary: false,
+            episodes_watched: None,
+            entry: ObjectId::new().to_hex(),
+            user: user.to_hex(),
+            status: Default::default(),
+            hold_reason: None,
+        };
+        let review = reviews.create(review).await.unwrap().id.unwrap();
+
+        // The review is trashed along with its author, and restored after them
+        assert!(users.delete(&user.to_hex(), "staff").await.unwrap());
+        assert!(matches!(
+            reviews.restore(&review).await,
+            Err(AppError::HttpError(ErrorCode::ReferenceMissing, _))
+        ));
+        users.restore(&user.to_hex()).await.unwrap();
+        assert!(reviews.get_by_id(&review).await.unwrap().is_none());
+        reviews.restore(&review).await.unwrap();
+        assert!(reviews.get_by_id(&review).await.unwrap().is_some());
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn deleting_a_user_uncounts_their_reactions() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let (integrity, counters) = social(&db);
+        let users = UserService::new(Arc::new(DatabaseRepository::new(db.collection("users"))))
+            .with_soft_delete()
+            .with_integrity(integrity)
+            .with_counters(counters.clone());
+
+        let deleted = insert(&db, "users", doc! {}).await;
+        let kept = insert(&db, "users", doc! {}).await;
+        let reactions = doc! { "reactions": { "nice": 2_i64, "funny": 1_i64, "overall": 3_i64 } };
+        let review = insert(&db, "reviews", reactions).await;
+        for (user, reaction) in [(deleted, "nice"), (deleted, "funny"), (kept, "nice")] {
+            let reaction = doc! {
+                "target": review.to_hex(),
+                "user": user.to_hex(),
+                "reaction": reaction,
+            };
+            insert(&db, "review_reactions", reaction).await;
+        }
+
+        assert!(users.delete(&deleted.to_hex(), "staff").await.unwrap());
+        assert_eq!(stored(&db, "reviews", review, "reactions.nice").await, 1);
+        assert_eq!(stored(&db, "reviews", review, "reactions.overall").await, 1);
+        // The funny reaction is only counted overall
+        let drift = counters.reconcile(false).await.unwrap();
+        assert!(drift.is_empty(), "{:?}", drift);
+
+        db.drop().await.unwrap();
     }
 }
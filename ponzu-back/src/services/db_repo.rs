@@ -1,12 +1,40 @@
 use crate::types::app_error::AppError;
 use futures::TryStreamExt;
-use mongodb::bson::{doc, Document};
-use mongodb::error::Error;
-use mongodb::options::{AggregateOptions, FindOptions, UpdateModifications};
-use mongodb::results::DeleteResult;
-use mongodb::Collection;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::change_stream::event::ChangeStreamEvent;
+use mongodb::change_stream::ChangeStream;
+use mongodb::error::ErrorKind;
+use mongodb::options::{
+    AggregateOptions, FindOptions, IndexOptions, ReturnDocument, UpdateModifications,
+};
+use mongodb::results::{DeleteResult, UpdateResult};
+use mongodb::{Collection, IndexModel};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::time::Duration;
+
+/// The code of the server error raised when a collection does not exist.
+const NAMESPACE_NOT_FOUND: i32 = 26;
+
+/// The field repeating the ID of a trashed document, which live documents lack.
+pub const TRASH_KEY: &str = "deleted_id";
+
+/// Returns the update moving documents to the trash.
+///
+/// # Parameters
+/// - `actor`: The ID of the user trashing the documents.
+pub fn trash(actor: &str) -> Vec<Document> {
+    vec![doc! { "$set": {
+        "deleted_at": DateTime::now(),
+        "deleted_by": { "$literal": actor },
+        TRASH_KEY: "$_id",
+    } }]
+}
+
+/// The update taking documents out of the trash.
+pub fn untrash() -> Document {
+    doc! { "$unset": { "deleted_at": "", "deleted_by": "", TRASH_KEY: "" } }
+}
 
 /// A generic repository for interacting with a MongoDB collection.
 ///
@@ -15,11 +43,12 @@ use serde::Serialize;
 ///
 /// # Type Parameters
 /// - `T`: The type of documents stored in the collection. This type must implement
-///        `Send`, `Sync`, `DeserializeOwned`, and `Serialize` traits.
+///   `Send`, `Sync`, `DeserializeOwned`, and `Serialize` traits.
 pub struct DatabaseRepository<T: Send + Sync + DeserializeOwned + Serialize> {
     collection: Collection<T>,
 }
 
+#[allow(dead_code)]
 impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
     /// Creates a new instance of `DatabaseRepository`.
     ///
@@ -32,6 +61,11 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
         DatabaseRepository { collection }
     }
 
+    /// Returns the name of the underlying collection.
+    pub fn name(&self) -> &str {
+        self.collection.name()
+    }
+
     /// Finds documents in the collection that match the provided filter.
     ///
     /// # Parameters
@@ -47,13 +81,13 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
     ) -> Result<Vec<T>, AppError> {
         // Execute the query
         self.collection
-            .find(filter.unwrap_or(doc! {}))
+            .find(filter.unwrap_or_default())
             .with_options(options)
             .await?
             // Collect the results into a Vec<T>
             .try_collect()
             .await
-            .or_else(|e| Err(AppError::from(e)))
+            .map_err(AppError::from)
     }
 
     /// Finds a single document in the collection that matches the provided filter.
@@ -65,11 +99,10 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
     /// A `Result` containing an `Option<T>` if successful, or an `AppError` if the operation fails.
     /// The `Option<T>` will be `Some(T)` if a document is found, otherwise `None`.
     pub async fn find_one(&self, filter: Document) -> Result<Option<T>, AppError> {
-        match self.collection.find_one(filter).await? {
-            Some(doc) => Ok(Some(doc)),
-            None => Ok(None),
-        }
-        .or_else(|e: Error| Err(AppError::from(e)))
+        self.collection
+            .find_one(filter)
+            .await
+            .map_err(AppError::from)
     }
 
     /// Inserts a single document into the collection.
@@ -81,9 +114,70 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
     /// A document if successful, or an `AppError` if the operation fails.
     pub async fn insert_one(&self, doc: T) -> Result<T, AppError> {
         let id = self.collection.insert_one(doc).await?.inserted_id;
-        self.find_one(doc! {"_id": id.clone()}).await?.ok_or_else(|| {
-            AppError::from(format!("Document not found after insert: {:?}", id))
-        })
+        self.find_one(doc! {"_id": id.clone()})
+            .await?
+            .ok_or_else(|| AppError::from(format!("Document not found after insert: {:?}", id)))
+    }
+
+    /// Inserts several documents into the collection.
+    ///
+    /// # Parameters
+    /// - `docs`: The documents to insert.
+    ///
+    /// # Returns
+    /// The inserted documents if successful, or an `AppError` if the operation fails.
+    pub async fn insert_many(&self, docs: Vec<T>) -> Result<Vec<T>, AppError> {
+        if docs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids = self
+            .collection
+            .insert_many(docs)
+            .await?
+            .inserted_ids
+            .into_values()
+            .collect::<Vec<_>>();
+        self.find(Some(doc! { "_id": { "$in": ids } }), None).await
+    }
+
+    /// Finds a single document in the collection as a raw BSON document.
+    ///
+    /// Useful when the stored representation is needed as-is, e.g. to compare field values.
+    ///
+    /// # Parameters
+    /// - `filter`: A MongoDB document specifying the query criteria.
+    ///
+    /// # Returns
+    /// A `Result` containing an `Option<Document>` if successful, or an `AppError` if the operation fails.
+    pub async fn find_one_document(&self, filter: Document) -> Result<Option<Document>, AppError> {
+        self.collection
+            .clone_with_type::<Document>()
+            .find_one(filter)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Finds documents in the collection as raw BSON documents.
+    ///
+    /// # Parameters
+    /// - `filter`: A MongoDB document specifying the query criteria.
+    /// - `options`: Optional `FindOptions` to configure the find operation.
+    ///
+    /// # Returns
+    /// A `Result` containing a `Vec<Document>` if successful, or an `AppError` if the operation fails.
+    pub async fn find_documents(
+        &self,
+        filter: Document,
+        options: Option<FindOptions>,
+    ) -> Result<Vec<Document>, AppError> {
+        self.collection
+            .clone_with_type::<Document>()
+            .find(filter)
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await
+            .map_err(AppError::from)
     }
 
     /// Updates a single document in the collection that matches the provided filter.
@@ -99,23 +193,51 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
         filter: Document,
         update: impl Into<UpdateModifications>,
     ) -> Result<T, AppError> {
-        match self
-            .collection
-            .update_one(filter.clone(), update)
+        self.collection
+            .find_one_and_update(filter.clone(), update)
+            .return_document(ReturnDocument::After)
             .await?
-            .upserted_id
-        {
-            Some(id) => self.find_one(doc! {"_id": id}).await?.ok_or_else(|| {
-                AppError::from(format!(
-                    "Document not found after update: {:?}",
-                    filter.clone()
-                ))
-            }),
-            None => Err(AppError::from(format!(
-                "Document not found: {:?}",
-                filter.clone()
-            ))),
-        }
+            .ok_or_else(|| AppError::NotFound(format!("Document not found: {:?}", filter)))
+    }
+
+    /// Updates all documents in the collection that match the provided filter.
+    ///
+    /// # Parameters
+    /// - `filter`: A MongoDB document specifying the query criteria.
+    /// - `update`: The update operations to apply to the matching documents.
+    ///
+    /// # Returns
+    /// A `Result` containing an `UpdateResult` if successful, or an `AppError` if the operation fails.
+    pub async fn update_many(
+        &self,
+        filter: Document,
+        update: impl Into<UpdateModifications>,
+    ) -> Result<UpdateResult, AppError> {
+        self.collection
+            .update_many(filter, update)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Updates a single document that matches the provided filter, or inserts one if none does.
+    ///
+    /// # Parameters
+    /// - `filter`: A MongoDB document specifying the query criteria. Its equality conditions are
+    ///   part of the inserted document.
+    /// - `update`: The update operations to apply to the matching or inserted document.
+    ///
+    /// # Returns
+    /// A `Result` containing an `UpdateResult` if successful, or an `AppError` if the operation fails.
+    pub async fn upsert_one(
+        &self,
+        filter: Document,
+        update: impl Into<UpdateModifications>,
+    ) -> Result<UpdateResult, AppError> {
+        self.collection
+            .update_one(filter, update)
+            .upsert(true)
+            .await
+            .map_err(AppError::from)
     }
 
     /// Deletes a single document from the collection that matches the provided filter.
@@ -129,7 +251,7 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
         self.collection
             .delete_one(filter)
             .await
-            .or_else(|e| Err(AppError::from(e)))
+            .map_err(AppError::from)
     }
 
     /// Deletes multiple documents from the collection that match the provided filter.
@@ -143,7 +265,7 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
         self.collection
             .delete_many(filter)
             .await
-            .or_else(|e| Err(AppError::from(e)))
+            .map_err(AppError::from)
     }
 
     /// Counts the number of documents in the collection that match the provided filter.
@@ -177,7 +299,7 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
             Some(filter) => self.collection.count_documents(filter).await,
             None => self.collection.estimated_document_count().await,
         }
-        .or_else(|e| Err(AppError::from(e)))
+        .map_err(AppError::from)
     }
 
     /// Executes an aggregation pipeline on the collection.
@@ -223,6 +345,173 @@ impl<T: Send + Sync + DeserializeOwned + Serialize> DatabaseRepository<T> {
             .await?
             .try_collect()
             .await
-            .or_else(|e| Err(AppError::from(e)))
+            .map_err(AppError::from)
+    }
+
+    /// Opens a change stream on the documents inserted into the collection.
+    ///
+    /// Change streams require a replica set or a sharded cluster.
+    ///
+    /// # Returns
+    /// A stream of the insert events if successful, or an `AppError` if the operation fails.
+    pub async fn watch_inserts(&self) -> Result<ChangeStream<ChangeStreamEvent<T>>, AppError>
+    where
+        T: Unpin,
+    {
+        self.collection
+            .watch()
+            .pipeline(vec![doc! { "$match": { "operationType": "insert" } }])
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// Checks whether the collection has an index with the given name.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the index.
+    ///
+    /// # Returns
+    /// Whether the index exists, which it never does on a missing collection.
+    pub async fn has_index(&self, name: &str) -> Result<bool, AppError> {
+        Ok(self.index_keys(name).await?.is_some())
+    }
+
+    /// Returns the keys of the index with the given name, if the collection has one.
+    async fn index_keys(&self, name: &str) -> Result<Option<Document>, AppError> {
+        let indexes = match self.collection.list_indexes().await {
+            Ok(indexes) => indexes.try_collect::<Vec<IndexModel>>().await?,
+            Err(e) => match e.kind.as_ref() {
+                ErrorKind::Command(c) if c.code == NAMESPACE_NOT_FOUND => return Ok(None),
+                _ => return Err(AppError::from(e)),
+            },
+        };
+        Ok(indexes
+            .into_iter()
+            .find(|index| {
+                let options = index.options.as_ref();
+                options.and_then(|o| o.name.as_deref()) == Some(name)
+            })
+            .map(|index| index.keys))
+    }
+
+    /// Ensures a unique index exists on the given keys of the collection.
+    ///
+    /// An existing index of the same name on other keys is replaced.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the index.
+    /// - `keys`: The indexed keys, e.g. `{ "review": 1, "user": 1 }`.
+    ///
+    /// # Returns
+    /// An empty `Result` if successful, or an `AppError` if the operation fails.
+    pub async fn ensure_unique_index(&self, name: &str, keys: Document) -> Result<(), AppError> {
+        match self.index_keys(name).await? {
+            // The order of the keys matters, unlike in the equality of documents
+            Some(existing) if existing.iter().eq(keys.iter()) => return Ok(()),
+            Some(_) => self.collection.drop_index(name).await?,
+            None => {}
+        }
+
+        let index = IndexModel::builder()
+            .keys(keys)
+            .options(
+                IndexOptions::builder()
+                    .name(name.to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+        self.collection.create_index(index).await?;
+        Ok(())
+    }
+
+    /// Ensures a unique index exists on the given keys among the documents that are not in the trash.
+    ///
+    /// The keys end with `TRASH_KEY`, so that trashed documents never conflict with each other
+    /// nor with live ones. This holds for the documents trashed along with the ones they
+    /// reference as well, and for duplicates trashed at the same time. Documents trashed before
+    /// `TRASH_KEY` existed are given theirs first.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the index.
+    /// - `keys`: The indexed keys, without `TRASH_KEY`.
+    ///
+    /// # Returns
+    /// An empty `Result` if successful, or an `AppError` if the operation fails.
+    pub async fn ensure_live_unique_index(
+        &self,
+        name: &str,
+        mut keys: Document,
+    ) -> Result<(), AppError> {
+        keys.insert(TRASH_KEY, 1);
+        self.collection
+            .update_many(
+                doc! { "deleted_at": { "$ne": null }, TRASH_KEY: { "$exists": false } },
+                vec![doc! { "$set": { TRASH_KEY: "$_id" } }],
+            )
+            .await?;
+        self.ensure_unique_index(name, keys).await
+    }
+
+    /// Ensures an index exists on the given keys of the collection.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the index.
+    /// - `keys`: The indexed keys, e.g. `{ "user": 1, "_id": -1 }`.
+    ///
+    /// # Returns
+    /// An empty `Result` if successful, or an `AppError` if the operation fails.
+    pub async fn ensure_index(&self, name: &str, keys: Document) -> Result<(), AppError> {
+        let index = IndexModel::builder()
+            .keys(keys)
+            .options(IndexOptions::builder().name(name.to_string()).build())
+            .build();
+        self.collection.create_index(index).await?;
+        Ok(())
+    }
+
+    /// Ensures a TTL index exists on a date field of the collection.
+    ///
+    /// MongoDB removes a document once the value of the field is older than `expire_after`.
+    /// An existing index with a different expiration is replaced.
+    ///
+    /// # Parameters
+    /// - `field`: The date field to index.
+    /// - `expire_after`: How long documents are kept after the date in `field`.
+    ///
+    /// # Returns
+    /// An empty `Result` if successful, or an `AppError` if the operation fails.
+    pub async fn ensure_ttl_index(
+        &self,
+        field: &str,
+        expire_after: Duration,
+    ) -> Result<(), AppError> {
+        let name = format!("{}_ttl", field);
+        let existing = self
+            .collection
+            .list_indexes()
+            .await?
+            .try_collect::<Vec<IndexModel>>()
+            .await?
+            .into_iter()
+            .find_map(|index| index.options.filter(|o| o.name.as_deref() == Some(&name)));
+
+        match existing {
+            Some(options) if options.expire_after == Some(expire_after) => return Ok(()),
+            Some(_) => self.collection.drop_index(&name).await?,
+            None => {}
+        }
+
+        let index = IndexModel::builder()
+            .keys(doc! { field: 1 })
+            .options(
+                IndexOptions::builder()
+                    .name(name)
+                    .expire_after(expire_after)
+                    .build(),
+            )
+            .build();
+        self.collection.create_index(index).await?;
+        Ok(())
     }
 }
@@ -0,0 +1,448 @@
+use crate::dto::fan_work::{
+    CreateFanWorkDto, FanWorkActionDto, FanWorkDto, FanWorkQueueItemDto, FanWorkReportDto,
+    ModerateFanWorkDto, UpdateFanWorkDto,
+};
+use crate::dto::moderation::CreateReportDto;
+use crate::dto::pagination::Pagination;
+use crate::models::fan_work::{
+    Chapter, FanWork, FanWorkAction, FanWorkActionType, FanWorkKind, FanWorkLike, FanWorkReport,
+    FanWorkStatus,
+};
+use crate::models::moderation::ReportReason;
+use crate::services::counters::CounterService;
+use crate::services::crud::{CrudService, CrudServiceImpl};
+use crate::services::db_repo::DatabaseRepository;
+use crate::services::images::ImageService;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use crate::utils::bson::parse_object_id;
+use actix_web::web::Bytes;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, from_bson, to_bson, to_document, Bson, DateTime, Document};
+use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Handles what surrounds the fan works: their images, likes, word counts, reports and the
+/// staff moderation queue.
+///
+/// `FanWork.like_count` is kept in step through the `CounterService`, and `FanWork.word_count`
+/// is recomputed from the chapters whenever they change.
+pub struct FanWorkInteractionService {
+    works: DatabaseRepository<FanWork>,
+    chapters: DatabaseRepository<Chapter>,
+    likes: DatabaseRepository<FanWorkLike>,
+    reports: DatabaseRepository<FanWorkReport>,
+    actions: DatabaseRepository<FanWorkAction>,
+    counters: Arc<CounterService>,
+    images: Arc<ImageService>,
+    hold_weight: u64,
+}
+
+impl FanWorkInteractionService {
+    /// Creates a new instance of the `FanWorkInteractionService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the fan work collections.
+    /// - `counters`: The counter service maintaining the like counters of fan works.
+    /// - `images`: The image service processing the fanart.
+    /// - `hold_weight`: The total weight of open reports above which a fan work is held.
+    pub fn new(
+        db: &Database,
+        counters: Arc<CounterService>,
+        images: Arc<ImageService>,
+        hold_weight: u64,
+    ) -> Self {
+        Self {
+            works: DatabaseRepository::new(db.collection("fan_works")),
+            chapters: DatabaseRepository::new(db.collection("fan_work_chapters")),
+            likes: DatabaseRepository::new(db.collection("fan_work_likes")),
+            reports: DatabaseRepository::new(db.collection("fan_work_reports")),
+            actions: DatabaseRepository::new(db.collection("fan_work_actions")),
+            counters,
+            images,
+            hold_weight,
+        }
+    }
+
+    /// Creates the indexes allowing a user to like and to report a fan work only once.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.likes
+            .ensure_live_unique_index("work_user", doc! { "work": 1, "user": 1 })
+            .await?;
+        self.reports
+            .ensure_unique_index("work_user", doc! { "work": 1, "user": 1 })
+            .await
+    }
+
+    /// Stores the image of a fanart, replacing the previous one.
+    ///
+    /// # Parameters
+    /// - `work`: The fanart.
+    /// - `bytes`: The content of the uploaded file.
+    ///
+    /// # Returns
+    /// The updated fanart, or an `AppError` with status 400 for a fanfiction, or the error of
+    /// the `ImageService` if the file is rejected.
+    pub async fn upload_image(
+        &self,
+        work: &FanWorkDto,
+        bytes: Bytes,
+    ) -> Result<FanWorkDto, AppError> {
+        if work.kind != FanWorkKind::Fanart {
+            return Err(AppError::from((
+                ErrorCode::WrongWorkKind,
+                "Only fanart has an image",
+            )));
+        }
+        let id = work.id.as_deref().unwrap_or_default();
+        let images = self
+            .images
+            .upload(&format!("fan-works/{}", id), bytes)
+            .await?;
+        let image = to_bson(&images).map_err(|e| AppError::from(e.to_string()))?;
+
+        let updated = match self
+            .works
+            .update_one(
+                live(doc! { "_id": parse_object_id(id)? }),
+                doc! { "$set": { "image": image } },
+            )
+            .await
+        {
+            Ok(updated) => updated,
+            Err(e) => {
+                self.images.remove(&images).await?;
+                return Err(e);
+            }
+        };
+        if let Some(previous) = &work.image {
+            self.images.remove(previous).await?;
+        }
+        Ok(FanWorkDto::from(updated))
+    }
+
+    /// Makes a user like a fan work.
+    ///
+    /// # Returns
+    /// An empty `Result`, or an `AppError` with status 409 if the user already likes it.
+    pub async fn like(&self, work: &str, user: &str) -> Result<(), AppError> {
+        let like = self
+            .likes
+            .insert_one(FanWorkLike {
+                id: None,
+                work: work.to_string(),
+                user: user.to_string(),
+                created_at: DateTime::now(),
+            })
+            .await
+            .map_err(|e| match e.is_duplicate_key() {
+                true => AppError::from((ErrorCode::LikeDuplicate, "You already like this work")),
+                false => e,
+            })?;
+        let document = to_document(&like).map_err(|e| AppError::from(e.to_string()))?;
+        self.counters
+            .apply("fan_work_likes", None, Some(&document))
+            .await
+    }
+
+    /// Makes a user stop liking a fan work.
+    ///
+    /// # Returns
+    /// Whether the user liked the fan work.
+    pub async fn unlike(&self, work: &str, user: &str) -> Result<bool, AppError> {
+        let filter = live(doc! { "work": work, "user": user });
+        let Some(document) = self.likes.find_one_document(filter.clone()).await? else {
+            return Ok(false);
+        };
+        self.likes.delete_one(filter).await?;
+        self.counters
+            .apply("fan_work_likes", Some(&document), None)
+            .await?;
+        Ok(true)
+    }
+
+    /// Returns whether a user likes a fan work.
+    pub async fn is_liked(&self, work: &str, user: &str) -> Result<bool, AppError> {
+        Ok(self
+            .likes
+            .count_documents(Some(live(doc! { "work": work, "user": user })))
+            .await?
+            > 0)
+    }
+
+    /// Recomputes the word count of a fanfiction from its chapters.
+    pub async fn refresh_word_count(&self, work: &str) -> Result<(), AppError> {
+        let pipeline = vec![
+            doc! { "$match": live(doc! { "work": work }) },
+            doc! { "$group": { "_id": Bson::Null, "words": { "$sum": "$word_count" } } },
+        ];
+        let words = self
+            .chapters
+            .aggregate(pipeline, None)
+            .await?
+            .first()
+            .map_or(0, |group| match group.get("words") {
+                Some(Bson::Int32(n)) => *n as i64,
+                Some(Bson::Int64(n)) => *n,
+                _ => 0,
+            });
+        self.works
+            .update_many(
+                doc! { "_id": parse_object_id(work)? },
+                doc! { "$set": { "word_count": words } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Reports a fan work. The fan work is held once the weight of its open reports is too high.
+    ///
+    /// # Parameters
+    /// - `work_id`: The ID of the fan work.
+    /// - `user_id`: The ID of the reporting user.
+    /// - `report`: The report.
+    ///
+    /// # Returns
+    /// The created report, or an `AppError` with status 409 if the user already reported the work.
+    pub async fn report(
+        &self,
+        work_id: &str,
+        user_id: &str,
+        report: CreateReportDto,
+    ) -> Result<FanWorkReportDto, AppError> {
+        let work = self.live_work(work_id).await?;
+        let created = self
+            .reports
+            .insert_one(FanWorkReport {
+                id: None,
+                work: work_id.to_string(),
+                user: user_id.to_string(),
+                reason: report.reason,
+                comment: report.comment,
+                weight: report.reason.weight(),
+                resolved: false,
+                created_at: DateTime::now(),
+            })
+            .await
+            .map_err(|e| match e.is_duplicate_key() {
+                true => {
+                    AppError::from((ErrorCode::ReportDuplicate, "You already reported this work"))
+                }
+                false => e,
+            })?;
+
+        let weight = self
+            .open_reports(Some(work_id))
+            .await?
+            .get(work_id)
+            .map_or(0, |(weight, _, _)| *weight);
+        if work.status == FanWorkStatus::Published && weight >= self.hold_weight {
+            self.set_work_fields(
+                work_id,
+                doc! { "status": "held", "hold_reason": "Reported by users" },
+            )
+            .await?;
+        }
+
+        Ok(FanWorkReportDto::from(created))
+    }
+
+    /// Retrieves the fan works waiting for moderation: the reported ones and the held ones,
+    /// heaviest reports first.
+    ///
+    /// # Parameters
+    /// - `page`: The page number to read.
+    /// - `limit`: The number of fan works to read per page.
+    ///
+    /// # Returns
+    /// A `Pagination` of queue items.
+    pub async fn queue(
+        &self,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<FanWorkQueueItemDto>, AppError> {
+        let reported = self.open_reports(None).await?;
+        let ids = reported
+            .keys()
+            .filter_map(|id| ObjectId::parse_str(id).ok())
+            .collect::<Vec<_>>();
+        let works = self
+            .works
+            .find(
+                Some(doc! {
+                    "deleted_at": null,
+                    "$or": [{ "_id": { "$in": ids } }, { "status": "held" }],
+                }),
+                None,
+            )
+            .await?;
+
+        let mut queue = works
+            .into_iter()
+            .map(|work| {
+                let (weight, reports, reasons) = work
+                    .id
+                    .as_ref()
+                    .and_then(|id| reported.get(id))
+                    .cloned()
+                    .unwrap_or_default();
+                FanWorkQueueItemDto {
+                    work: FanWorkDto::from(work),
+                    weight,
+                    reports,
+                    reasons,
+                }
+            })
+            .collect::<Vec<_>>();
+        queue.sort_by(|a, b| {
+            b.weight
+                .cmp(&a.weight)
+                .then_with(|| b.reports.cmp(&a.reports))
+        });
+
+        let total = queue.len() as u64;
+        let payload = queue
+            .into_iter()
+            .skip((page.saturating_sub(1) * limit) as usize)
+            .take(limit as usize)
+            .collect();
+        Ok(Pagination::new(payload, page, limit, total))
+    }
+
+    /// Applies a staff action to a fan work and resolves its open reports.
+    ///
+    /// # Parameters
+    /// - `works`: The fan work service, used to delete fan works.
+    /// - `work_id`: The ID of the fan work.
+    /// - `actor`: The ID of the staff member.
+    /// - `action`: The action to apply.
+    ///
+    /// # Returns
+    /// The logged action, or an `AppError` with status 400 if a `rerate` has no rating.
+    pub async fn moderate(
+        &self,
+        works: &CrudServiceImpl<FanWork, FanWorkDto, CreateFanWorkDto, UpdateFanWorkDto>,
+        work_id: &str,
+        actor: &str,
+        action: ModerateFanWorkDto,
+    ) -> Result<FanWorkActionDto, AppError> {
+        let work = self.live_work(work_id).await?;
+        let rating = match (action.action, action.rating) {
+            (FanWorkActionType::Rerate, None) => {
+                return Err(AppError::from((
+                    ErrorCode::RatingRequired,
+                    "A new rating is required",
+                )));
+            }
+            (FanWorkActionType::Rerate, rating) => rating,
+            _ => None,
+        };
+        match action.action {
+            FanWorkActionType::Publish => {
+                self.set_work_fields(work_id, doc! { "status": "published" })
+                    .await?;
+            }
+            FanWorkActionType::Hide => {
+                self.set_work_fields(work_id, doc! { "status": "hidden" })
+                    .await?;
+            }
+            FanWorkActionType::Delete => {
+                works.delete(work_id, actor).await?;
+            }
+            FanWorkActionType::Warn => {}
+            FanWorkActionType::Rerate => {
+                let rating = to_bson(&rating).map_err(|e| AppError::from(e.to_string()))?;
+                self.set_work_fields(work_id, doc! { "rating": rating })
+                    .await?;
+            }
+        }
+
+        self.reports
+            .update_many(
+                doc! { "work": work_id, "resolved": false },
+                doc! { "$set": { "resolved": true } },
+            )
+            .await?;
+        let logged = self
+            .actions
+            .insert_one(FanWorkAction {
+                id: None,
+                work: work_id.to_string(),
+                user: work.user,
+                actor: actor.to_string(),
+                action: action.action,
+                rating,
+                note: action.note,
+                created_at: DateTime::now(),
+            })
+            .await?;
+        Ok(FanWorkActionDto::from(logged))
+    }
+
+    /// Sums the open reports per fan work: their weight, their count and their reasons.
+    async fn open_reports(
+        &self,
+        work_id: Option<&str>,
+    ) -> Result<HashMap<String, (u64, u64, Vec<ReportReason>)>, AppError> {
+        let mut filter = doc! { "resolved": false };
+        if let Some(work_id) = work_id {
+            filter.insert("work", work_id);
+        }
+        let pipeline = vec![
+            doc! { "$match": filter },
+            doc! { "$group": {
+                "_id": "$work",
+                "weight": { "$sum": "$weight" },
+                "reports": { "$sum": 1 },
+                "reasons": { "$addToSet": "$reason" },
+            } },
+        ];
+
+        Ok(self
+            .reports
+            .aggregate(pipeline, None)
+            .await?
+            .into_iter()
+            .filter_map(|group| {
+                let work = group.get_str("_id").ok()?.to_string();
+                let count = |field: &str| match group.get(field) {
+                    Some(Bson::Int32(n)) => *n as u64,
+                    Some(Bson::Int64(n)) => *n as u64,
+                    _ => 0,
+                };
+                let reasons = group
+                    .get("reasons")
+                    .cloned()
+                    .and_then(|r| from_bson::<Vec<ReportReason>>(r).ok())
+                    .unwrap_or_default();
+                Some((work, (count("weight"), count("reports"), reasons)))
+            })
+            .collect())
+    }
+
+    /// Sets top-level fields of a fan work that is not in the trash.
+    async fn set_work_fields(&self, work_id: &str, fields: Document) -> Result<(), AppError> {
+        self.works
+            .update_one(
+                live(doc! { "_id": parse_object_id(work_id)? }),
+                doc! { "$set": fields },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Loads a fan work that is not in the trash.
+    async fn live_work(&self, work_id: &str) -> Result<FanWork, AppError> {
+        self.works
+            .find_one(live(doc! { "_id": parse_object_id(work_id)? }))
+            .await?
+            .ok_or_else(|| AppError::NotFound("Fan work not found".to_string()))
+    }
+}
+
+/// Restricts a filter to the documents that are not in the trash.
+fn live(mut filter: Document) -> Document {
+    filter.insert("deleted_at", Bson::Null);
+    filter
+}
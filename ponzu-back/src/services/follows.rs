@@ -0,0 +1,164 @@
+use crate::dto::follow::FollowDto;
+use crate::dto::pagination::Pagination;
+use crate::models::follow::Follow;
+use crate::services::counters::CounterService;
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use mongodb::bson::{doc, to_document, Bson, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use std::sync::Arc;
+
+/// Handles the follow graph between users.
+///
+/// `User.follower_count` and `User.following_count` are kept in step through the
+/// `CounterService`.
+pub struct FollowService {
+    follows: DatabaseRepository<Follow>,
+    counters: Arc<CounterService>,
+}
+
+impl FollowService {
+    /// Creates a new instance of the `FollowService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `follows` collection.
+    /// - `counters`: The counter service maintaining the follow counters of users.
+    pub fn new(db: &Database, counters: Arc<CounterService>) -> Self {
+        Self {
+            follows: DatabaseRepository::new(db.collection("follows")),
+            counters,
+        }
+    }
+
+    /// Creates the index allowing a user to follow another only once, and the one listing the
+    /// followers of a user.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.follows
+            .ensure_live_unique_index("follower_followee", doc! { "follower": 1, "followee": 1 })
+            .await?;
+        self.follows
+            .ensure_index("followee", doc! { "followee": 1, "deleted_at": 1 })
+            .await
+    }
+
+    /// Makes a user follow another.
+    ///
+    /// # Returns
+    /// The new follow, or an `AppError` with status 400 for oneself or 409 if already followed.
+    pub async fn follow(&self, follower: &str, followee: &str) -> Result<FollowDto, AppError> {
+        if follower == followee {
+            return Err(AppError::from((
+                ErrorCode::FollowSelf,
+                "You cannot follow yourself",
+            )));
+        }
+        let follow = self
+            .follows
+            .insert_one(Follow {
+                id: None,
+                follower: follower.to_string(),
+                followee: followee.to_string(),
+                created_at: DateTime::now(),
+            })
+            .await
+            .map_err(|e| match e.is_duplicate_key() {
+                true => {
+                    AppError::from((ErrorCode::FollowDuplicate, "You already follow this user"))
+                }
+                false => e,
+            })?;
+        let document = to_document(&follow).map_err(|e| AppError::from(e.to_string()))?;
+        self.counters
+            .apply("follows", None, Some(&document))
+            .await?;
+        Ok(FollowDto::from(follow))
+    }
+
+    /// Makes a user stop following another.
+    ///
+    /// # Returns
+    /// Whether the user was following the other.
+    pub async fn unfollow(&self, follower: &str, followee: &str) -> Result<bool, AppError> {
+        let filter = live(doc! { "follower": follower, "followee": followee });
+        let Some(document) = self.follows.find_one_document(filter.clone()).await? else {
+            return Ok(false);
+        };
+        self.follows.delete_one(filter).await?;
+        self.counters
+            .apply("follows", Some(&document), None)
+            .await?;
+        Ok(true)
+    }
+
+    /// Retrieves a page of the followers of a user, the most recent first.
+    pub async fn followers(
+        &self,
+        user: &str,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<FollowDto>, AppError> {
+        self.paginate(live(doc! { "followee": user }), page, limit)
+            .await
+    }
+
+    /// Retrieves a page of the users a user follows, the most recent first.
+    pub async fn following(
+        &self,
+        user: &str,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<FollowDto>, AppError> {
+        self.paginate(live(doc! { "follower": user }), page, limit)
+            .await
+    }
+
+    /// Whether a user follows another.
+    pub async fn is_following(&self, follower: &str, followee: &str) -> Result<bool, AppError> {
+        Ok(self
+            .follows
+            .find_one(live(doc! { "follower": follower, "followee": followee }))
+            .await?
+            .is_some())
+    }
+
+    /// Returns the IDs of all the users a user follows.
+    pub async fn followed_ids(&self, user: &str) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .follows
+            .find(Some(live(doc! { "follower": user })), None)
+            .await?
+            .into_iter()
+            .map(|f| f.followee)
+            .collect())
+    }
+
+    /// Reads a page of follows, the most recent first.
+    async fn paginate(
+        &self,
+        filter: Document,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<FollowDto>, AppError> {
+        let total = self.follows.count_documents(Some(filter.clone())).await?;
+        let options = FindOptions::builder()
+            .sort(doc! { "_id": -1 })
+            .skip((page.saturating_sub(1)) * limit)
+            .limit(limit as i64)
+            .build();
+        let follows = self.follows.find(Some(filter), Some(options)).await?;
+        Ok(Pagination::new(
+            follows.into_iter().map(FollowDto::from).collect(),
+            page,
+            limit,
+            total,
+        ))
+    }
+}
+
+/// Restricts a filter to the follows that are not in the trash.
+fn live(mut filter: Document) -> Document {
+    filter.insert("deleted_at", Bson::Null);
+    filter
+}
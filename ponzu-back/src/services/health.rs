@@ -0,0 +1,97 @@
+use crate::dto::health::{DependencyDto, HealthStatus, ReadinessDto};
+use crate::services::storage::Storage;
+use crate::types::app_error::AppError;
+use futures::future::join;
+use mongodb::bson::doc;
+use mongodb::Database;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The key read from the storage to check that it answers. No file is expected under it.
+const STORAGE_PROBE_KEY: &str = ".health";
+
+/// Checks the dependencies of the server, and remembers whether it is shutting down.
+pub struct HealthService {
+    db: Database,
+    storage: Arc<dyn Storage>,
+    timeout: Duration,
+    shutting_down: AtomicBool,
+}
+
+impl HealthService {
+    /// Creates a new instance of the `HealthService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database to ping.
+    /// - `storage`: The storage backend of the uploaded files.
+    /// - `timeout`: How long each dependency is given to answer.
+    pub fn new(db: &Database, storage: Arc<dyn Storage>, timeout: Duration) -> Self {
+        Self {
+            db: db.clone(),
+            storage,
+            timeout,
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+
+    /// Marks the server as shutting down, which fails every later readiness check.
+    pub fn begin_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks every dependency at once.
+    ///
+    /// # Returns
+    /// The state of each dependency. The server is ready when all of them are up and it is not
+    /// shutting down.
+    pub async fn readiness(&self) -> ReadinessDto {
+        let (database, storage) = join(
+            self.probe("database", async {
+                self.db.run_command(doc! { "ping": 1 }).await?;
+                Ok(())
+            }),
+            self.probe("storage", async {
+                self.storage.get(STORAGE_PROBE_KEY).await?;
+                Ok(())
+            }),
+        )
+        .await;
+        let dependencies = vec![database, storage];
+
+        let shutting_down = self.shutting_down.load(Ordering::SeqCst);
+        let up = !shutting_down && dependencies.iter().all(|d| d.status == HealthStatus::Up);
+        ReadinessDto {
+            status: match up {
+                true => HealthStatus::Up,
+                false => HealthStatus::Down,
+            },
+            shutting_down,
+            dependencies,
+        }
+    }
+
+    /// Runs the check of a dependency, failing it when it does not answer in time.
+    async fn probe(
+        &self,
+        name: &str,
+        check: impl Future<Output = Result<(), AppError>>,
+    ) -> DependencyDto {
+        let started = Instant::now();
+        let error = match actix_web::rt::time::timeout(self.timeout, check).await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some(format!("No answer within {} ms", self.timeout.as_millis())),
+        };
+        DependencyDto {
+            name: name.to_string(),
+            status: match error {
+                None => HealthStatus::Up,
+                Some(_) => HealthStatus::Down,
+            },
+            latency_ms: started.elapsed().as_millis() as u64,
+            error,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::models::image_mirror::MirroredImage;
+use crate::services::db_repo::DatabaseRepository;
+use crate::services::storage::Storage;
+use crate::types::app_error::AppError;
+use crate::utils::images::ImageFormat;
+use actix_web::web::Bytes;
+use colored::Colorize;
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// The path the mirrored images are served under.
+const PROXY_PATH: &str = "/api/images/proxy";
+
+/// The collections whose documents reference images of the origin.
+const MIRRORED_COLLECTIONS: [&str; 5] = ["anime", "manga", "characters", "people", "producers"];
+
+/// Keeps local copies of the images hosted by an external origin, such as the MAL CDN, so that
+/// the frontend does not depend on it.
+///
+/// A mirrored image is served under the SHA-256 of its original URL, which lets responses be
+/// rewritten without a database lookup: the hashes of the mirrored images are kept in memory
+/// and refreshed on every mirroring run.
+pub struct ImageMirrorService {
+    mirrors: DatabaseRepository<MirroredImage>,
+    db: Database,
+    storage: Arc<dyn Storage>,
+    client: reqwest::Client,
+    origin: String,
+    source: String,
+    mirrored: RwLock<HashSet<String>>,
+}
+
+impl ImageMirrorService {
+    /// Creates a new instance of the `ImageMirrorService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the mirrored images and the documents referencing them.
+    /// - `storage`: The storage backend holding the copies.
+    /// - `origin`: The URL prefix of the images to mirror, e.g. `https://cdn.myanimelist.net`.
+    /// - `source`: The URL the images are downloaded from, in place of the origin. Empty to
+    ///   download them from the origin itself.
+    pub fn new(db: &Database, storage: Arc<dyn Storage>, origin: &str, source: &str) -> Self {
+        let origin = origin.trim_end_matches('/').to_string();
+        let source = match source.trim_end_matches('/') {
+            "" => origin.clone(),
+            source => source.to_string(),
+        };
+        Self {
+            mirrors: DatabaseRepository::new(db.collection("image_mirrors")),
+            db: db.clone(),
+            storage,
+            client: reqwest::Client::new(),
+            origin,
+            source,
+            mirrored: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Creates the index allowing an image to be mirrored only once.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.mirrors
+            .ensure_unique_index("hash", doc! { "hash": 1 })
+            .await
+    }
+
+    /// Mirrors the images of the origin referenced by the catalog that are not mirrored yet.
+    ///
+    /// Images failing to download are reported on the standard error output and retried on
+    /// the next run.
+    ///
+    /// # Returns
+    /// The number of newly mirrored images.
+    pub async fn mirror_all(&self) -> Result<u64, AppError> {
+        self.load().await?;
+
+        let mut urls = HashSet::new();
+        for collection in MIRRORED_COLLECTIONS {
+            let documents = DatabaseRepository::<Document>::new(self.db.collection(collection))
+                .find_documents(
+                    doc! {},
+                    Some(
+                        FindOptions::builder()
+                            .projection(doc! { "images": 1 })
+                            .build(),
+                    ),
+                )
+                .await?;
+            for document in documents {
+                self.collect_urls(&Bson::Document(document), &mut urls);
+            }
+        }
+
+        let mut mirrored = 0;
+        for url in urls {
+            if self.is_mirrored(&hash(&url)) {
+                continue;
+            }
+            match self.mirror(&url).await {
+                Ok(()) => mirrored += 1,
+                Err(e) => eprintln!("{} {}: {}", "Image mirroring failed:".red(), url, e),
+            }
+        }
+        Ok(mirrored)
+    }
+
+    /// Downloads an image of the origin and stores a copy of it.
+    async fn mirror(&self, url: &str) -> Result<(), AppError> {
+        let hash = hash(url);
+        let download = format!("{}{}", self.source, &url[self.origin.len()..]);
+        let response = self
+            .client
+            .get(&download)
+            .send()
+            .await
+            .map_err(|e| AppError::from(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(AppError::from(format!(
+                "The origin answered {}",
+                response.status()
+            )));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::from(e.to_string()))?;
+        let format = ImageFormat::sniff(&bytes)
+            .ok_or_else(|| AppError::from("The origin did not answer with an image"))?;
+
+        let key = format!("mirror/{}.{}", hash, format.extension());
+        let mirror = MirroredImage {
+            id: None,
+            hash: hash.clone(),
+            url: url.to_string(),
+            key: key.clone(),
+            content_type: format.mime().to_string(),
+            etag: format!("{:x}", Sha256::digest(&bytes)),
+            size: bytes.len() as u64,
+            created_at: DateTime::now(),
+        };
+        self.storage.put(&key, bytes).await?;
+        match self.mirrors.insert_one(mirror).await {
+            // Another process mirrored it first, under the same key
+            Err(e) if !e.is_duplicate_key() => {
+                self.storage.delete(&key).await?;
+                return Err(e);
+            }
+            _ => {}
+        }
+        self.mirrored
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(hash);
+        Ok(())
+    }
+
+    /// Reads a mirrored image.
+    ///
+    /// # Returns
+    /// The mirrored image and its content, or `None` if no image is mirrored under the hash.
+    pub async fn get(&self, hash: &str) -> Result<Option<(MirroredImage, Bytes)>, AppError> {
+        let Some(mirror) = self.mirrors.find_one(doc! { "hash": hash }).await? else {
+            return Ok(None);
+        };
+        Ok(self
+            .storage
+            .get(&mirror.key)
+            .await?
+            .map(|bytes| (mirror, bytes)))
+    }
+
+    /// Replaces the URLs of mirrored images found in a JSON value by the URLs of their copies.
+    ///
+    /// # Returns
+    /// Whether any URL was replaced.
+    pub fn rewrite(&self, value: &mut Value) -> bool {
+        match value {
+            Value::String(url) if url.starts_with(&self.origin) => {
+                let hash = hash(url);
+                match self.is_mirrored(&hash) {
+                    true => {
+                        *url = format!("{}/{}", PROXY_PATH, hash);
+                        true
+                    }
+                    false => false,
+                }
+            }
+            Value::Array(values) => values
+                .iter_mut()
+                .map(|v| self.rewrite(v))
+                .fold(false, |rewritten, r| rewritten | r),
+            Value::Object(fields) => fields
+                .values_mut()
+                .map(|v| self.rewrite(v))
+                .fold(false, |rewritten, r| rewritten | r),
+            _ => false,
+        }
+    }
+
+    /// Whether any image is mirrored, so that responses may need rewriting.
+    pub fn has_mirrors(&self) -> bool {
+        !self
+            .mirrored
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// Starts a background job mirroring the new images at a fixed interval.
+    ///
+    /// # Parameters
+    /// - `every`: The interval between two runs. The first run happens right away.
+    pub fn spawn_mirroring(self: Arc<Self>, every: Duration) {
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(every);
+            loop {
+                interval.tick().await;
+                match self.mirror_all().await {
+                    Ok(0) => {}
+                    Ok(mirrored) => {
+                        eprintln!("{} {} image(s) mirrored", "Mirroring:".yellow(), mirrored)
+                    }
+                    Err(e) => eprintln!("{} {}", "Mirroring failed:".red(), e),
+                }
+            }
+        });
+    }
+
+    /// Loads the hashes of the images mirrored by any process.
+    pub async fn load(&self) -> Result<(), AppError> {
+        let hashes = self
+            .mirrors
+            .find_documents(
+                doc! {},
+                Some(
+                    FindOptions::builder()
+                        .projection(doc! { "hash": 1 })
+                        .build(),
+                ),
+            )
+            .await?
+            .into_iter()
+            .filter_map(|d| d.get_str("hash").ok().map(str::to_string))
+            .collect::<HashSet<_>>();
+        *self.mirrored.write().unwrap_or_else(|e| e.into_inner()) = hashes;
+        Ok(())
+    }
+
+    /// Whether the image with the given URL hash is mirrored.
+    fn is_mirrored(&self, hash: &str) -> bool {
+        self.mirrored
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(hash)
+    }
+
+    /// Collects the URLs of the origin found in a BSON value.
+    fn collect_urls(&self, value: &Bson, urls: &mut HashSet<String>) {
+        match value {
+            Bson::String(url) if url.starts_with(&self.origin) => {
+                urls.insert(url.clone());
+            }
+            Bson::Array(values) => values.iter().for_each(|v| self.collect_urls(v, urls)),
+            Bson::Document(fields) => fields.values().for_each(|v| self.collect_urls(v, urls)),
+            _ => {}
+        }
+    }
+}
+
+/// The SHA-256 of a URL, in hexadecimal.
+fn hash(url: &str) -> String {
+    format!("{:x}", Sha256::digest(url.as_bytes()))
+}
@@ -0,0 +1,253 @@
+use crate::services::storage::Storage;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use crate::types::links::{ImageUrls, Images};
+use crate::utils::images::ImageFormat;
+use actix_web::web::{self, Bytes};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ExtendedColorType, ImageDecoder, ImageReader, Limits, RgbImage};
+use mongodb::bson::oid::ObjectId;
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// The quality of the JPEG variants, from 1 to 100.
+const JPEG_QUALITY: u8 = 85;
+
+/// The variants generated for every upload, with the longest side they are scaled down to.
+/// Smaller images are never scaled up.
+const VARIANTS: [(Variant, u32); 3] = [
+    (Variant::Small, 100),
+    (Variant::Default, 300),
+    (Variant::Large, 1200),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Variant {
+    Small,
+    Default,
+    Large,
+}
+
+impl Variant {
+    fn name(&self) -> &'static str {
+        match self {
+            Variant::Small => "small",
+            Variant::Default => "image",
+            Variant::Large => "large",
+        }
+    }
+}
+
+/// The limits an uploaded image must respect.
+#[derive(Debug, Clone)]
+pub struct ImageRules {
+    /// The largest accepted file, in bytes.
+    pub max_bytes: usize,
+    /// The smallest accepted width and height, in pixels.
+    pub min_dimension: u32,
+    /// The largest accepted width and height, in pixels.
+    pub max_dimension: u32,
+}
+
+/// Turns uploaded images into the `Images` served for avatars, fan art and covers.
+///
+/// Each upload is decoded, turned upright and re-encoded as small, default and large JPEG and
+/// WebP variants, which drops the EXIF metadata of the original along the way. The variants are
+/// stored under a fresh key, so their URLs never serve stale content.
+pub struct ImageService {
+    storage: Arc<dyn Storage>,
+    public_url: String,
+    rules: ImageRules,
+}
+
+impl ImageService {
+    /// Creates a new instance of the `ImageService`.
+    ///
+    /// # Parameters
+    /// - `storage`: The storage backend holding the variants.
+    /// - `public_url`: The URL the stored files are served under, e.g. `/api/files`.
+    /// - `rules`: The limits an uploaded image must respect.
+    pub fn new(storage: Arc<dyn Storage>, public_url: &str, rules: ImageRules) -> Self {
+        Self {
+            storage,
+            public_url: public_url.trim_end_matches('/').to_string(),
+            rules,
+        }
+    }
+
+    /// Processes and stores an uploaded image.
+    ///
+    /// # Parameters
+    /// - `prefix`: The folder of the variants, e.g. `avatars/<user id>`.
+    /// - `bytes`: The content of the uploaded file.
+    ///
+    /// # Returns
+    /// The URLs of the variants, or an `AppError` with status 413 if the file is too large, 415
+    /// if it is not an accepted image, or 400 if its dimensions are out of bounds.
+    pub async fn upload(&self, prefix: &str, bytes: Bytes) -> Result<Images, AppError> {
+        if bytes.len() > self.rules.max_bytes {
+            return Err(AppError::from((
+                ErrorCode::ImageTooLarge,
+                format!("The image cannot exceed {} bytes", self.rules.max_bytes),
+            )));
+        }
+        let format = ImageFormat::sniff(&bytes).ok_or_else(|| {
+            AppError::from((
+                ErrorCode::ImageUnsupported,
+                "The file must be a PNG, JPEG, GIF or WebP image",
+            ))
+        })?;
+
+        let rules = self.rules.clone();
+        let encoded = web::block(move || encode_variants(&bytes, format, &rules))
+            .await
+            .map_err(|e| AppError::from(e.to_string()))??;
+
+        let folder = format!("{}/{}", prefix, ObjectId::new().to_hex());
+        let mut stored: Vec<String> = Vec::new();
+        for (name, extension, bytes) in encoded {
+            let key = format!("{}/{}.{}", folder, name, extension);
+            if let Err(e) = self.storage.put(&key, bytes).await {
+                for key in &stored {
+                    self.storage.delete(key).await?;
+                }
+                return Err(e);
+            }
+            stored.push(key);
+        }
+
+        let urls = |extension: &str| ImageUrls {
+            image_url: self.url(&folder, Variant::Default, extension),
+            small_image_url: self.url(&folder, Variant::Small, extension),
+            large_image_url: self.url(&folder, Variant::Large, extension),
+        };
+        Ok(Images {
+            jpg: urls("jpg"),
+            webp: urls("webp"),
+        })
+    }
+
+    /// Removes the stored variants of an image. URLs pointing elsewhere are left alone.
+    pub async fn remove(&self, images: &Images) -> Result<(), AppError> {
+        for urls in [&images.jpg, &images.webp] {
+            for url in [
+                &urls.image_url,
+                &urls.small_image_url,
+                &urls.large_image_url,
+            ] {
+                if let Some(key) = url
+                    .strip_prefix(&self.public_url)
+                    .and_then(|key| key.strip_prefix('/'))
+                {
+                    self.storage.delete(key).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The public URL of a stored variant.
+    fn url(&self, folder: &str, variant: Variant, extension: &str) -> String {
+        format!(
+            "{}/{}/{}.{}",
+            self.public_url,
+            folder,
+            variant.name(),
+            extension
+        )
+    }
+}
+
+/// Decodes an image and encodes its variants.
+///
+/// # Returns
+/// The name, extension and content of every variant.
+fn encode_variants(
+    bytes: &[u8],
+    format: ImageFormat,
+    rules: &ImageRules,
+) -> Result<Vec<(&'static str, &'static str, Bytes)>, AppError> {
+    let undecodable = |_| {
+        AppError::from((
+            ErrorCode::ImageUnsupported,
+            "The image could not be decoded",
+        ))
+    };
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(rules.max_dimension);
+    limits.max_image_height = Some(rules.max_dimension);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), decoder_format(format));
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| match e {
+        image::ImageError::Limits(_) => out_of_bounds(rules),
+        e => undecodable(e),
+    })?;
+    let (width, height) = decoder.dimensions();
+    if width.min(height) < rules.min_dimension {
+        return Err(out_of_bounds(rules));
+    }
+    let orientation = decoder.orientation().map_err(undecodable)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(undecodable)?;
+    image.apply_orientation(orientation);
+
+    let mut variants = Vec::new();
+    for (variant, side) in VARIANTS {
+        let scaled = match image.width().max(image.height()) > side {
+            true => image.resize(side, side, FilterType::Lanczos3),
+            false => image.clone(),
+        };
+
+        let mut jpg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpg, JPEG_QUALITY)
+            .encode_image(&flatten(&scaled))
+            .map_err(|e| AppError::from(e.to_string()))?;
+        variants.push((variant.name(), "jpg", Bytes::from(jpg)));
+
+        let rgba = scaled.to_rgba8();
+        let mut webp = Vec::new();
+        WebPEncoder::new_lossless(&mut webp)
+            .encode(
+                rgba.as_raw(),
+                rgba.width(),
+                rgba.height(),
+                ExtendedColorType::Rgba8,
+            )
+            .map_err(|e| AppError::from(e.to_string()))?;
+        variants.push((variant.name(), "webp", Bytes::from(webp)));
+    }
+    Ok(variants)
+}
+
+/// Lays an image over a white background, as JPEG has no transparency.
+fn flatten(image: &DynamicImage) -> RgbImage {
+    let rgba = image.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+/// The error returned for an image whose dimensions are out of bounds.
+fn out_of_bounds(rules: &ImageRules) -> AppError {
+    AppError::from((
+        ErrorCode::ImageDimensions,
+        format!(
+            "The image must be between {} and {} pixels wide and high",
+            rules.min_dimension, rules.max_dimension
+        ),
+    ))
+}
+
+/// The decoder of the `image` crate for an accepted format.
+fn decoder_format(format: ImageFormat) -> image::ImageFormat {
+    match format {
+        ImageFormat::Png => image::ImageFormat::Png,
+        ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+        ImageFormat::Gif => image::ImageFormat::Gif,
+        ImageFormat::Webp => image::ImageFormat::WebP,
+    }
+}
@@ -0,0 +1,575 @@
+use crate::dto::integrity::DanglingReferenceDto;
+use crate::services::db_repo::trash;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::{ClientSession, Database};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// What happens to the referencing documents when a referenced document is deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnDelete {
+    /// The referencing documents are deleted as well.
+    Cascade,
+    /// The reference is cleared: set to `null`, or pulled from the array.
+    Nullify,
+    /// The deletion is refused while referencing documents exist.
+    Restrict,
+}
+
+/// A declared reference from a field of one collection to the documents of others.
+///
+/// The field holds the hex ID of the referenced document, or an array of them when `many` is set.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    /// The collection holding the reference.
+    pub source: &'static str,
+    /// The field holding the referenced ID(s).
+    pub field: &'static str,
+    /// The collections the referenced document may live in.
+    pub targets: &'static [&'static str],
+    /// Whether the field is an array of IDs.
+    pub many: bool,
+    /// The behavior when a referenced document is deleted.
+    pub on_delete: OnDelete,
+}
+
+/// The outcome of a deletion, cascades included.
+#[derive(Debug, Default)]
+pub struct Deletion {
+    /// The number of documents deleted from the requested collection.
+    pub count: u64,
+    /// The deleted documents as they were before the deletion, paired with their collection.
+    pub documents: Vec<(String, Document)>,
+}
+
+/// Enforces the declared references between collections.
+///
+/// Writes are validated against the referenced collections, and deletions are propagated to
+/// the referencing documents according to their `OnDelete` behavior, within a transaction.
+pub struct IntegrityService {
+    db: Database,
+    references: Vec<Reference>,
+    transactions: bool,
+}
+
+impl IntegrityService {
+    /// Creates a new instance of the `IntegrityService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database the collections live in.
+    /// - `references`: The declared references.
+    /// - `transactions`: Whether deletions run in a transaction. Requires a replica set.
+    pub fn new(db: Database, references: Vec<Reference>, transactions: bool) -> Self {
+        Self {
+            db,
+            references,
+            transactions,
+        }
+    }
+
+    /// Checks that the references held by a document point at existing documents.
+    ///
+    /// Only the referencing fields present in `document` are checked, so partial updates can be validated too.
+    ///
+    /// # Parameters
+    /// - `source`: The collection the document is written to.
+    /// - `document`: The document, or the fields being set.
+    ///
+    /// # Returns
+    /// An `AppError` with status 422 if a reference points at nothing.
+    pub async fn validate(&self, source: &str, document: &Document) -> Result<(), AppError> {
+        for reference in self.references.iter().filter(|r| r.source == source) {
+            let Some(value) = document.get(reference.field) else {
+                continue;
+            };
+            let ids = referenced_ids(value);
+            let existing = self.existing(reference.targets, &ids).await?;
+            if let Some(missing) = ids.iter().find(|id| !existing.contains(*id)) {
+                return Err(AppError::from((
+                    ErrorCode::ReferenceMissing,
+                    format!(
+                        "{} references a missing document: {}",
+                        reference.field, missing
+                    )
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the documents matching a filter and applies the `OnDelete` behavior of the
+    /// references pointing at them.
+    ///
+    /// # Parameters
+    /// - `collection`: The collection to delete from.
+    /// - `filter`: The documents to delete.
+    /// - `actor`: The ID of the user deleting the documents.
+    /// - `soft`: Whether documents are moved to the trash instead of being removed.
+    ///
+    /// # Returns
+    /// The documents deleted from `collection` and the ones the deletion cascaded to, so that
+    /// their counters can be adjusted, or an `AppError` with status 409 if a restricting
+    /// reference exists.
+    pub async fn delete(
+        &self,
+        collection: &str,
+        filter: Document,
+        actor: &str,
+        soft: bool,
+    ) -> Result<Deletion, AppError> {
+        let mut session = self.db.client().start_session().await?;
+        if self.transactions {
+            session.start_transaction().await?;
+        }
+
+        match self
+            .delete_in_session(&mut session, collection, filter, actor, soft)
+            .await
+        {
+            Ok(deleted) => {
+                if self.transactions {
+                    session.commit_transaction().await?;
+                }
+                Ok(deleted)
+            }
+            Err(e) => {
+                if self.transactions {
+                    let _ = session.abort_transaction().await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Reports the references that point at missing or trashed documents.
+    ///
+    /// # Returns
+    /// A `Vec` with an entry for each declared reference that has dangling IDs.
+    pub async fn dangling(&self) -> Result<Vec<DanglingReferenceDto>, AppError> {
+        let mut report = Vec::new();
+        for reference in &self.references {
+            let source = self.db.collection::<Document>(reference.source);
+            let ids = source
+                .distinct(reference.field, doc! { "deleted_at": null })
+                .await?
+                .iter()
+                .flat_map(referenced_ids)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            let existing = self.existing(reference.targets, &ids).await?;
+            let mut missing = ids
+                .into_iter()
+                .filter(|id| !existing.contains(id))
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                continue;
+            }
+            missing.sort();
+
+            let documents = source
+                .count_documents(doc! { reference.field: { "$in": &missing }, "deleted_at": null })
+                .await?;
+            report.push(DanglingReferenceDto {
+                source: reference.source.to_string(),
+                field: reference.field.to_string(),
+                targets: reference.targets.iter().map(|t| t.to_string()).collect(),
+                missing,
+                documents,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Deletes documents and propagates the deletion using the given session.
+    ///
+    /// Every restricting reference of the cascade is checked before anything is written, so a
+    /// refused deletion leaves the documents untouched even without a transaction.
+    async fn delete_in_session(
+        &self,
+        session: &mut ClientSession,
+        collection: &str,
+        filter: Document,
+        actor: &str,
+        soft: bool,
+    ) -> Result<Deletion, AppError> {
+        let plan = self.plan(session, collection, filter).await?;
+        self.check_restrictions(session, &plan).await?;
+
+        let mut deletion = Deletion::default();
+        for (index, (name, documents)) in plan.iter().enumerate() {
+            let ids = object_ids(documents);
+            let hex = ids.iter().map(|o| o.to_hex()).collect::<Vec<_>>();
+            for reference in self
+                .references
+                .iter()
+                .filter(|r| r.on_delete == OnDelete::Nullify && r.targets.contains(name))
+            {
+                let referencing = doc! { reference.field: { "$in": &hex }, "deleted_at": null };
+                let update = match reference.many {
+                    true => doc! { "$pull": { reference.field: { "$in": &hex } } },
+                    false => doc! { "$set": { reference.field: null } },
+                };
+                self.db
+                    .collection::<Document>(reference.source)
+                    .update_many(referencing, update)
+                    .session(&mut *session)
+                    .await?;
+            }
+
+            let target = self.db.collection::<Document>(name);
+            let matched = doc! { "_id": { "$in": &ids } };
+            let count = match soft {
+                true => {
+                    target
+                        .update_many(matched, trash(actor))
+                        .session(&mut *session)
+                        .await?
+                        .modified_count
+                }
+                false => {
+                    target
+                        .delete_many(matched)
+                        .session(&mut *session)
+                        .await?
+                        .deleted_count
+                }
+            };
+            if index == 0 {
+                deletion.count = count;
+            }
+        }
+
+        deletion.documents = plan
+            .into_iter()
+            .flat_map(|(name, documents)| documents.into_iter().map(move |d| (name.to_string(), d)))
+            .collect();
+        Ok(deletion)
+    }
+
+    /// Finds the documents a deletion cascades to, breadth first, without writing anything.
+    ///
+    /// # Returns
+    /// The live documents to delete, batched by collection and starting with the requested ones.
+    async fn plan<'a>(
+        &self,
+        session: &mut ClientSession,
+        collection: &'a str,
+        filter: Document,
+    ) -> Result<Vec<(&'a str, Vec<Document>)>, AppError> {
+        let mut queue = VecDeque::from([(collection, filter)]);
+        let mut seen = HashSet::new();
+        let mut plan = Vec::new();
+
+        while let Some((name, filter)) = queue.pop_front() {
+            let documents = self
+                .db
+                .collection::<Document>(name)
+                .find(doc! { "$and": [filter, { "deleted_at": null }] })
+                .session(&mut *session)
+                .await?
+                .stream(&mut *session)
+                .try_collect::<Vec<_>>()
+                .await?
+                .into_iter()
+                .filter(|d| {
+                    d.get_object_id("_id")
+                        .is_ok_and(|id| seen.insert((name, id)))
+                })
+                .collect::<Vec<_>>();
+            if documents.is_empty() {
+                continue;
+            }
+
+            let hex = object_ids(&documents)
+                .iter()
+                .map(|o| o.to_hex())
+                .collect::<Vec<_>>();
+            for reference in self
+                .references
+                .iter()
+                .filter(|r| r.on_delete == OnDelete::Cascade && r.targets.contains(&name))
+            {
+                let referencing = doc! { reference.field: { "$in": &hex }, "deleted_at": null };
+                queue.push_back((reference.source, referencing));
+            }
+            plan.push((name, documents));
+        }
+
+        Ok(plan)
+    }
+
+    /// Refuses a planned deletion while documents left out of it hold a restricting reference.
+    ///
+    /// # Returns
+    /// An `AppError` with status 409 if such a document exists.
+    async fn check_restrictions(
+        &self,
+        session: &mut ClientSession,
+        plan: &[(&str, Vec<Document>)],
+    ) -> Result<(), AppError> {
+        let mut planned = HashMap::<&str, Vec<ObjectId>>::new();
+        for (name, documents) in plan {
+            planned
+                .entry(name)
+                .or_default()
+                .extend(object_ids(documents));
+        }
+
+        for (name, documents) in plan {
+            let hex = object_ids(documents)
+                .iter()
+                .map(|o| o.to_hex())
+                .collect::<Vec<_>>();
+            for reference in self
+                .references
+                .iter()
+                .filter(|r| r.on_delete == OnDelete::Restrict && r.targets.contains(name))
+            {
+                let deleted = planned.get(reference.source).cloned().unwrap_or_default();
+                let referencing = doc! {
+                    reference.field: { "$in": &hex },
+                    "_id": { "$nin": deleted },
+                    "deleted_at": null,
+                };
+                let count = self
+                    .db
+                    .collection::<Document>(reference.source)
+                    .count_documents(referencing)
+                    .session(&mut *session)
+                    .await?;
+                if count > 0 {
+                    return Err(AppError::from((
+                        ErrorCode::StillReferenced,
+                        format!(
+                            "Still referenced by {} document(s) in {}",
+                            count, reference.source
+                        )
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns which of the given IDs exist, not trashed, in any of the target collections.
+    async fn existing(
+        &self,
+        targets: &[&str],
+        ids: &[String],
+    ) -> Result<HashSet<String>, AppError> {
+        let oids = ids
+            .iter()
+            .filter_map(|id| ObjectId::parse_str(id).ok())
+            .collect::<Vec<_>>();
+        let mut existing = HashSet::new();
+        if oids.is_empty() {
+            return Ok(existing);
+        }
+
+        for target in targets {
+            let found = self
+                .db
+                .collection::<Document>(target)
+                .distinct("_id", doc! { "_id": { "$in": &oids }, "deleted_at": null })
+                .await?;
+            existing.extend(
+                found
+                    .iter()
+                    .filter_map(Bson::as_object_id)
+                    .map(|o| o.to_hex()),
+            );
+        }
+        Ok(existing)
+    }
+}
+
+/// Returns the IDs of the given documents.
+fn object_ids(documents: &[Document]) -> Vec<ObjectId> {
+    documents
+        .iter()
+        .filter_map(|d| d.get_object_id("_id").ok())
+        .collect()
+}
+
+/// Extracts the referenced IDs held by a field value. `null` references nothing.
+fn referenced_ids(value: &Bson) -> Vec<String> {
+    match value {
+        Bson::String(id) => vec![id.clone()],
+        Bson::ObjectId(oid) => vec![oid.to_hex()],
+        Bson::Array(values) => values.iter().flat_map(referenced_ids).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntegrityService, OnDelete, Reference};
+    use crate::types::app_error::AppError;
+    use crate::types::error_response::ErrorCode;
+    use crate::utils::testing::test_database;
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::{doc, Document};
+    use mongodb::Database;
+
+    fn comments_of_reviews(db: &Database) -> IntegrityService {
+        let references = vec![
+            Reference {
+                source: "comments",
+                field: "review",
+                targets: &["reviews"],
+                many: false,
+                on_delete: OnDelete::Cascade,
+            },
+            Reference {
+                source: "comments",
+                field: "parent",
+                targets: &["comments"],
+                many: false,
+                on_delete: OnDelete::Cascade,
+            },
+        ];
+        IntegrityService::new(db.clone(), references, false)
+    }
+
+    #[actix_web::test]
+    async fn a_deletion_reports_every_document_it_cascaded_to() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let integrity = comments_of_reviews(&db);
+        let reviews = db.collection::<Document>("reviews");
+        let comments = db.collection::<Document>("comments");
+        let review = reviews
+            .insert_one(doc! { "title": "deleted" })
+            .await
+            .unwrap()
+            .inserted_id;
+        reviews.insert_one(doc! { "title": "kept" }).await.unwrap();
+        let review = review.as_object_id().unwrap().to_hex();
+        let comment = comments
+            .insert_one(doc! { "review": &review, "parent": null })
+            .await
+            .unwrap()
+            .inserted_id;
+        let parent = comment.as_object_id().unwrap().to_hex();
+        comments
+            .insert_one(doc! { "review": &review, "parent": &parent })
+            .await
+            .unwrap();
+        comments
+            .insert_one(doc! { "review": "elsewhere", "parent": null })
+            .await
+            .unwrap();
+
+        let deletion = integrity
+            .delete("reviews", doc! { "title": "deleted" }, "staff", true)
+            .await
+            .unwrap();
+        assert_eq!(deletion.count, 1);
+        let collections = deletion
+            .documents
+            .iter()
+            .map(|(collection, _)| collection.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(collections, ["reviews", "comments", "comments"]);
+        assert_eq!(
+            comments
+                .count_documents(doc! { "deleted_at": null })
+                .await
+                .unwrap(),
+            1
+        );
+
+        // Trashed documents are not deleted, nor reported, twice
+        let again = integrity
+            .delete("reviews", doc! { "title": "deleted" }, "staff", false)
+            .await
+            .unwrap();
+        assert_eq!(again.count, 0);
+        assert!(again.documents.is_empty());
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn a_restriction_deep_in_the_cascade_leaves_everything_untouched() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let reference = |source, field, targets, on_delete| Reference {
+            source,
+            field,
+            targets,
+            many: false,
+            on_delete,
+        };
+        let references = vec![
+            reference("comments", "review", &["reviews"], OnDelete::Cascade),
+            reference("users", "pinned", &["reviews"], OnDelete::Nullify),
+            reference("flags", "comment", &["comments"], OnDelete::Restrict),
+            reference("flags", "review", &["reviews"], OnDelete::Cascade),
+        ];
+        let integrity = IntegrityService::new(db.clone(), references, false);
+        let reviews = db.collection::<Document>("reviews");
+        let comments = db.collection::<Document>("comments");
+        let users = db.collection::<Document>("users");
+        let flags = db.collection::<Document>("flags");
+
+        let review = ObjectId::new();
+        reviews.insert_one(doc! { "_id": review }).await.unwrap();
+        let review = review.to_hex();
+        let comment = ObjectId::new();
+        comments
+            .insert_one(doc! { "_id": comment, "review": &review })
+            .await
+            .unwrap();
+        users.insert_one(doc! { "pinned": &review }).await.unwrap();
+        flags
+            .insert_one(doc! { "comment": comment.to_hex(), "review": "elsewhere" })
+            .await
+            .unwrap();
+
+        let refused = integrity
+            .delete("reviews", doc! {}, "staff", true)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            refused,
+            AppError::HttpError(ErrorCode::StillReferenced, _)
+        ));
+        let live = doc! { "deleted_at": null };
+        assert_eq!(reviews.count_documents(live.clone()).await.unwrap(), 1);
+        assert_eq!(comments.count_documents(live).await.unwrap(), 1);
+        assert_eq!(
+            users
+                .count_documents(doc! { "pinned": &review })
+                .await
+                .unwrap(),
+            1
+        );
+
+        // A restricting document deleted by the same cascade does not refuse it
+        flags
+            .update_many(doc! {}, doc! { "$set": { "review": &review } })
+            .await
+            .unwrap();
+        let deletion = integrity
+            .delete("reviews", doc! {}, "staff", true)
+            .await
+            .unwrap();
+        assert_eq!(deletion.documents.len(), 3);
+        assert_eq!(
+            users
+                .count_documents(doc! { "pinned": null })
+                .await
+                .unwrap(),
+            1
+        );
+
+        db.drop().await.unwrap();
+    }
+}
@@ -1,2 +1,19 @@
+pub mod activity;
 pub mod db_repo;
+pub mod clubs;
+pub mod content_policy;
+pub mod counters;
 pub mod crud;
+pub mod fan_works;
+pub mod follows;
+pub mod health;
+pub mod image_mirror;
+pub mod images;
+pub mod integrity;
+pub mod moderation;
+pub mod notifications;
+pub mod profiles;
+pub mod reactions;
+pub mod read_markers;
+pub mod storage;
+pub mod typed_fields;
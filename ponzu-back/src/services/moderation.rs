@@ -0,0 +1,788 @@
+use crate::dto::moderation::{
+    CreateReportDto, ModerateReviewDto, ModerationActionDto, ModerationQueueItemDto, ReportDto,
+};
+use crate::dto::pagination::Pagination;
+use crate::dto::review::{CreateReviewDto, ReviewDto, UpdateReviewDto};
+use crate::models::moderation::{
+    ModerationAction, ModerationActionType, ReportReason, ReviewReport,
+};
+use crate::models::notification::NotificationKind;
+use crate::models::review::{Review, ReviewStatus};
+use crate::services::crud::{CrudService, CrudServiceImpl};
+use crate::services::db_repo::DatabaseRepository;
+use crate::services::notifications::NotificationService;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use crate::utils::bson::parse_object_id;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, from_bson, Bson, DateTime, Document};
+use mongodb::Database;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The rules deciding when a review is held for moderation.
+#[derive(Debug, Clone)]
+pub struct ModerationRules {
+    /// Lowercase words a review may not contain.
+    pub words: HashSet<String>,
+    /// How many reviews a user may post within `window` before the next one is held.
+    pub rapid_posts: u64,
+    /// The window of the rapid-posting heuristic.
+    pub window: Duration,
+    /// The total weight of open reports above which a review is held.
+    pub hold_weight: u64,
+}
+
+impl ModerationRules {
+    /// Parses a word list, one word per line. Blank lines and lines starting with `#` are skipped.
+    ///
+    /// # Parameters
+    /// - `list`: The content of the word list.
+    pub fn parse_words(list: &str) -> HashSet<String> {
+        list.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// Returns the first word of a text found in the word list, if any.
+    ///
+    /// # Parameters
+    /// - `text`: The text to check.
+    pub fn flagged_word(&self, text: &str) -> Option<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .find(|word| self.words.contains(word))
+    }
+}
+
+/// Handles review reports, the staff moderation queue and automatic holds.
+pub struct ModerationService {
+    rules: ModerationRules,
+    reports: DatabaseRepository<ReviewReport>,
+    actions: DatabaseRepository<ModerationAction>,
+    reviews: DatabaseRepository<Review>,
+}
+
+impl ModerationService {
+    /// Creates a new instance of the `ModerationService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the reviews and the moderation collections.
+    /// - `rules`: The rules deciding when a review is held.
+    pub fn new(db: &Database, rules: ModerationRules) -> Self {
+        Self {
+            rules,
+            reports: DatabaseRepository::new(db.collection("review_reports")),
+            actions: DatabaseRepository::new(db.collection("moderation_actions")),
+            reviews: DatabaseRepository::new(db.collection("reviews")),
+        }
+    }
+
+    /// Creates the index allowing a user to report a review only once.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.reports
+            .ensure_live_unique_index("review_user", doc! { "review": 1, "user": 1 })
+            .await
+    }
+
+    /// Holds a new review when it trips the word list or the rapid-posting heuristic.
+    ///
+    /// # Parameters
+    /// - `review`: The review about to be created. Its status is set in place.
+    pub async fn screen(&self, review: &mut CreateReviewDto) -> Result<(), AppError> {
+        let reason = match self.rules.flagged_word(&review.review) {
+            Some(word) => Some(flagged_reason(&word)),
+            None => {
+                let recent = self
+                    .reviews
+                    .count_documents(Some(doc! {
+                        "user": &review.user,
+                        "_id": { "$gte": object_id_since(self.rules.window) },
+                    }))
+                    .await?;
+                (recent >= self.rules.rapid_posts).then(|| {
+                    format!(
+                        "Posted {} reviews within {} minutes",
+                        recent + 1,
+                        self.rules.window.as_secs() / 60
+                    )
+                })
+            }
+        };
+
+        if let Some(reason) = reason {
+            review.status = ReviewStatus::Held;
+            review.hold_reason = Some(reason);
+        }
+        Ok(())
+    }
+
+    /// Screens the edit of a review.
+    ///
+    /// An edited body tripping the word list holds the review again. The rapid-posting heuristic
+    /// only applies to new reviews.
+    ///
+    /// # Parameters
+    /// - `review`: The review being edited.
+    /// - `edit`: The edit. Its status is set in place.
+    /// - `staff`: Whether a staff member makes the edit.
+    ///
+    /// # Returns
+    /// An `AppError` with status 403 if the author clears a spoiler flag set by the staff.
+    pub fn screen_edit(
+        &self,
+        review: &ReviewDto,
+        edit: &mut UpdateReviewDto,
+        staff: bool,
+    ) -> Result<(), AppError> {
+        if review.spoiler_locked && !staff && edit.is_spoiler == Some(false) {
+            return Err(AppError::from((
+                ErrorCode::SpoilerLocked,
+                "The staff marked this review as a spoiler",
+            )));
+        }
+
+        let flagged = edit
+            .review
+            .as_deref()
+            .and_then(|text| self.rules.flagged_word(text));
+        if let Some(word) = flagged {
+            edit.status = Some(ReviewStatus::Held);
+            edit.hold_reason = Some(flagged_reason(&word));
+        }
+        Ok(())
+    }
+
+    /// Reports a review. The review is held once the weight of its open reports is too high.
+    ///
+    /// # Parameters
+    /// - `review_id`: The ID of the review.
+    /// - `user_id`: The ID of the reporting user.
+    /// - `report`: The report.
+    ///
+    /// # Returns
+    /// The created report, or an `AppError` with status 409 if the user already reported the review.
+    pub async fn report(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        report: CreateReportDto,
+    ) -> Result<ReportDto, AppError> {
+        let review = self.live_review(review_id).await?;
+        let created = self
+            .reports
+            .insert_one(ReviewReport {
+                id: None,
+                review: review_id.to_string(),
+                user: user_id.to_string(),
+                reason: report.reason,
+                comment: report.comment,
+                weight: report.reason.weight(),
+                resolved: false,
+                created_at: DateTime::now(),
+            })
+            .await
+            .map_err(|e| match e.is_duplicate_key() {
+                true => AppError::from((
+                    ErrorCode::ReportDuplicate,
+                    "You already reported this review",
+                )),
+                false => e,
+            })?;
+
+        let weight = self
+            .open_reports(Some(review_id))
+            .await?
+            .get(review_id)
+            .map_or(0, |(weight, _, _)| *weight);
+        if review.status == ReviewStatus::Published && weight >= self.rules.hold_weight {
+            self.set_review_fields(
+                review_id,
+                doc! { "status": "held", "hold_reason": "Reported by users" },
+            )
+            .await?;
+        }
+
+        Ok(ReportDto::from(created))
+    }
+
+    /// Retrieves the reviews waiting for moderation: the reported ones and the held ones,
+    /// heaviest reports first.
+    ///
+    /// # Parameters
+    /// - `page`: The page number to read.
+    /// - `limit`: The number of reviews to read per page.
+    ///
+    /// # Returns
+    /// A `Pagination` of queue items.
+    pub async fn queue(
+        &self,
+        page: u64,
+        limit: u64,
+    ) -> Result<Pagination<ModerationQueueItemDto>, AppError> {
+        let reported = self.open_reports(None).await?;
+        let ids = reported
+            .keys()
+            .filter_map(|id| ObjectId::parse_str(id).ok())
+            .collect::<Vec<_>>();
+        let reviews = self
+            .reviews
+            .find(
+                Some(doc! {
+                    "deleted_at": null,
+                    "$or": [{ "_id": { "$in": ids } }, { "status": "held" }],
+                }),
+                None,
+            )
+            .await?;
+
+        let mut queue = reviews
+            .into_iter()
+            .map(|review| {
+                let (weight, reports, reasons) = review
+                    .id
+                    .as_ref()
+                    .and_then(|id| reported.get(id))
+                    .cloned()
+                    .unwrap_or_default();
+                ModerationQueueItemDto {
+                    review: ReviewDto::from(review),
+                    weight,
+                    reports,
+                    reasons,
+                }
+            })
+            .collect::<Vec<_>>();
+        queue.sort_by(|a, b| {
+            b.weight
+                .cmp(&a.weight)
+                .then_with(|| b.reports.cmp(&a.reports))
+        });
+
+        let total = queue.len() as u64;
+        let payload = queue
+            .into_iter()
+            .skip((page.saturating_sub(1) * limit) as usize)
+            .take(limit as usize)
+            .collect();
+        Ok(Pagination::new(payload, page, limit, total))
+    }
+
+    /// Applies a staff action to a review and resolves its open reports.
+    ///
+    /// # Parameters
+    /// - `reviews`: The review service, used to delete reviews.
+    /// - `notifications`: The notification service, used to warn the author.
+    /// - `review_id`: The ID of the review.
+    /// - `actor`: The ID of the staff member.
+    /// - `action`: The action to apply.
+    ///
+    /// # Returns
+    /// The logged action.
+    pub async fn moderate(
+        &self,
+        reviews: &CrudServiceImpl<Review, ReviewDto, CreateReviewDto, UpdateReviewDto>,
+        notifications: &NotificationService,
+        review_id: &str,
+        actor: &str,
+        action: ModerateReviewDto,
+    ) -> Result<ModerationActionDto, AppError> {
+        let review = self.live_review(review_id).await?;
+        match action.action {
+            ModerationActionType::Publish => {
+                self.set_review_fields(review_id, doc! { "status": "published" })
+                    .await?;
+            }
+            ModerationActionType::Hide => {
+                self.set_review_fields(review_id, doc! { "status": "hidden" })
+                    .await?;
+            }
+            ModerationActionType::Delete => {
+                reviews.delete(review_id, actor).await?;
+            }
+            ModerationActionType::Warn => {
+                notifications
+                    .notify(
+                        &review.user,
+                        NotificationKind::Warning,
+                        Some(actor),
+                        review_id,
+                        action.note.clone(),
+                    )
+                    .await?;
+            }
+            ModerationActionType::MarkSpoiler => {
+                self.set_review_fields(
+                    review_id,
+                    doc! { "is_spoiler": true, "spoiler_locked": true },
+                )
+                .await?;
+            }
+        }
+
+        self.reports
+            .update_many(
+                doc! { "review": review_id, "resolved": false },
+                doc! { "$set": { "resolved": true } },
+            )
+            .await?;
+        let logged = self
+            .actions
+            .insert_one(ModerationAction {
+                id: None,
+                review: review_id.to_string(),
+                user: review.user,
+                actor: actor.to_string(),
+                action: action.action,
+                note: action.note,
+                created_at: DateTime::now(),
+            })
+            .await?;
+        Ok(ModerationActionDto::from(logged))
+    }
+
+    /// Sums the open reports per review: their weight, their count and their reasons.
+    async fn open_reports(
+        &self,
+        review_id: Option<&str>,
+    ) -> Result<HashMap<String, (u64, u64, Vec<ReportReason>)>, AppError> {
+        let mut filter = doc! { "resolved": false, "deleted_at": null };
+        if let Some(review_id) = review_id {
+            filter.insert("review", review_id);
+        }
+        let pipeline = vec![
+            doc! { "$match": filter },
+            doc! { "$group": {
+                "_id": "$review",
+                "weight": { "$sum": "$weight" },
+                "reports": { "$sum": 1 },
+                "reasons": { "$addToSet": "$reason" },
+            } },
+        ];
+
+        Ok(self
+            .reports
+            .aggregate(pipeline, None)
+            .await?
+            .into_iter()
+            .filter_map(|group| {
+                let review = group.get_str("_id").ok()?.to_string();
+                let count = |field: &str| match group.get(field) {
+                    Some(Bson::Int32(n)) => *n as u64,
+                    Some(Bson::Int64(n)) => *n as u64,
+                    _ => 0,
+                };
+                let reasons = group
+                    .get("reasons")
+                    .cloned()
+                    .and_then(|r| from_bson::<Vec<ReportReason>>(r).ok())
+                    .unwrap_or_default();
+                Some((review, (count("weight"), count("reports"), reasons)))
+            })
+            .collect())
+    }
+
+    /// Sets top-level fields of a review that is not in the trash.
+    async fn set_review_fields(&self, review_id: &str, fields: Document) -> Result<(), AppError> {
+        self.reviews
+            .update_one(
+                doc! { "_id": parse_object_id(review_id)?, "deleted_at": null },
+                doc! { "$set": fields },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Loads a review that is not in the trash.
+    async fn live_review(&self, review_id: &str) -> Result<Review, AppError> {
+        self.reviews
+            .find_one(doc! { "_id": parse_object_id(review_id)?, "deleted_at": null })
+            .await?
+            .ok_or_else(|| AppError::NotFound("Review not found".to_string()))
+    }
+}
+
+/// The hold reason of a review containing a flagged word.
+fn flagged_reason(word: &str) -> String {
+    format!("Contains the flagged word \"{}\"", word)
+}
+
+/// Returns the smallest `ObjectId` generated within the given duration, to filter documents by creation time.
+fn object_id_since(duration: Duration) -> ObjectId {
+    let since = SystemTime::now()
+        .checked_sub(duration)
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as u32);
+    let mut bytes = [0u8; 12];
+    bytes[..4].copy_from_slice(&since.to_be_bytes());
+    ObjectId::from_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{object_id_since, ModerationRules, ModerationService};
+    use crate::dto::moderation::{CreateReportDto, ModerateReviewDto};
+    use crate::dto::review::{CreateReviewDto, ReactionsDto, ReviewDto, UpdateReviewDto};
+    use crate::models::moderation::{ModerationActionType, ReportReason};
+    use crate::models::review::{Reactions, Review, ReviewStatus};
+    use crate::models::user::User;
+    use crate::services::crud::{CrudService, CrudServiceImpl};
+    use crate::services::db_repo::DatabaseRepository;
+    use crate::services::notifications::NotificationService;
+    use crate::types::app_error::AppError;
+    use crate::types::error_response::ErrorCode;
+    use crate::utils::testing::test_database;
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::{doc, to_document, DateTime, Document};
+    use mongodb::Client;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn rules(words: &str) -> ModerationRules {
+        ModerationRules {
+            words: ModerationRules::parse_words(words),
+            rapid_posts: 5,
+            window: Duration::from_secs(600),
+            hold_weight: 10,
+        }
+    }
+
+    /// A service whose database is never reached, as the client connects lazily.
+    async fn service(words: &str) -> ModerationService {
+        let client = Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .unwrap();
+        ModerationService::new(&client.database("ponzu_test"), rules(words))
+    }
+
+    fn review(spoiler_locked: bool) -> ReviewDto {
+        ReviewDto {
+            id: Some(ObjectId::new().to_hex()),
+            mal_id: 1,
+            url: String::new(),
+            r#type: "anime".to_string(),
+            reactions: ReactionsDto {
+                overall: 0,
+                nice: 0,
+                love_it: 0,
+                funny: 0,
+                confusing: 0,
+                informative: 0,
+                well_written: 0,
+                creative: 0,
+            },
+            date: DateTime::now(),
+            review: "A fine show".to_string(),
+            score: 8,
+            tags: Vec::new(),
+            is_spoiler: spoiler_locked,
+            spoiler_locked,
+            is_preliminary: false,
+            episodes_watched: None,
+            entry: ObjectId::new().to_hex(),
+            user: "author".to_string(),
+            status: ReviewStatus::Published,
+            hold_reason: None,
+            created_at: None,
+            edited_at: None,
+            edit_count: 0,
+            comment_count: 0,
+        }
+    }
+
+    fn edit(body: serde_json::Value) -> UpdateReviewDto {
+        serde_json::from_value(body).unwrap()
+    }
+
+    /// A new review by the given user.
+    fn create(user: &str, text: &str) -> CreateReviewDto {
+        let mut dto: CreateReviewDto = serde_json::from_value(json!({
+            "mal_id": 1,
+            "url": "",
+            "type": "anime",
+            "review": text,
+            "score": 8,
+            "tags": [],
+            "is_spoiler": false,
+            "is_preliminary": false,
+            "entry": ObjectId::new().to_hex(),
+        }))
+        .unwrap();
+        dto.user = user.to_string();
+        dto
+    }
+
+    /// A stored, published review.
+    fn stored_review(id: ObjectId) -> Document {
+        let mut review = to_document(&Review {
+            id: None,
+            mal_id: 1,
+            url: String::new(),
+            r#type: "anime".to_string(),
+            reactions: Reactions::default(),
+            date: DateTime::now(),
+            review: "A fine show".to_string(),
+            score: 8,
+            tags: Vec::new(),
+            is_spoiler: false,
+            spoiler_locked: false,
+            is_preliminary: false,
+            episodes_watched: None,
+            entry: ObjectId::new().to_hex(),
+            user: "author".to_string(),
+            status: ReviewStatus::Published,
+            hold_reason: None,
+            created_at: None,
+            edited_at: None,
+            edit_count: 0,
+            comment_count: 0,
+        })
+        .unwrap();
+        review.insert("_id", id);
+        review
+    }
+
+    /// Builds an `ObjectId` generated at the given number of seconds since the epoch.
+    fn object_id_at(secs: u32) -> ObjectId {
+        let mut bytes = [0xffu8; 12];
+        bytes[..4].copy_from_slice(&secs.to_be_bytes());
+        ObjectId::from_bytes(bytes)
+    }
+
+    fn now() -> u32 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as u32
+    }
+
+    #[test]
+    fn word_lists_skip_comments_and_blank_lines() {
+        let words =
+            ModerationRules::parse_words("# slurs\n\n  Spam \nscam\n  # not a word\nSCAM\n");
+        assert_eq!(words.len(), 2);
+        assert!(words.contains("spam"));
+        assert!(words.contains("scam"));
+    }
+
+    #[test]
+    fn flagged_words_match_whole_words_in_any_case() {
+        let rules = rules("spam");
+        assert_eq!(
+            rules.flagged_word("Buy SPAM, now!"),
+            Some("spam".to_string())
+        );
+        assert_eq!(rules.flagged_word("(spam)"), Some("spam".to_string()));
+        assert_eq!(rules.flagged_word("spammer and spamspam"), None);
+        assert_eq!(rules.flagged_word(""), None);
+    }
+
+    #[test]
+    fn object_ids_are_bounded_by_their_creation_time() {
+        let since = object_id_since(Duration::from_secs(3600));
+        assert!(ObjectId::new() >= since);
+        assert!(object_id_at(now() - 60) >= since);
+        assert!(object_id_at(now() - 7200) < since);
+        // Only the timestamp is set, so every ID of that second is included
+        assert_eq!(&since.bytes()[4..], &[0; 8]);
+    }
+
+    #[test]
+    fn object_id_since_before_the_epoch_includes_everything() {
+        let since = object_id_since(Duration::from_secs(u64::MAX));
+        assert_eq!(since, ObjectId::from_bytes([0; 12]));
+    }
+
+    #[actix_web::test]
+    async fn new_reviews_with_a_flagged_word_are_held() {
+        let service = service("spam").await;
+        let mut dto = create("author", "Buy SPAM now");
+        service.screen(&mut dto).await.unwrap();
+        assert_eq!(dto.status, ReviewStatus::Held);
+        assert_eq!(
+            dto.hold_reason.as_deref(),
+            Some("Contains the flagged word \"spam\"")
+        );
+    }
+
+    #[actix_web::test]
+    async fn new_reviews_of_rapid_posters_are_held() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let service = ModerationService::new(&db, rules(""));
+        let reviews = db.collection::<Document>("reviews");
+        for _ in 0..4 {
+            reviews.insert_one(doc! { "user": "author" }).await.unwrap();
+        }
+        let old = doc! { "_id": object_id_at(now() - 7200), "user": "author" };
+        reviews.insert_one(old).await.unwrap();
+
+        let mut dto = create("author", "A fine show");
+        service.screen(&mut dto).await.unwrap();
+        assert_eq!(dto.status, ReviewStatus::Published);
+
+        reviews.insert_one(doc! { "user": "author" }).await.unwrap();
+        let mut dto = create("author", "A fine show");
+        service.screen(&mut dto).await.unwrap();
+        assert_eq!(dto.status, ReviewStatus::Held);
+        assert_eq!(
+            dto.hold_reason.as_deref(),
+            Some("Posted 6 reviews within 10 minutes")
+        );
+
+        let mut dto = create("someone-else", "A fine show");
+        service.screen(&mut dto).await.unwrap();
+        assert_eq!(dto.status, ReviewStatus::Published);
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn reviews_are_held_once_their_reports_weigh_enough() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let service = ModerationService::new(&db, rules(""));
+        service.ensure_indexes().await.unwrap();
+        let reviews = db.collection::<Document>("reviews");
+        let id = ObjectId::new();
+        reviews.insert_one(stored_review(id)).await.unwrap();
+        let report = || CreateReportDto {
+            reason: ReportReason::Harassment,
+            comment: None,
+        };
+        let status = || async {
+            let review = reviews.find_one(doc! { "_id": id }).await.unwrap().unwrap();
+            review.get_str("status").unwrap().to_string()
+        };
+
+        for user in ["a", "b", "c"] {
+            service.report(&id.to_hex(), user, report()).await.unwrap();
+        }
+        assert_eq!(status().await, "published");
+        match service.report(&id.to_hex(), "c", report()).await {
+            Err(AppError::HttpError(code, _)) => assert_eq!(code, ErrorCode::ReportDuplicate),
+            other => panic!("expected REPORT_DUPLICATE, got {:?}", other),
+        }
+        assert_eq!(status().await, "published");
+
+        service.report(&id.to_hex(), "d", report()).await.unwrap();
+        assert_eq!(status().await, "held");
+        let review = reviews.find_one(doc! { "_id": id }).await.unwrap().unwrap();
+        assert_eq!(review.get_str("hold_reason").unwrap(), "Reported by users");
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn edits_with_a_flagged_word_are_held() {
+        let service = service("spam").await;
+        let mut dto = edit(json!({ "review": "Now with spam" }));
+        service
+            .screen_edit(&review(false), &mut dto, false)
+            .unwrap();
+        assert_eq!(dto.status, Some(ReviewStatus::Held));
+        assert_eq!(
+            dto.hold_reason.as_deref(),
+            Some("Contains the flagged word \"spam\"")
+        );
+
+        let mut dto = edit(json!({ "review": "Still fine", "score": 9 }));
+        service
+            .screen_edit(&review(false), &mut dto, false)
+            .unwrap();
+        assert_eq!(dto.status, None);
+        assert_eq!(dto.hold_reason, None);
+    }
+
+    #[actix_web::test]
+    async fn edits_cannot_clear_a_spoiler_flag_set_by_the_staff() {
+        let service = service("").await;
+        let locked = review(true);
+
+        let mut dto = edit(json!({ "is_spoiler": false }));
+        match service.screen_edit(&locked, &mut dto, false) {
+            Err(AppError::HttpError(code, _)) => assert_eq!(code, ErrorCode::SpoilerLocked),
+            other => panic!("expected SPOILER_LOCKED, got {:?}", other),
+        }
+
+        let mut dto = edit(json!({ "is_spoiler": false }));
+        assert!(service.screen_edit(&locked, &mut dto, true).is_ok());
+        let mut dto = edit(json!({ "is_spoiler": true, "review": "More" }));
+        assert!(service.screen_edit(&locked, &mut dto, false).is_ok());
+        let mut dto = edit(json!({ "is_spoiler": false }));
+        assert!(service.screen_edit(&review(false), &mut dto, false).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn warnings_notify_the_author_even_with_notifications_turned_off() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let service = ModerationService::new(&db, rules(""));
+        let reviews = CrudServiceImpl::<Review, ReviewDto, CreateReviewDto, UpdateReviewDto>::new(
+            Arc::new(DatabaseRepository::new(db.collection("reviews"))),
+        )
+        .with_soft_delete();
+        let notifications = NotificationService::new(&db);
+
+        let author = ObjectId::new();
+        let mut user = User::new(
+            "author".to_string(),
+            "author@example.com".to_string(),
+            "hash".to_string(),
+            true,
+            false,
+            false,
+            None,
+            None,
+            None,
+        );
+        user.enabled_notifications = Vec::new();
+        let mut user = to_document(&user).unwrap();
+        user.insert("_id", author);
+        db.collection::<Document>("users")
+            .insert_one(user)
+            .await
+            .unwrap();
+        let id = ObjectId::new();
+        let mut review = stored_review(id);
+        review.insert("user", author.to_hex());
+        db.collection::<Document>("reviews")
+            .insert_one(review)
+            .await
+            .unwrap();
+
+        let action = ModerateReviewDto {
+            action: ModerationActionType::Warn,
+            note: Some("Mind the spoilers".to_string()),
+        };
+        service
+            .moderate(&reviews, &notifications, &id.to_hex(), "staff", action)
+            .await
+            .unwrap();
+
+        let warning = db
+            .collection::<Document>("notifications")
+            .find_one(doc! { "user": author.to_hex() })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(warning.get_str("kind").unwrap(), "warning");
+        assert_eq!(warning.get_str("target").unwrap(), id.to_hex());
+        assert_eq!(warning.get_str("detail").unwrap(), "Mind the spoilers");
+        let review = db
+            .collection::<Document>("reviews")
+            .find_one(doc! { "_id": id })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(review.get_str("status").unwrap(), "published");
+
+        db.drop().await.unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+use crate::dto::notification::NotificationDto;
+use crate::dto::pagination::CursorPage;
+use crate::models::notification::{Notification, NotificationKind};
+use crate::models::user::User;
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::utils::bson::parse_object_id;
+use colored::Colorize;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Stores the notifications of users and delivers them to their open streams.
+///
+/// Every server process watches the `notifications` collection through a change stream, and
+/// forwards the inserted notifications to the streams its workers hold open. A notification
+/// created by any process thus reaches its user wherever they are connected. Without change
+/// streams, as on a standalone MongoDB server, notifications are delivered within the process
+/// creating them.
+pub struct NotificationService {
+    notifications: DatabaseRepository<Notification>,
+    users: DatabaseRepository<User>,
+    subscribers: Mutex<HashMap<String, Vec<UnboundedSender<NotificationDto>>>>,
+    watching: AtomicBool,
+}
+
+impl NotificationService {
+    /// Creates a new instance of the `NotificationService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `notifications` and `users` collections.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            notifications: DatabaseRepository::new(db.collection("notifications")),
+            users: DatabaseRepository::new(db.collection("users")),
+            subscribers: Mutex::new(HashMap::new()),
+            watching: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates the index reading the notifications of users, newest first.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.notifications
+            .ensure_index("user_id", doc! { "user": 1, "_id": -1 })
+            .await
+    }
+
+    /// Notifies a user, unless they turned this kind of notification off or caused it themselves.
+    ///
+    /// # Parameters
+    /// - `user`: The ID of the notified user.
+    /// - `kind`: What happened.
+    /// - `actor`: The ID of the user who caused it, if any.
+    /// - `target`: The ID of the user, review, comment, club or anime it is about.
+    /// - `detail`: The reaction given, the number of episodes aired, or the note of a warning.
+    pub async fn notify(
+        &self,
+        user: &str,
+        kind: NotificationKind,
+        actor: Option<&str>,
+        target: &str,
+        detail: Option<String>,
+    ) -> Result<(), AppError> {
+        self.notify_many(&[user.to_string()], kind, actor, target, detail)
+            .await
+    }
+
+    /// Notifies several users at once. See `notify`.
+    pub async fn notify_many(
+        &self,
+        users: &[String],
+        kind: NotificationKind,
+        actor: Option<&str>,
+        target: &str,
+        detail: Option<String>,
+    ) -> Result<(), AppError> {
+        let ids = users
+            .iter()
+            .filter(|u| Some(u.as_str()) != actor)
+            .filter_map(|u| parse_object_id(u).ok())
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let now = DateTime::now();
+        let notifications = self
+            .users
+            .find(Some(doc! { "_id": { "$in": ids } }), None)
+            .await?
+            .into_iter()
+            .filter(|u| !kind.optional() || u.enabled_notifications.contains(&kind))
+            .filter_map(|u| u.id)
+            .map(|user| Notification {
+                id: None,
+                user,
+                kind,
+                actor: actor.map(str::to_string),
+                target: target.to_string(),
+                detail: detail.clone(),
+                read_at: None,
+                created_at: now,
+            })
+            .collect::<Vec<_>>();
+        let inserted = self.notifications.insert_many(notifications).await?;
+
+        // The change stream delivers them otherwise
+        if !self.watching.load(Ordering::Acquire) {
+            for notification in inserted {
+                self.publish(NotificationDto::from(notification));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a slice of the notifications of a user, newest first.
+    ///
+    /// # Parameters
+    /// - `user`: The ID of the user.
+    /// - `before`: The cursor returned with the previous slice.
+    /// - `limit`: The size of the slice.
+    /// - `unread`: Whether only the unread notifications are read.
+    pub async fn list(
+        &self,
+        user: &str,
+        before: Option<&str>,
+        limit: u64,
+        unread: bool,
+    ) -> Result<CursorPage<NotificationDto>, AppError> {
+        let mut filter = live(doc! { "user": user });
+        if unread {
+            filter.insert("read_at", Bson::Null);
+        }
+        if let Some(before) = before {
+            filter.insert("_id", doc! { "$lt": parse_object_id(before)? });
+        }
+        // Read one more notification than requested to know whether a next slice exists
+        let options = FindOptions::builder()
+            .sort(doc! { "_id": -1 })
+            .limit((limit + 1) as i64)
+            .build();
+        let mut payload = self
+            .notifications
+            .find(Some(filter), Some(options))
+            .await?
+            .into_iter()
+            .map(NotificationDto::from)
+            .collect::<Vec<_>>();
+
+        let next = match payload.len() as u64 > limit {
+            true => {
+                payload.truncate(limit as usize);
+                payload.last().and_then(|n| n.id.clone())
+            }
+            false => None,
+        };
+        Ok(CursorPage { payload, next })
+    }
+
+    /// Returns the number of unread notifications of a user.
+    pub async fn unread_count(&self, user: &str) -> Result<u64, AppError> {
+        self.notifications
+            .count_documents(Some(live(doc! { "user": user, "read_at": Bson::Null })))
+            .await
+    }
+
+    /// Marks a notification of a user as read.
+    ///
+    /// # Returns
+    /// The notification, or an `AppError` with status 404 if the user has no such notification.
+    pub async fn mark_read(&self, user: &str, id: &str) -> Result<NotificationDto, AppError> {
+        let filter = live(doc! { "_id": parse_object_id(id)?, "user": user });
+        // Keeps the first reading time of a notification read twice
+        let update = vec![doc! { "$set": { "read_at": { "$ifNull": ["$read_at", now()?] } } }];
+        match self.notifications.update_one(filter, update).await {
+            Ok(notification) => Ok(NotificationDto::from(notification)),
+            Err(AppError::NotFound(_)) => {
+                Err(AppError::NotFound("Notification not found".to_string()))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Marks all the notifications of a user as read.
+    ///
+    /// # Returns
+    /// The number of notifications that were unread.
+    pub async fn mark_all_read(&self, user: &str) -> Result<u64, AppError> {
+        Ok(self
+            .notifications
+            .update_many(
+                live(doc! { "user": user, "read_at": Bson::Null }),
+                doc! { "$set": { "read_at": now()? } },
+            )
+            .await?
+            .modified_count)
+    }
+
+    /// Opens a stream of the notifications of a user, as they are created.
+    ///
+    /// The stream is dropped from the service once its receiver is dropped, the next time a
+    /// notification or a new stream comes in for the user.
+    pub fn subscribe(&self, user: &str) -> UnboundedReceiver<NotificationDto> {
+        let (sender, receiver) = unbounded();
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        let senders = subscribers.entry(user.to_string()).or_default();
+        senders.retain(|s| !s.is_closed());
+        senders.push(sender);
+        receiver
+    }
+
+    /// Starts a background job forwarding the notifications inserted by any process to the
+    /// streams open in this one.
+    ///
+    /// The change stream is reopened after a failure. While it is down, notifications are
+    /// delivered within the process creating them.
+    ///
+    /// # Parameters
+    /// - `retry`: The delay before reopening a failed change stream.
+    pub fn spawn_delivery(self: Arc<Self>, retry: Duration) {
+        actix_web::rt::spawn(async move {
+            // Standalone servers never support change streams, so this is only reported once
+            let mut reported = false;
+            loop {
+                match self.notifications.watch_inserts().await {
+                    Ok(mut events) => {
+                        reported = false;
+                        self.watching.store(true, Ordering::Release);
+                        while let Some(event) = events.next().await {
+                            match event {
+                                Ok(event) => {
+                                    if let Some(notification) = event.full_document {
+                                        self.publish(NotificationDto::from(notification));
+                                    }
+                                }
+                                Err(e) => {
+                                    eprintln!("{} {}", "Notification stream failed:".red(), e);
+                                    break;
+                                }
+                            }
+                        }
+                        self.watching.store(false, Ordering::Release);
+                    }
+                    Err(e) if !reported => {
+                        reported = true;
+                        eprintln!(
+                            "{} {}",
+                            "Notification stream unavailable, delivering locally:".yellow(),
+                            e
+                        );
+                    }
+                    Err(_) => {}
+                }
+                actix_web::rt::time::sleep(retry).await;
+            }
+        });
+    }
+
+    /// Sends a notification to the open streams of its user, dropping the closed ones.
+    fn publish(&self, notification: NotificationDto) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(senders) = subscribers.get_mut(&notification.user) {
+            senders.retain(|s| s.unbounded_send(notification.clone()).is_ok());
+            if senders.is_empty() {
+                subscribers.remove(&notification.user);
+            }
+        }
+    }
+}
+
+/// Restricts a filter to the notifications that are not in the trash.
+fn live(mut filter: Document) -> Document {
+    filter.insert("deleted_at", Bson::Null);
+    filter
+}
+
+/// The current time, as stored in the documents.
+fn now() -> Result<String, AppError> {
+    DateTime::now()
+        .try_to_rfc3339_string()
+        .map_err(|e| AppError::from(e.to_string()))
+}
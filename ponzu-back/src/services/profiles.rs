@@ -0,0 +1,122 @@
+use crate::dto::profile::{ListStatsDto, ProfileStatsDto};
+use crate::models::anime::Anime;
+use crate::models::list_entry::{ListEntry, ListStatus};
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::types::jikan::EpisodeDuration;
+use crate::utils::bson::parse_object_id;
+use mongodb::bson::{doc, from_bson, Bson};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use std::collections::HashMap;
+
+/// Computes the statistics shown on the profiles of users.
+pub struct ProfileService {
+    lists: DatabaseRepository<ListEntry>,
+    anime: DatabaseRepository<Anime>,
+}
+
+impl ProfileService {
+    /// Creates a new instance of the `ProfileService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `list_entries` and `anime` collections.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            lists: DatabaseRepository::new(db.collection("list_entries")),
+            anime: DatabaseRepository::new(db.collection("anime")),
+        }
+    }
+
+    /// Computes the statistics of the list of a user.
+    ///
+    /// The days watched are derived from `Anime.duration`. Anime with an unknown duration count
+    /// towards the episodes watched only.
+    pub async fn stats(&self, user: &str) -> Result<ProfileStatsDto, AppError> {
+        let entries = self
+            .lists
+            .find(Some(doc! { "user": user, "deleted_at": Bson::Null }), None)
+            .await?;
+
+        let ids = entries
+            .iter()
+            .filter(|e| e.r#type == "anime" && e.progress > 0)
+            .map(|e| parse_object_id(&e.entry))
+            .collect::<Result<Vec<_>, _>>()?;
+        let durations = match ids.is_empty() {
+            true => HashMap::new(),
+            false => self
+                .anime
+                .find_documents(
+                    doc! { "_id": { "$in": ids } },
+                    Some(
+                        FindOptions::builder()
+                            .projection(doc! { "duration": 1 })
+                            .build(),
+                    ),
+                )
+                .await?
+                .into_iter()
+                .filter_map(|d| {
+                    let duration = from_bson::<EpisodeDuration>(d.get("duration")?.clone()).ok()?;
+                    Some((d.get_object_id("_id").ok()?.to_hex(), duration))
+                })
+                .collect::<HashMap<_, _>>(),
+        };
+
+        let mut stats = ProfileStatsDto::default();
+        let mut seconds_watched = 0u64;
+        for kind in ["anime", "manga"] {
+            let of_kind = entries
+                .iter()
+                .filter(|e| e.r#type == kind)
+                .collect::<Vec<_>>();
+            let progress = of_kind.iter().map(|e| e.progress as u64).sum::<u64>();
+            let list = list_stats(&of_kind);
+            match kind {
+                "anime" => {
+                    stats.anime = list;
+                    stats.episodes_watched = progress;
+                    seconds_watched = of_kind
+                        .iter()
+                        .filter_map(|e| {
+                            durations
+                                .get(&e.entry)
+                                .map(|d| d.0 as u64 * e.progress as u64)
+                        })
+                        .sum();
+                }
+                _ => {
+                    stats.manga = list;
+                    stats.chapters_read = progress;
+                }
+            }
+        }
+        // Rounded to a tenth of a day
+        stats.days_watched = (seconds_watched as f64 / 8640.0).round() / 10.0;
+        Ok(stats)
+    }
+}
+
+/// Counts the entries of a list per status and averages their scores.
+fn list_stats(entries: &[&ListEntry]) -> ListStatsDto {
+    let mut stats = ListStatsDto::default();
+    for entry in entries {
+        let count = match entry.status {
+            ListStatus::Current => &mut stats.current,
+            ListStatus::Completed => &mut stats.completed,
+            ListStatus::OnHold => &mut stats.on_hold,
+            ListStatus::Dropped => &mut stats.dropped,
+            ListStatus::Planned => &mut stats.planned,
+        };
+        *count += 1;
+    }
+    stats.total = entries.len() as u64;
+
+    let scores = entries.iter().filter_map(|e| e.score).collect::<Vec<_>>();
+    if !scores.is_empty() {
+        let mean = scores.iter().map(|s| *s as f64).sum::<f64>() / scores.len() as f64;
+        stats.mean_score = Some((mean * 100.0).round() / 100.0);
+    }
+    stats
+}
@@ -0,0 +1,299 @@
+use crate::dto::reaction::ReactedDto;
+use crate::models::reaction::{Reaction, ReactionType};
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::utils::bson::parse_object_id;
+use mongodb::bson::{doc, to_bson, DateTime};
+use mongodb::Database;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// Records the reactions of users to reviews or comments.
+///
+/// Each user can give each reaction type once per target. The counters of the target's
+/// `reactions` field are kept in step with the stored reactions.
+///
+/// # Type Parameters
+/// - `T`: The model of the targets, which must hold a `reactions` field.
+/// - `R`: The DTO the targets are returned as.
+pub struct ReactionService<T, R>
+where
+    T: Send + Sync + DeserializeOwned + Serialize,
+    R: From<T>,
+{
+    reactions: DatabaseRepository<Reaction>,
+    targets: DatabaseRepository<T>,
+    not_found: &'static str,
+    _phantom: PhantomData<R>,
+}
+
+impl<T, R> ReactionService<T, R>
+where
+    T: Send + Sync + DeserializeOwned + Serialize,
+    R: From<T>,
+{
+    /// Creates a new instance of the `ReactionService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the collections.
+    /// - `reactions`: The collection holding the reactions.
+    /// - `targets`: The collection holding the documents reacted to.
+    /// - `not_found`: The message of the 404 returned when a target does not exist.
+    pub fn new(db: &Database, reactions: &str, targets: &str, not_found: &'static str) -> Self {
+        Self {
+            reactions: DatabaseRepository::new(db.collection(reactions)),
+            targets: DatabaseRepository::new(db.collection(targets)),
+            not_found,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates the index preventing a user from giving the same reaction twice.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.reactions
+            .ensure_live_unique_index(
+                "target_user_reaction",
+                doc! { "target": 1, "user": 1, "reaction": 1 },
+            )
+            .await
+    }
+
+    /// Adds the reaction of a user to a target, or removes it if the user already gave it.
+    ///
+    /// # Parameters
+    /// - `target_id`: The ID of the review or comment.
+    /// - `user_id`: The ID of the reacting user.
+    /// - `reaction`: The reaction type to toggle.
+    ///
+    /// # Returns
+    /// The updated target, with the reactions the user now gives it.
+    pub async fn toggle(
+        &self,
+        target_id: &str,
+        user_id: &str,
+        reaction: ReactionType,
+    ) -> Result<ReactedDto<R>, AppError> {
+        let target = self.live_target(target_id).await?;
+        let filter = doc! {
+            "target": target_id,
+            "user": user_id,
+            "reaction": to_bson(&reaction).map_err(|e| AppError::from(e.to_string()))?,
+            "deleted_at": null,
+        };
+
+        let by = if self.reactions.delete_one(filter).await?.deleted_count > 0 {
+            -1
+        } else {
+            let inserted = self
+                .reactions
+                .insert_one(Reaction {
+                    id: None,
+                    target: target_id.to_string(),
+                    user: user_id.to_string(),
+                    reaction,
+                    created_at: DateTime::now(),
+                })
+                .await;
+            match inserted {
+                Ok(_) => 1,
+                // A concurrent request of the same user added it first
+                Err(e) if e.is_duplicate_key() => 0,
+                Err(e) => return Err(e),
+            }
+        };
+
+        let target = match by {
+            0 => target,
+            by => {
+                self.targets
+                    .update_one(
+                        doc! { "_id": parse_object_id(target_id)?, "deleted_at": null },
+                        doc! { "$inc": { reaction.counter(): by, "reactions.overall": by } },
+                    )
+                    .await?
+            }
+        };
+
+        Ok(ReactedDto {
+            target: R::from(target),
+            my_reactions: Some(self.of_user(target_id, user_id).await?),
+        })
+    }
+
+    /// Retrieves the reactions a user gives a target.
+    ///
+    /// # Parameters
+    /// - `target_id`: The ID of the review or comment.
+    /// - `user_id`: The ID of the user.
+    ///
+    /// # Returns
+    /// A `Vec` of reaction types, in the order of `ReactionType::ALL`.
+    pub async fn of_user(
+        &self,
+        target_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<ReactionType>, AppError> {
+        let given = self
+            .reactions
+            .find(
+                Some(doc! { "target": target_id, "user": user_id, "deleted_at": null }),
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(|r| r.reaction)
+            .collect::<Vec<_>>();
+        Ok(ReactionType::ALL
+            .into_iter()
+            .filter(|r| given.contains(r))
+            .collect())
+    }
+
+    /// Loads a target that is not in the trash.
+    async fn live_target(&self, target_id: &str) -> Result<T, AppError> {
+        self.targets
+            .find_one(doc! { "_id": parse_object_id(target_id)?, "deleted_at": null })
+            .await?
+            .ok_or_else(|| AppError::NotFound(self.not_found.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReactionService;
+    use crate::models::reaction::ReactionType;
+    use crate::types::app_error::AppError;
+    use crate::utils::testing::test_database;
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::{doc, to_bson, Bson, DateTime, Document};
+    use mongodb::Database;
+
+    type DocumentReactions = ReactionService<Document, Document>;
+
+    /// Inserts a review with no reaction, returning its ID.
+    async fn review(db: &Database, deleted: bool) -> String {
+        let id = ObjectId::new();
+        let deleted_at = match deleted {
+            true => Bson::DateTime(DateTime::now()),
+            false => Bson::Null,
+        };
+        let reactions = ReactionType::ALL
+            .iter()
+            .map(|r| (r.field().to_string(), Bson::Int64(0)))
+            .chain([("overall".to_string(), Bson::Int64(0))])
+            .collect::<Document>();
+        db.collection::<Document>("reviews")
+            .insert_one(doc! { "_id": id, "reactions": reactions, "deleted_at": deleted_at })
+            .await
+            .unwrap();
+        id.to_hex()
+    }
+
+    fn count(target: &Document, field: &str) -> i64 {
+        target
+            .get_document("reactions")
+            .unwrap()
+            .get_i64(field)
+            .unwrap()
+    }
+
+    #[test]
+    fn counter_fields_match_the_stored_names() {
+        for reaction in ReactionType::ALL {
+            assert_eq!(to_bson(&reaction).unwrap(), Bson::from(reaction.field()));
+            assert_eq!(
+                reaction.counter(),
+                format!("reactions.{}", reaction.field())
+            );
+        }
+    }
+
+    #[actix_web::test]
+    async fn toggling_twice_adds_then_removes_the_reaction() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let service =
+            DocumentReactions::new(&db, "review_reactions", "reviews", "Review not found");
+        service.ensure_indexes().await.unwrap();
+        let id = review(&db, false).await;
+
+        let added = service
+            .toggle(&id, "alice", ReactionType::Funny)
+            .await
+            .unwrap();
+        assert_eq!(count(&added.target, "funny"), 1);
+        assert_eq!(count(&added.target, "overall"), 1);
+        assert_eq!(added.my_reactions, Some(vec![ReactionType::Funny]));
+
+        let removed = service
+            .toggle(&id, "alice", ReactionType::Funny)
+            .await
+            .unwrap();
+        assert_eq!(count(&removed.target, "funny"), 0);
+        assert_eq!(count(&removed.target, "overall"), 0);
+        assert_eq!(removed.my_reactions, Some(vec![]));
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn reactions_are_counted_per_type_and_per_user() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let service =
+            DocumentReactions::new(&db, "review_reactions", "reviews", "Review not found");
+        service.ensure_indexes().await.unwrap();
+        let id = review(&db, false).await;
+
+        service
+            .toggle(&id, "alice", ReactionType::Creative)
+            .await
+            .unwrap();
+        service
+            .toggle(&id, "alice", ReactionType::Nice)
+            .await
+            .unwrap();
+        let last = service
+            .toggle(&id, "bob", ReactionType::Nice)
+            .await
+            .unwrap();
+
+        assert_eq!(count(&last.target, "nice"), 2);
+        assert_eq!(count(&last.target, "creative"), 1);
+        assert_eq!(count(&last.target, "overall"), 3);
+        assert_eq!(last.my_reactions, Some(vec![ReactionType::Nice]));
+        assert_eq!(
+            service.of_user(&id, "alice").await.unwrap(),
+            vec![ReactionType::Nice, ReactionType::Creative]
+        );
+
+        db.drop().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn missing_and_trashed_targets_cannot_be_reacted_to() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let service =
+            DocumentReactions::new(&db, "review_reactions", "reviews", "Review not found");
+        let trashed = review(&db, true).await;
+
+        for id in [trashed, ObjectId::new().to_hex()] {
+            let result = service.toggle(&id, "alice", ReactionType::Nice).await;
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+        assert_eq!(
+            db.collection::<Document>("review_reactions")
+                .count_documents(doc! {})
+                .await
+                .unwrap(),
+            0
+        );
+
+        db.drop().await.unwrap();
+    }
+}
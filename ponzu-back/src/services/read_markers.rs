@@ -0,0 +1,62 @@
+use crate::models::board::TopicRead;
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use mongodb::bson::{doc, Bson, DateTime};
+use mongodb::Database;
+use std::collections::HashMap;
+
+/// Remembers when members last read the topics of club boards, to mark the unread ones.
+pub struct ReadMarkerService {
+    reads: DatabaseRepository<TopicRead>,
+}
+
+impl ReadMarkerService {
+    /// Creates a new instance of the `ReadMarkerService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `topic_reads` collection.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            reads: DatabaseRepository::new(db.collection("topic_reads")),
+        }
+    }
+
+    /// Creates the index allowing a single marker per topic and user.
+    pub async fn ensure_indexes(&self) -> Result<(), AppError> {
+        self.reads
+            .ensure_live_unique_index("topic_user", doc! { "topic": 1, "user": 1 })
+            .await
+    }
+
+    /// Marks a topic as read by a user, up to now.
+    pub async fn mark_read(&self, topic: &str, user: &str) -> Result<(), AppError> {
+        let now = DateTime::now()
+            .try_to_rfc3339_string()
+            .map_err(|e| AppError::from(e.to_string()))?;
+        self.reads
+            .upsert_one(
+                doc! { "topic": topic, "user": user, "deleted_at": Bson::Null },
+                doc! { "$set": { "read_at": now } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Retrieves when a user last read each of the given topics. Unread topics are left out.
+    pub async fn read_at(
+        &self,
+        user: &str,
+        topics: &[String],
+    ) -> Result<HashMap<String, DateTime>, AppError> {
+        Ok(self
+            .reads
+            .find(
+                Some(doc! { "user": user, "topic": { "$in": topics }, "deleted_at": Bson::Null }),
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(|r| (r.topic, r.read_at))
+            .collect())
+    }
+}
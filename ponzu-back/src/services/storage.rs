@@ -0,0 +1,295 @@
+use crate::types::app_config::AppConfig;
+use crate::types::app_error::AppError;
+use actix_web::web::{self, Bytes};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use hmac::{Hmac, Mac};
+use mongodb::bson::DateTime;
+use reqwest::{Method, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// A place where uploaded files are kept, addressed by keys such as `fan-works/<id>/<name>.png`.
+///
+/// The methods return boxed futures, so that the backend can be chosen at runtime.
+pub trait Storage: Send + Sync {
+    /// Stores a file, replacing the one held under the same key.
+    fn put<'a>(&'a self, key: &'a str, bytes: Bytes) -> BoxFuture<'a, Result<(), AppError>>;
+
+    /// Reads a file, or `None` if no file is held under the key.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Bytes>, AppError>>;
+
+    /// Removes a file. Removing a missing file succeeds.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), AppError>>;
+}
+
+/// Creates the storage backend selected by the configuration.
+///
+/// # Panics
+/// If the configured backend is unknown.
+pub fn storage_from_config(config: &AppConfig) -> Arc<dyn Storage> {
+    match config.storage_backend.as_str() {
+        "local" => Arc::new(LocalStorage::new(&config.storage_path)),
+        "s3" => Arc::new(S3Storage::new(
+            &config.s3_endpoint,
+            &config.s3_bucket,
+            &config.s3_region,
+            &config.s3_access_key,
+            &config.s3_secret_key,
+        )),
+        backend => panic!("Unknown storage backend \"{}\"", backend),
+    }
+}
+
+/// Keeps the files in a directory of the local filesystem, the key being the relative path.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Creates a new instance of the `LocalStorage`.
+    ///
+    /// # Parameters
+    /// - `root`: The directory holding the files. It is created on the first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Resolves a key to a path under the root, refusing keys that would escape it.
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(AppError::NotFound("File not found".to_string()));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl Storage for LocalStorage {
+    fn put<'a>(&'a self, key: &'a str, bytes: Bytes) -> BoxFuture<'a, Result<(), AppError>> {
+        async move {
+            let path = self.path(key)?;
+            blocking(move || {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, bytes)
+            })
+            .await
+        }
+        .boxed()
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Bytes>, AppError>> {
+        async move {
+            let path = self.path(key)?;
+            blocking(move || match std::fs::read(path) {
+                Ok(bytes) => Ok(Some(Bytes::from(bytes))),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            })
+            .await
+        }
+        .boxed()
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), AppError>> {
+        async move {
+            let path = self.path(key)?;
+            blocking(move || match std::fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            })
+            .await
+        }
+        .boxed()
+    }
+}
+
+/// Keeps the files in a bucket of an S3-compatible object store, the key being the object key.
+///
+/// Requests are signed with AWS Signature Version 4 and use path-style addressing, which every
+/// S3-compatible store supports.
+pub struct S3Storage {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3Storage {
+    /// Creates a new instance of the `S3Storage`.
+    ///
+    /// # Parameters
+    /// - `endpoint`: The URL of the object store, e.g. `https://s3.eu-west-1.amazonaws.com`.
+    /// - `bucket`: The bucket holding the files.
+    /// - `region`: The region the requests are signed for.
+    /// - `access_key`: The access key ID.
+    /// - `secret_key`: The secret access key.
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+        }
+    }
+
+    /// Sends a signed request about an object.
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Bytes,
+    ) -> Result<reqwest::Response, AppError> {
+        let path = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(key));
+        let url = Url::parse(&format!("{}{}", self.endpoint, path))
+            .map_err(|e| AppError::from(e.to_string()))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(AppError::from("The S3 endpoint has no host")),
+        };
+
+        // 20240102T030405Z, from 2024-01-02T03:04:05.678Z
+        let timestamp = DateTime::now()
+            .try_to_rfc3339_string()
+            .map_err(|e| AppError::from(e.to_string()))?
+            .replace(['-', ':'], "");
+        let amz_date = format!("{}Z", &timestamp[..15]);
+        let date = &amz_date[..8];
+        let payload_hash = hex(&Sha256::digest(&body));
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let mut signing_key = hmac(format!("AWS4{}", self.secret_key).as_bytes(), date);
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac(&signing_key, part);
+        }
+        let signature = hex(&hmac(&signing_key, &string_to_sign));
+
+        self.client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", &amz_date)
+            .header(
+                "authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                    self.access_key, scope, signed_headers, signature
+                ),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::from(e.to_string()))
+    }
+}
+
+impl Storage for S3Storage {
+    fn put<'a>(&'a self, key: &'a str, bytes: Bytes) -> BoxFuture<'a, Result<(), AppError>> {
+        async move {
+            let response = self.send(Method::PUT, key, bytes).await?;
+            match response.status().is_success() {
+                true => Ok(()),
+                false => Err(unexpected(response.status())),
+            }
+        }
+        .boxed()
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Bytes>, AppError>> {
+        async move {
+            let response = self.send(Method::GET, key, Bytes::new()).await?;
+            match response.status() {
+                StatusCode::NOT_FOUND => Ok(None),
+                status if status.is_success() => response
+                    .bytes()
+                    .await
+                    .map(Some)
+                    .map_err(|e| AppError::from(e.to_string())),
+                status => Err(unexpected(status)),
+            }
+        }
+        .boxed()
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), AppError>> {
+        async move {
+            let response = self.send(Method::DELETE, key, Bytes::new()).await?;
+            match response.status() {
+                StatusCode::NOT_FOUND => Ok(()),
+                status if status.is_success() => Ok(()),
+                status => Err(unexpected(status)),
+            }
+        }
+        .boxed()
+    }
+}
+
+/// The error returned when the object store answers with an unexpected status.
+fn unexpected(status: StatusCode) -> AppError {
+    AppError::from(format!("The object store answered {}", status))
+}
+
+/// Percent-encodes a path as required by Signature Version 4, keeping the slashes.
+fn uri_encode(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Signs a message with HMAC-SHA256.
+fn hmac(key: &[u8], message: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Encodes bytes as lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Runs a filesystem operation on the blocking thread pool.
+async fn blocking<T, F>(f: F) -> Result<T, AppError>
+where
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    web::block(f)
+        .await
+        .map_err(|e| AppError::from(e.to_string()))?
+        .map_err(|e| AppError::from(e.to_string()))
+}
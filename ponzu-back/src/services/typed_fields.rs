@@ -0,0 +1,237 @@
+use crate::services::db_repo::DatabaseRepository;
+use crate::types::app_error::AppError;
+use crate::types::jikan::{
+    AgeRating, AnimeSource, AnimeStatus, AnimeType, BroadcastDay, EpisodeDuration, MangaStatus,
+    MangaType,
+};
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use colored::Colorize;
+use mongodb::bson::{doc, to_bson, Bson, Document, Regex};
+use mongodb::Database;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Rewrites the free strings imported from Jikan into the typed values the models expect.
+pub struct TypedFieldService {
+    anime: DatabaseRepository<Document>,
+    manga: DatabaseRepository<Document>,
+}
+
+impl TypedFieldService {
+    /// Creates a new instance of the `TypedFieldService`.
+    ///
+    /// # Parameters
+    /// - `db`: The database holding the `anime` and `manga` collections.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            anime: DatabaseRepository::new(db.collection("anime")),
+            manga: DatabaseRepository::new(db.collection("manga")),
+        }
+    }
+
+    /// Backfills the typed fields of the anime and manga documents still holding Jikan strings.
+    ///
+    /// Documents already holding typed values are not read. Unreadable optional values become
+    /// `null`. Unreadable sources and statuses are left as they are and reported on the standard
+    /// error output, as every title has one.
+    ///
+    /// # Returns
+    /// The number of updated documents.
+    pub async fn backfill(&self) -> Result<u64, AppError> {
+        let mut updated = 0;
+
+        // Times are stored with their seconds, e.g. `01:00:00`, while Jikan leaves them out
+        let stored_time = Regex {
+            pattern: r"^\d{2}:\d{2}:\d{2}".to_string(),
+            options: String::new(),
+        };
+        let untyped_anime = doc! {
+            "$or": [
+                untyped("type", AnimeType::ALL.iter().map(AnimeType::code)),
+                untyped("source", AnimeSource::ALL.iter().map(AnimeSource::code)),
+                untyped("status", AnimeStatus::ALL.iter().map(AnimeStatus::code)),
+                untyped("rating", AgeRating::ALL.iter().map(AgeRating::code)),
+                { "duration": { "$type": "string" } },
+                untyped("broadcast.day", BroadcastDay::ALL.iter().map(BroadcastDay::name)),
+                { "broadcast.time": { "$type": "string", "$not": stored_time } },
+            ]
+        };
+        for anime in self.anime.find_documents(untyped_anime, None).await? {
+            let mut set = Document::new();
+            retype::<AnimeType>(&anime, "type", false, &mut set);
+            retype::<AnimeSource>(&anime, "source", true, &mut set);
+            retype::<AnimeStatus>(&anime, "status", true, &mut set);
+            retype::<EpisodeDuration>(&anime, "duration", false, &mut set);
+            retype::<AgeRating>(&anime, "rating", false, &mut set);
+            if let Ok(broadcast) = anime.get_document("broadcast") {
+                let mut fields = Document::new();
+                retype::<BroadcastDay>(broadcast, "day", false, &mut fields);
+                retype::<NaiveTime>(broadcast, "time", false, &mut fields);
+                retype::<Tz>(broadcast, "timezone", false, &mut fields);
+                for (field, value) in fields {
+                    set.insert(format!("broadcast.{}", field), value);
+                }
+            }
+            updated += self.apply(&self.anime, &anime, set).await?;
+        }
+
+        let untyped_manga = doc! {
+            "$or": [
+                untyped("type", MangaType::ALL.iter().map(MangaType::code)),
+                untyped("status", MangaStatus::ALL.iter().map(MangaStatus::code)),
+            ]
+        };
+        for manga in self.manga.find_documents(untyped_manga, None).await? {
+            let mut set = Document::new();
+            retype::<MangaType>(&manga, "type", false, &mut set);
+            retype::<MangaStatus>(&manga, "status", true, &mut set);
+            updated += self.apply(&self.manga, &manga, set).await?;
+        }
+        Ok(updated)
+    }
+
+    /// Sets the backfilled fields of a document.
+    ///
+    /// # Returns
+    /// `1` if the document was updated, `0` if it had nothing to backfill.
+    async fn apply(
+        &self,
+        repository: &DatabaseRepository<Document>,
+        document: &Document,
+        set: Document,
+    ) -> Result<u64, AppError> {
+        if set.is_empty() {
+            return Ok(0);
+        }
+        let Ok(id) = document.get_object_id("_id") else {
+            return Ok(0);
+        };
+        repository
+            .update_many(doc! { "_id": id }, doc! { "$set": set })
+            .await?;
+        Ok(1)
+    }
+}
+
+/// Matches the documents whose field holds a string other than the given stored values.
+///
+/// The timezones are left out, as Jikan uses the IANA names they are stored as.
+fn untyped<'a>(field: &str, stored: impl Iterator<Item = &'a str>) -> Document {
+    doc! { field: { "$type": "string", "$nin": stored.collect::<Vec<_>>() } }
+}
+
+/// Reads a string field as a typed value, and records the stored form of that value when it
+/// differs from the string.
+///
+/// # Parameters
+/// - `document`: The document holding the field.
+/// - `field`: The name of the field.
+/// - `required`: Whether an unreadable value is kept and reported rather than set to `null`.
+/// - `set`: The fields to update.
+fn retype<T: FromStr + Serialize>(
+    document: &Document,
+    field: &str,
+    required: bool,
+    set: &mut Document,
+) {
+    let Ok(value) = document.get_str(field) else {
+        return;
+    };
+    let typed = match value.parse::<T>() {
+        Ok(typed) => to_bson(&typed).unwrap_or(Bson::Null),
+        Err(_) if required => {
+            eprintln!(
+                "{} {} \"{}\" of {} cannot be read",
+                "Backfill:".yellow(),
+                field,
+                value,
+                document
+                    .get_object_id("_id")
+                    .map(|id| id.to_hex())
+                    .unwrap_or_default()
+            );
+            return;
+        }
+        Err(_) => Bson::Null,
+    };
+    if typed != Bson::String(value.to_string()) {
+        set.insert(field, typed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypedFieldService;
+    use crate::utils::testing::test_database;
+    use mongodb::bson::{doc, Document};
+
+    #[actix_web::test]
+    async fn only_the_documents_holding_jikan_strings_are_backfilled() {
+        let Some(db) = test_database().await else {
+            return;
+        };
+        let anime = db.collection::<Document>("anime");
+        let jikan = doc! {
+            "mal_id": 1,
+            "type": "TV",
+            "source": "Light novel",
+            "status": "Finished Airing",
+            "duration": "24 min per ep",
+            "rating": "PG-13 - Teens 13 or older",
+            "broadcast": { "day": "Saturdays", "time": "01:00", "timezone": "Asia/Tokyo" },
+        };
+        let typed = doc! {
+            "mal_id": 2,
+            "type": "movie",
+            "source": "original",
+            "status": "finished_airing",
+            "duration": 6900,
+            "rating": null,
+            "broadcast": { "day": null, "time": null, "timezone": null },
+        };
+        let unreadable = doc! { "mal_id": 3, "source": "Sequel", "status": "finished_airing" };
+        anime
+            .insert_many([jikan, typed.clone(), unreadable])
+            .await
+            .unwrap();
+        db.collection::<Document>("manga")
+            .insert_one(doc! { "mal_id": 4, "type": "One-shot", "status": "Finished" })
+            .await
+            .unwrap();
+
+        let service = TypedFieldService::new(&db);
+        assert_eq!(service.backfill().await.unwrap(), 2);
+
+        let find = |mal_id: i32| {
+            let anime = anime.clone();
+            async move {
+                let mut found = anime
+                    .find_one(doc! { "mal_id": mal_id })
+                    .await
+                    .unwrap()
+                    .unwrap();
+                found.remove("_id");
+                found
+            }
+        };
+        let backfilled = doc! {
+            "mal_id": 1,
+            "type": "tv",
+            "source": "light_novel",
+            "status": "finished_airing",
+            "duration": 1440_i64,
+            "rating": "pg13",
+            "broadcast": { "day": "saturday", "time": "01:00:00", "timezone": "Asia/Tokyo" },
+        };
+        assert_eq!(find(1).await, backfilled);
+        assert_eq!(find(2).await, typed);
+        // An unreadable source is kept, to be fixed by hand
+        assert_eq!(find(3).await.get_str("source").unwrap(), "Sequel");
+
+        // Nothing is left to backfill but the unreadable source
+        assert_eq!(service.backfill().await.unwrap(), 0);
+
+        db.drop().await.unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use crate::env::get_from_env;
+
+/// Runtime configuration of the application.
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    /// Secret used to sign and verify access tokens.
+    pub jwt_secret: String,
+    /// Lifetime of an issued access token, in hours.
+    pub jwt_ttl_hours: u64,
+    /// How long soft-deleted documents stay in the trash before being purged, in days.
+    pub trash_retention_days: u64,
+    /// Whether multi-collection changes run in a transaction. Requires a replica set.
+    pub database_transactions: bool,
+    /// Hours between two reconciliations of the denormalized counters. `0` disables them.
+    pub counter_reconcile_hours: u64,
+    /// Path of the word list holding new reviews for moderation, one word per line. Empty for none.
+    pub moderation_word_list: String,
+    /// How many reviews a user may post within the rapid-posting window before being held.
+    pub moderation_rapid_posts: u64,
+    /// The rapid-posting window, in minutes.
+    pub moderation_rapid_window_minutes: u64,
+    /// The total weight of open reports above which a review is held.
+    pub moderation_hold_weight: u64,
+    /// How deep replies can be nested under a top-level comment.
+    pub comment_max_depth: u32,
+    /// Seconds before reopening a failed notification change stream.
+    pub notification_retry_seconds: u64,
+    /// Where uploaded files are kept, `local` or `s3`.
+    pub storage_backend: String,
+    /// The directory of the `local` storage backend.
+    pub storage_path: String,
+    /// The URL the stored files are served under. Defaults to the files endpoint.
+    pub storage_public_url: String,
+    /// The URL of the object store of the `s3` storage backend.
+    pub s3_endpoint: String,
+    /// The bucket of the `s3` storage backend.
+    pub s3_bucket: String,
+    /// The region the requests of the `s3` storage backend are signed for.
+    pub s3_region: String,
+    /// The access key ID of the `s3` storage backend.
+    pub s3_access_key: String,
+    /// The secret access key of the `s3` storage backend.
+    pub s3_secret_key: String,
+    /// The URL prefix of the external images to mirror locally.
+    pub image_mirror_origin: String,
+    /// The URL the mirrored images are downloaded from, in place of the origin. Empty for the
+    /// origin itself.
+    pub image_mirror_source: String,
+    /// Hours between two runs of the image mirroring. `0` disables them.
+    pub image_mirror_hours: u64,
+    /// The largest accepted upload, in bytes.
+    pub upload_max_bytes: usize,
+    /// The smallest accepted width and height of an uploaded image, in pixels.
+    pub image_min_dimension: u32,
+    /// The largest accepted width and height of an uploaded image, in pixels.
+    pub image_max_dimension: u32,
+    /// How deep a GraphQL query can nest its selections.
+    pub graphql_max_depth: usize,
+    /// The highest complexity of a GraphQL query, each field costing one and each list of a
+    /// page costing its items.
+    pub graphql_max_complexity: usize,
+    /// How long the readiness check waits for each dependency, in milliseconds.
+    pub readiness_timeout_ms: u64,
+    /// Seconds between a shutdown signal and the server closing, during which readiness fails
+    /// so that load balancers stop routing to it.
+    pub shutdown_grace_seconds: u64,
+}
+
+impl AppConfig {
+    /// Loads the configuration from the environment.
+    ///
+    /// # Returns
+    /// The `AppConfig` built from the environment variables.
+    pub fn from_env() -> Self {
+        Self {
+            jwt_secret: get_from_env("JWT_SECRET", None),
+            jwt_ttl_hours: get_from_env("JWT_TTL_HOURS", Some("24")),
+            trash_retention_days: get_from_env("TRASH_RETENTION_DAYS", Some("30")),
+            database_transactions: get_from_env("DATABASE_TRANSACTIONS", Some("true")),
+            counter_reconcile_hours: get_from_env("COUNTER_RECONCILE_HOURS", Some("24")),
+            moderation_word_list: get_from_env("MODERATION_WORD_LIST", Some("")),
+            moderation_rapid_posts: get_from_env("MODERATION_RAPID_POSTS", Some("3")),
+            moderation_rapid_window_minutes: get_from_env(
+                "MODERATION_RAPID_WINDOW_MINUTES",
+                Some("10"),
+            ),
+            moderation_hold_weight: get_from_env("MODERATION_HOLD_WEIGHT", Some("6")),
+            comment_max_depth: get_from_env("COMMENT_MAX_DEPTH", Some("4")),
+            notification_retry_seconds: get_from_env("NOTIFICATION_RETRY_SECONDS", Some("30")),
+            storage_backend: get_from_env("STORAGE_BACKEND", Some("local")),
+            storage_path: get_from_env("STORAGE_PATH", Some("uploads")),
+            storage_public_url: get_from_env("STORAGE_PUBLIC_URL", Some("/api/files")),
+            s3_endpoint: get_from_env("S3_ENDPOINT", Some("")),
+            s3_bucket: get_from_env("S3_BUCKET", Some("")),
+            s3_region: get_from_env("S3_REGION", Some("us-east-1")),
+            s3_access_key: get_from_env("S3_ACCESS_KEY", Some("")),
+            s3_secret_key: get_from_env("S3_SECRET_KEY", Some("")),
+            image_mirror_origin: get_from_env(
+                "IMAGE_MIRROR_ORIGIN",
+                Some("https://cdn.myanimelist.net"),
+            ),
+            image_mirror_source: get_from_env("IMAGE_MIRROR_SOURCE", Some("")),
+            image_mirror_hours: get_from_env("IMAGE_MIRROR_HOURS", Some("24")),
+            upload_max_bytes: get_from_env("UPLOAD_MAX_BYTES", Some("10485760")),
+            image_min_dimension: get_from_env("IMAGE_MIN_DIMENSION", Some("32")),
+            image_max_dimension: get_from_env("IMAGE_MAX_DIMENSION", Some("8000")),
+            graphql_max_depth: get_from_env("GRAPHQL_MAX_DEPTH", Some("8")),
+            graphql_max_complexity: get_from_env("GRAPHQL_MAX_COMPLEXITY", Some("2000")),
+            readiness_timeout_ms: get_from_env("READINESS_TIMEOUT_MS", Some("2000")),
+            shutdown_grace_seconds: get_from_env("SHUTDOWN_GRACE_SECONDS", Some("5")),
+        }
+    }
+}
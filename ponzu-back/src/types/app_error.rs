@@ -1,7 +1,10 @@
-use crate::types::error_response::SerializableError;
+use crate::types::error_response::{ErrorCode, SerializableError};
+use crate::types::request_id;
+use crate::types::validation::FieldErrors;
 use actix_web::http::StatusCode;
 use actix_web::{HttpResponse, ResponseError};
-use mongodb::error::Error as MongoError;
+use async_graphql::ErrorExtensions;
+use mongodb::error::{Error as MongoError, ErrorKind, WriteFailure};
 use std::fmt;
 
 /// Custom error type for the application.
@@ -10,7 +13,22 @@ pub enum AppError {
     MongoError(MongoError),
     NotFound(String),
     InternalServerError(String),
-    HttpError(String, StatusCode),
+    HttpError(ErrorCode, String),
+    /// A request body breaking validation rules, with the messages of the invalid fields.
+    Validation(FieldErrors),
+}
+
+impl AppError {
+    /// Returns whether the error is a MongoDB duplicate key error, i.e. a unique index violation.
+    pub fn is_duplicate_key(&self) -> bool {
+        match self {
+            AppError::MongoError(err) => matches!(
+                err.kind.as_ref(),
+                ErrorKind::Write(WriteFailure::WriteError(e)) if e.code == 11000
+            ),
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for AppError {
@@ -19,30 +37,64 @@ impl fmt::Display for AppError {
             AppError::MongoError(err) => write!(f, "MongoDB error: {}", err),
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
             AppError::InternalServerError(msg) => write!(f, "Internal server error: {}", msg),
-            AppError::HttpError(msg, status_code) => {
-                write!(f, "HTTP error: {} ({})", msg, status_code.to_string())
+            AppError::HttpError(code, msg) => {
+                write!(f, "HTTP error: {} ({:?}, {})", msg, code, code.status())
+            }
+            AppError::Validation(fields) => {
+                write!(f, "Validation error: {} invalid field(s)", fields.len())
             }
         }
     }
 }
 
-impl ResponseError for AppError {
-    fn error_response(&self) -> HttpResponse {
+impl AppError {
+    /// The code of the error, and the message sent with it.
+    fn describe(&self) -> (ErrorCode, String) {
         match self {
-            AppError::MongoError(_) => HttpResponse::InternalServerError()
-                .json(r#"{"error": "Database error", "status": 500}"#),
-            AppError::NotFound(msg) => {
-                HttpResponse::NotFound().json(SerializableError::new(msg.to_string(), 404))
-            }
-            AppError::InternalServerError(msg) => HttpResponse::InternalServerError()
-                .json(SerializableError::new(msg.to_string(), 500)),
-            AppError::HttpError(msg, status_code) => HttpResponse::build(*status_code).json(
-                SerializableError::new(msg.to_string(), status_code.as_u16()),
+            AppError::MongoError(_) if self.is_duplicate_key() => (
+                ErrorCode::Duplicate,
+                "A value that must be unique is already taken".to_string(),
             ),
+            AppError::MongoError(_) => (ErrorCode::DatabaseError, "Database error".to_string()),
+            AppError::NotFound(msg) => (ErrorCode::NotFound, msg.to_string()),
+            AppError::InternalServerError(msg) => (ErrorCode::InternalError, msg.to_string()),
+            AppError::HttpError(code, msg) => (*code, msg.to_string()),
+            AppError::Validation(_) => {
+                (ErrorCode::ValidationFailed, "Validation failed".to_string())
+            }
         }
     }
 }
 
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        self.describe().0.status()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let (code, message) = self.describe();
+        let mut error =
+            SerializableError::new(code, message).with_request_id(request_id::current());
+        if let AppError::Validation(fields) = self {
+            error = error.with_fields(fields.clone());
+        }
+        HttpResponse::build(code.status()).json(error)
+    }
+}
+
+/// GraphQL errors carry the code and the status of the REST response in their extensions.
+impl From<AppError> for async_graphql::Error {
+    fn from(error: AppError) -> Self {
+        let (code, message) = error.describe();
+        async_graphql::Error::new(message).extend_with(|_, extensions| {
+            if let Ok(value) = async_graphql::to_value(code) {
+                extensions.set("code", value);
+            }
+            extensions.set("status", code.status().as_u16());
+        })
+    }
+}
+
 impl From<MongoError> for AppError {
     fn from(err: MongoError) -> Self {
         AppError::MongoError(err)
@@ -67,20 +119,27 @@ impl From<std::io::Error> for AppError {
     }
 }
 
+impl From<(ErrorCode, String)> for AppError {
+    fn from((code, msg): (ErrorCode, String)) -> Self {
+        AppError::HttpError(code, msg)
+    }
+}
+
+impl From<(ErrorCode, &str)> for AppError {
+    fn from((code, msg): (ErrorCode, &str)) -> Self {
+        AppError::HttpError(code, msg.to_owned())
+    }
+}
+
+/// Errors raised with a bare status get the generic code of that status.
 impl From<(String, u16)> for AppError {
     fn from((msg, status_code): (String, u16)) -> Self {
-        AppError::HttpError(
-            msg,
-            StatusCode::from_u16(status_code).expect("Invalid status code"),
-        )
+        AppError::HttpError(ErrorCode::for_status(status_code), msg)
     }
 }
 
 impl From<(&str, u16)> for AppError {
     fn from((msg, status_code): (&str, u16)) -> Self {
-        AppError::HttpError(
-            msg.to_owned(),
-            StatusCode::from_u16(status_code).expect("Invalid status code"),
-        )
+        AppError::HttpError(ErrorCode::for_status(status_code), msg.to_owned())
     }
 }
@@ -1,7 +1,15 @@
 use crate::dto::anime::{AnimeDto, CreateAnimeDto, UpdateAnimeDto};
+use crate::dto::board::{
+    CreatePostDto, CreateTopicDto, PostDto, TopicDto, UpdatePostDto, UpdateTopicDto,
+};
 use crate::dto::character::{CharacterDto, CreateCharacterDto, UpdateCharacterDto};
 use crate::dto::club::{ClubDto, CreateClubDto, UpdateClubDto};
+use crate::dto::comment::{CommentDto, CreateCommentDto, UpdateCommentDto};
+use crate::dto::fan_work::{
+    ChapterDto, CreateChapterDto, CreateFanWorkDto, FanWorkDto, UpdateChapterDto, UpdateFanWorkDto,
+};
 use crate::dto::genre::{CreateGenreDto, GenreDto, UpdateGenreDto};
+use crate::dto::list_entry::{CreateListEntryDto, ListEntryDto, UpdateListEntryDto};
 use crate::dto::magazine::{CreateMagazineDto, MagazineDto, UpdateMagazineDto};
 use crate::dto::manga::{CreateMangaDto, MangaDto, UpdateMangaDto};
 use crate::dto::person::{CreatePersonDto, PersonDto, UpdatePersonDto};
@@ -9,69 +17,853 @@ use crate::dto::producer::{CreateProducerDto, ProducerDto, UpdateProducerDto};
 use crate::dto::review::{CreateReviewDto, ReviewDto, UpdateReviewDto};
 use crate::dto::user::{RegisterUserDto, UpdateUserDto, UserDto};
 use crate::models::anime::Anime;
+use crate::models::board::{Post, Topic};
 use crate::models::character::Character;
 use crate::models::club::Club;
+use crate::models::comment::Comment;
+use crate::models::fan_work::{Chapter, FanWork};
 use crate::models::genre::Genre;
+use crate::models::list_entry::ListEntry;
 use crate::models::magazine::Magazine;
 use crate::models::manga::Manga;
 use crate::models::person::Person;
 use crate::models::producer::Producer;
+use crate::models::reaction::ReactionType;
 use crate::models::review::Review;
+use crate::models::revision::Revision;
 use crate::models::user::User;
-use crate::services::crud::{CrudService, CrudServiceImpl};
+use crate::services::activity::ActivityService;
+use crate::services::clubs::MembershipService;
+use crate::services::content_policy::ContentPolicy;
+use crate::services::counters::{Counter, CounterService};
+use crate::services::crud::{CrudService, CrudServiceImpl, TrashedDuplicate};
 use crate::services::db_repo::DatabaseRepository;
+use crate::services::fan_works::FanWorkInteractionService;
+use crate::services::follows::FollowService;
+use crate::services::health::HealthService;
+use crate::services::image_mirror::ImageMirrorService;
+use crate::services::images::{ImageRules, ImageService};
+use crate::services::integrity::{IntegrityService, OnDelete, Reference};
+use crate::services::moderation::{ModerationRules, ModerationService};
+use crate::services::notifications::NotificationService;
+use crate::services::profiles::ProfileService;
+use crate::services::reactions::ReactionService;
+use crate::services::read_markers::ReadMarkerService;
+use crate::services::storage::{storage_from_config, Storage};
+use crate::services::typed_fields::TypedFieldService;
+use crate::types::app_config::AppConfig;
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
+use colored::Colorize;
 use mongodb::Database;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// The application state.
+#[allow(dead_code)]
 pub struct AppState {
-    anime_service: CrudServiceImpl<Anime, AnimeDto, CreateAnimeDto, UpdateAnimeDto>,
-    character_service:
+    pub config: AppConfig,
+    pub integrity_service: Arc<IntegrityService>,
+    pub counter_service: Arc<CounterService>,
+    pub reaction_service: ReactionService<Review, ReviewDto>,
+    pub comment_reaction_service: ReactionService<Comment, CommentDto>,
+    pub moderation_service: ModerationService,
+    pub membership_service: MembershipService,
+    pub read_marker_service: ReadMarkerService,
+    pub follow_service: FollowService,
+    pub activity_service: ActivityService,
+    pub notification_service: Arc<NotificationService>,
+    pub profile_service: ProfileService,
+    pub typed_field_service: TypedFieldService,
+    pub health_service: HealthService,
+    pub storage: Arc<dyn Storage>,
+    pub image_service: Arc<ImageService>,
+    pub image_mirror_service: Arc<ImageMirrorService>,
+    pub fan_work_interaction_service: FanWorkInteractionService,
+    pub anime_service: CrudServiceImpl<Anime, AnimeDto, CreateAnimeDto, UpdateAnimeDto>,
+    pub chapter_service: CrudServiceImpl<Chapter, ChapterDto, CreateChapterDto, UpdateChapterDto>,
+    pub character_service:
         CrudServiceImpl<Character, CharacterDto, CreateCharacterDto, UpdateCharacterDto>,
-    club_service: CrudServiceImpl<Club, ClubDto, CreateClubDto, UpdateClubDto>,
-    genre_service: CrudServiceImpl<Genre, GenreDto, CreateGenreDto, UpdateGenreDto>,
-    magazine_service: CrudServiceImpl<Magazine, MagazineDto, CreateMagazineDto, UpdateMagazineDto>,
-    manga_service: CrudServiceImpl<Manga, MangaDto, CreateMangaDto, UpdateMangaDto>,
-    people_service: CrudServiceImpl<Person, PersonDto, CreatePersonDto, UpdatePersonDto>,
-    producer_service: CrudServiceImpl<Producer, ProducerDto, CreateProducerDto, UpdateProducerDto>,
-    review_service: CrudServiceImpl<Review, ReviewDto, CreateReviewDto, UpdateReviewDto>,
-    user_service: CrudServiceImpl<User, UserDto, RegisterUserDto, UpdateUserDto>,
+    pub club_service: CrudServiceImpl<Club, ClubDto, CreateClubDto, UpdateClubDto>,
+    pub comment_service: CrudServiceImpl<Comment, CommentDto, CreateCommentDto, UpdateCommentDto>,
+    pub fan_work_service: CrudServiceImpl<FanWork, FanWorkDto, CreateFanWorkDto, UpdateFanWorkDto>,
+    pub genre_service: CrudServiceImpl<Genre, GenreDto, CreateGenreDto, UpdateGenreDto>,
+    pub list_service:
+        CrudServiceImpl<ListEntry, ListEntryDto, CreateListEntryDto, UpdateListEntryDto>,
+    pub magazine_service:
+        CrudServiceImpl<Magazine, MagazineDto, CreateMagazineDto, UpdateMagazineDto>,
+    pub manga_service: CrudServiceImpl<Manga, MangaDto, CreateMangaDto, UpdateMangaDto>,
+    pub people_service: CrudServiceImpl<Person, PersonDto, CreatePersonDto, UpdatePersonDto>,
+    pub post_service: CrudServiceImpl<Post, PostDto, CreatePostDto, UpdatePostDto>,
+    pub producer_service:
+        CrudServiceImpl<Producer, ProducerDto, CreateProducerDto, UpdateProducerDto>,
+    pub review_service: CrudServiceImpl<Review, ReviewDto, CreateReviewDto, UpdateReviewDto>,
+    pub topic_service: CrudServiceImpl<Topic, TopicDto, CreateTopicDto, UpdateTopicDto>,
+    pub user_service: CrudServiceImpl<User, UserDto, RegisterUserDto, UpdateUserDto>,
 }
 
 impl AppState {
-    pub fn new(db: Database) -> AppState {
+    pub fn new(db: Database, config: AppConfig) -> AppState {
+        let revisions = Arc::new(DatabaseRepository::<Revision>::new(
+            db.collection("revisions"),
+        ));
+        let integrity = Arc::new(IntegrityService::new(
+            db.clone(),
+            vec![
+                Reference {
+                    source: "reviews",
+                    field: "entry",
+                    targets: &["anime", "manga"],
+                    many: false,
+                    on_delete: OnDelete::Restrict,
+                },
+                Reference {
+                    source: "reviews",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "list_entries",
+                    field: "entry",
+                    targets: &["anime", "manga"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "list_entries",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "comments",
+                    field: "review",
+                    targets: &["reviews"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "comments",
+                    field: "parent",
+                    targets: &["comments"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "comments",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "clubs",
+                    field: "anime",
+                    targets: &["anime"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "clubs",
+                    field: "manga",
+                    targets: &["manga"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "clubs",
+                    field: "characters",
+                    targets: &["characters"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "clubs",
+                    field: "people",
+                    targets: &["people"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "club_members",
+                    field: "club",
+                    targets: &["clubs"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "club_members",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "club_requests",
+                    field: "club",
+                    targets: &["clubs"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "club_requests",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "club_requests",
+                    field: "invited_by",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "club_bans",
+                    field: "club",
+                    targets: &["clubs"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "club_bans",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "topics",
+                    field: "club",
+                    targets: &["clubs"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "topics",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "posts",
+                    field: "topic",
+                    targets: &["topics"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "posts",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "posts",
+                    field: "mentions",
+                    targets: &["users"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "topic_reads",
+                    field: "topic",
+                    targets: &["topics"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "topic_reads",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "review_reactions",
+                    field: "target",
+                    targets: &["reviews"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "review_reactions",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "comment_reactions",
+                    field: "target",
+                    targets: &["comments"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "comment_reactions",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "review_reports",
+                    field: "review",
+                    targets: &["reviews"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "review_reports",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "follows",
+                    field: "follower",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "follows",
+                    field: "followee",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "activities",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "activities",
+                    field: "target",
+                    targets: &["anime", "manga", "reviews", "comments", "clubs"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "notifications",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "notifications",
+                    field: "actor",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "notifications",
+                    field: "target",
+                    targets: &["users", "anime", "reviews", "comments", "clubs"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "fan_works",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "fan_works",
+                    field: "anime",
+                    targets: &["anime"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "fan_works",
+                    field: "manga",
+                    targets: &["manga"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "fan_works",
+                    field: "characters",
+                    targets: &["characters"],
+                    many: true,
+                    on_delete: OnDelete::Nullify,
+                },
+                Reference {
+                    source: "fan_work_chapters",
+                    field: "work",
+                    targets: &["fan_works"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "fan_work_likes",
+                    field: "work",
+                    targets: &["fan_works"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+                Reference {
+                    source: "fan_work_likes",
+                    field: "user",
+                    targets: &["users"],
+                    many: false,
+                    on_delete: OnDelete::Cascade,
+                },
+            ],
+            config.database_transactions,
+        ));
+        let counters = Arc::new(CounterService::new(db.clone(), counted_tags()));
+        let rules = moderation_rules(&config);
+        let storage = storage_from_config(&config);
+        let images = Arc::new(ImageService::new(
+            storage.clone(),
+            &config.storage_public_url,
+            ImageRules {
+                max_bytes: config.upload_max_bytes,
+                min_dimension: config.image_min_dimension,
+                max_dimension: config.image_max_dimension,
+            },
+        ));
+        let content_policy = Arc::new(ContentPolicy::new(&db));
+        let mirrors = Arc::new(ImageMirrorService::new(
+            &db,
+            storage.clone(),
+            &config.image_mirror_origin,
+            &config.image_mirror_source,
+        ));
+        let health = HealthService::new(
+            &db,
+            storage.clone(),
+            Duration::from_millis(config.readiness_timeout_ms),
+        );
+
         AppState {
+            config,
+            integrity_service: integrity.clone(),
+            counter_service: counters.clone(),
+            reaction_service: ReactionService::new(
+                &db,
+                "review_reactions",
+                "reviews",
+                "Review not found",
+            ),
+            comment_reaction_service: ReactionService::new(
+                &db,
+                "comment_reactions",
+                "comments",
+                "Comment not found",
+            ),
+            fan_work_interaction_service: FanWorkInteractionService::new(
+                &db,
+                counters.clone(),
+                images.clone(),
+                rules.hold_weight,
+            ),
+            moderation_service: ModerationService::new(&db, rules),
+            membership_service: MembershipService::new(&db, counters.clone()),
+            read_marker_service: ReadMarkerService::new(&db),
+            follow_service: FollowService::new(&db, counters.clone()),
+            activity_service: ActivityService::new(&db),
+            notification_service: Arc::new(NotificationService::new(&db)),
+            profile_service: ProfileService::new(&db),
+            typed_field_service: TypedFieldService::new(&db),
+            health_service: health,
+            image_mirror_service: mirrors,
+            storage,
+            image_service: images,
             anime_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("anime"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_history("anime", revisions.clone())
+            .with_content_policy(content_policy.clone()),
+            chapter_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
+                db.collection("fan_work_chapters"),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_unique(
+                "work_number",
+                &["work", "number"],
+                ErrorCode::ChapterDuplicate,
+                "This chapter already exists",
+            )
+            .with_edit_tracking(),
             character_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("characters"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_history("characters", revisions.clone()),
             club_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("clubs"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone()),
+            comment_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
+                db.collection("comments"),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_edit_tracking(),
+            fan_work_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
+                db.collection("fan_works"),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_edit_tracking(),
             genre_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("genres"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone()),
+            list_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
+                db.collection("list_entries"),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_unique(
+                "user_entry",
+                &["user", "entry"],
+                ErrorCode::ListEntryDuplicate,
+                "Entry is already on your list",
+            ),
             magazine_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("magazines"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_history("magazines", revisions.clone()),
             manga_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("manga"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_history("manga", revisions.clone())
+            .with_content_policy(content_policy),
             people_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("people"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_history("people", revisions.clone()),
+            post_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
+                db.collection("posts"),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_edit_tracking(),
             producer_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("producers"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_history("producers", revisions.clone()),
             review_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("reviews"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone())
+            .with_unique(
+                "entry_user",
+                &["entry", "user"],
+                ErrorCode::ReviewDuplicate,
+                "You already reviewed this entry",
+            )
+            .with_edit_tracking()
+            .with_history("reviews", revisions.clone()),
+            topic_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
+                db.collection("topics"),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone()),
             user_service: CrudServiceImpl::new(Arc::from(DatabaseRepository::new(
                 db.collection("users"),
-            ))),
+            )))
+            .with_soft_delete()
+            .with_integrity(integrity.clone())
+            .with_counters(counters.clone()),
         }
     }
+
+    /// Creates the indexes the services rely on.
+    ///
+    /// # Returns
+    /// The duplicates moved to the trash before a unique index was first built, or an `AppError`
+    /// if an index could not be created.
+    pub async fn ensure_indexes(&self) -> Result<Vec<TrashedDuplicate>, AppError> {
+        let retention = Duration::from_secs(self.config.trash_retention_days * 24 * 60 * 60);
+
+        self.anime_service.ensure_trash_retention(retention).await?;
+        self.chapter_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.character_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.club_service.ensure_trash_retention(retention).await?;
+        self.comment_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.fan_work_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.genre_service.ensure_trash_retention(retention).await?;
+        self.list_service.ensure_trash_retention(retention).await?;
+        self.magazine_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.manga_service.ensure_trash_retention(retention).await?;
+        self.people_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.post_service.ensure_trash_retention(retention).await?;
+        self.producer_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.review_service
+            .ensure_trash_retention(retention)
+            .await?;
+        self.topic_service.ensure_trash_retention(retention).await?;
+        self.user_service.ensure_trash_retention(retention).await?;
+        self.reaction_service.ensure_indexes().await?;
+        self.comment_reaction_service.ensure_indexes().await?;
+        self.moderation_service.ensure_indexes().await?;
+        self.membership_service.ensure_indexes().await?;
+        self.read_marker_service.ensure_indexes().await?;
+        self.follow_service.ensure_indexes().await?;
+        self.activity_service.ensure_indexes().await?;
+        self.notification_service.ensure_indexes().await?;
+        self.fan_work_interaction_service.ensure_indexes().await?;
+        self.image_mirror_service.ensure_indexes().await?;
+
+        let mut trashed = self.list_service.ensure_unique_indexes().await?;
+        trashed.extend(self.review_service.ensure_unique_indexes().await?);
+        trashed.extend(self.chapter_service.ensure_unique_indexes().await?);
+        Ok(trashed)
+    }
 }
 
+/// The denormalized counters maintained from the catalogue, the comments and the clubs.
+///
+/// Genres are counted per media type, and matched by name where the title only embeds
+/// the name of the genre. Magazines are matched by name, as serializations carry no ID.
+/// Reviews count all their comments, comments their direct replies, clubs their members,
+/// topics their posts, users their followers and the users they follow, and fan works their
+/// chapters and likes. The reactions to reviews and comments are counted per type and overall.
+fn counted_tags() -> Vec<Counter> {
+    let mut counters = Vec::new();
+    for (source, scope) in [("anime", "anime"), ("manga", "manga")] {
+        counters.push(Counter {
+            target: "genres",
+            target_key: "mal_id",
+            scope: Some(("type", scope)),
+            source,
+            source_scope: None,
+            field: "genres",
+            key: Some("mal_id"),
+            count: "count",
+        });
+        for field in ["explicit_genres", "themes", "demographics"] {
+            counters.push(Counter {
+                target: "genres",
+                target_key: "name",
+                scope: Some(("type", scope)),
+                source,
+                source_scope: None,
+                field,
+                key: Some("name"),
+                count: "count",
+            });
+        }
+    }
+    counters.push(Counter {
+        target: "producers",
+        target_key: "mal_id",
+        scope: None,
+        source: "anime",
+        source_scope: None,
+        field: "producers",
+        key: Some("mal_id"),
+        count: "count",
+    });
+    counters.push(Counter {
+        target: "magazines",
+        target_key: "name",
+        scope: None,
+        source: "manga",
+        source_scope: None,
+        field: "serializations",
+        key: Some("name"),
+        count: "count",
+    });
+    counters.push(Counter {
+        target: "reviews",
+        target_key: "_id",
+        scope: None,
+        source: "comments",
+        source_scope: None,
+        field: "review",
+        key: None,
+        count: "comment_count",
+    });
+    counters.push(Counter {
+        target: "clubs",
+        target_key: "_id",
+        scope: None,
+        source: "club_members",
+        source_scope: None,
+        field: "club",
+        key: None,
+        count: "member_count",
+    });
+    counters.push(Counter {
+        target: "topics",
+        target_key: "_id",
+        scope: None,
+        source: "posts",
+        source_scope: None,
+        field: "topic",
+        key: None,
+        count: "post_count",
+    });
+    counters.push(Counter {
+        target: "comments",
+        target_key: "_id",
+        scope: None,
+        source: "comments",
+        source_scope: None,
+        field: "parent",
+        key: None,
+        count: "reply_count",
+    });
+    counters.push(Counter {
+        target: "users",
+        target_key: "_id",
+        scope: None,
+        source: "follows",
+        source_scope: None,
+        field: "followee",
+        key: None,
+        count: "follower_count",
+    });
+    counters.push(Counter {
+        target: "users",
+        target_key: "_id",
+        scope: None,
+        source: "follows",
+        source_scope: None,
+        field: "follower",
+        key: None,
+        count: "following_count",
+    });
+    counters.push(Counter {
+        target: "fan_works",
+        target_key: "_id",
+        scope: None,
+        source: "fan_work_chapters",
+        source_scope: None,
+        field: "work",
+        key: None,
+        count: "chapter_count",
+    });
+    counters.push(Counter {
+        target: "fan_works",
+        target_key: "_id",
+        scope: None,
+        source: "fan_work_likes",
+        source_scope: None,
+        field: "work",
+        key: None,
+        count: "like_count",
+    });
+    for (target, source) in [
+        ("reviews", "review_reactions"),
+        ("comments", "comment_reactions"),
+    ] {
+        for reaction in ReactionType::ALL {
+            counters.push(Counter {
+                target,
+                target_key: "_id",
+                scope: None,
+                source,
+                source_scope: Some(("reaction", reaction.field())),
+                field: "target",
+                key: None,
+                count: reaction.counter(),
+            });
+        }
+        counters.push(Counter {
+            target,
+            target_key: "_id",
+            scope: None,
+            source,
+            source_scope: None,
+            field: "target",
+            key: None,
+            count: "reactions.overall",
+        });
+    }
+    counters
+}
+
+/// The moderation rules from the configuration, reading the word list if one is set.
+///
+/// A word list that cannot be read is reported and replaced with an empty one, so that the
+/// server still starts, holding reviews on reports and rapid posting only.
+fn moderation_rules(config: &AppConfig) -> ModerationRules {
+    let words = match config.moderation_word_list.as_str() {
+        "" => HashSet::new(),
+        path => match std::fs::read_to_string(path) {
+            Ok(words) => ModerationRules::parse_words(&words),
+            Err(e) => {
+                eprintln!(
+                    "{} the word list {} could not be read, no word is flagged: {}",
+                    "Moderation:".yellow(),
+                    path,
+                    e
+                );
+                HashSet::new()
+            }
+        },
+    };
+    ModerationRules {
+        words,
+        rapid_posts: config.moderation_rapid_posts,
+        window: Duration::from_secs(config.moderation_rapid_window_minutes * 60),
+        hold_weight: config.moderation_hold_weight,
+    }
+}
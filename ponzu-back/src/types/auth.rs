@@ -0,0 +1,127 @@
+use crate::dto::user::UserDto;
+use crate::types::app_config::AppConfig;
+use crate::types::app_error::AppError;
+use crate::types::app_state::AppState;
+use crate::types::error_response::ErrorCode;
+use actix_web::dev::Payload;
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::{web, FromRequest, HttpRequest};
+use futures::future::{ready, Ready};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Claims carried by an access token.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Claims {
+    pub sub: String,
+    pub username: String,
+    pub is_staff: bool,
+    pub exp: u64,
+}
+
+/// The user the current request is made on behalf of.
+///
+/// Extracted from the `Authorization: Bearer <token>` header. Use `Option<AuthUser>`
+/// in handlers that also serve anonymous callers.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub id: String,
+    pub is_staff: bool,
+}
+
+impl AuthUser {
+    /// Ensures the user is a staff member.
+    ///
+    /// # Returns
+    /// An `AppError` with status 403 if the user is not a staff member.
+    pub fn ensure_staff(&self) -> Result<(), AppError> {
+        if self.is_staff {
+            Ok(())
+        } else {
+            Err(AppError::from((
+                ErrorCode::StaffRequired,
+                "Staff privileges required",
+            )))
+        }
+    }
+}
+
+impl From<Claims> for AuthUser {
+    fn from(claims: Claims) -> Self {
+        Self {
+            id: claims.sub,
+            is_staff: claims.is_staff,
+        }
+    }
+}
+
+impl FromRequest for AuthUser {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(authenticate(req))
+    }
+}
+
+/// Resolves the `AuthUser` from the request headers.
+fn authenticate(req: &HttpRequest) -> Result<AuthUser, AppError> {
+    let state = req
+        .app_data::<web::Data<AppState>>()
+        .ok_or_else(|| AppError::from("Application state is not configured"))?;
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::from((ErrorCode::Unauthenticated, "Missing bearer token")))?;
+
+    decode_token(token, &state.config).map(AuthUser::from)
+}
+
+/// Issues an access token for the given user.
+///
+/// # Parameters
+/// - `user`: The user to issue the token for.
+/// - `config`: The application configuration.
+///
+/// # Returns
+/// The encoded token.
+pub fn issue_token(user: &UserDto, config: &AppConfig) -> Result<String, AppError> {
+    let expires_at = SystemTime::now() + Duration::from_secs(config.jwt_ttl_hours * 3600);
+    let claims = Claims {
+        sub: user.id.clone().unwrap_or_default(),
+        username: user.username.clone(),
+        is_staff: user.is_staff,
+        exp: expires_at
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| AppError::from(e.to_string()))?
+            .as_secs(),
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )
+    .map_err(|e| AppError::from(e.to_string()))
+}
+
+/// Decodes and validates an access token.
+///
+/// # Parameters
+/// - `token`: The encoded token.
+/// - `config`: The application configuration.
+///
+/// # Returns
+/// The claims of the token, or an `AppError` with status 401 if the token is invalid.
+pub fn decode_token(token: &str, config: &AppConfig) -> Result<Claims, AppError> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map(|data| data.claims)
+    .map_err(|_| AppError::from((ErrorCode::TokenInvalid, "Invalid or expired token")))
+}
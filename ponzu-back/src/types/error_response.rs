@@ -1,21 +1,233 @@
+use std::collections::BTreeMap;
 use std::fmt;
+use actix_web::http::StatusCode;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-/// Serializable error object with error message and status code.
-#[derive(Debug, Serialize)]
+/// The stable, machine-readable code of an error, serialized in SCREAMING_SNAKE_CASE.
+///
+/// Each code always comes with the same HTTP status. Clients should branch on the code, as
+/// messages may be reworded.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// 400: The request is malformed.
+    BadRequest,
+    /// 400: A path parameter is not a valid ID.
+    InvalidId,
+    /// 400: A list entry or review points at something else than an anime or a manga.
+    InvalidEntryType,
+    /// 400: A comment, post, chapter or title is empty.
+    EmptyContent,
+    /// 400: The action does not apply to this kind of fan work.
+    WrongWorkKind,
+    /// 400: Rerating a fan work requires a new rating.
+    RatingRequired,
+    /// 400: Users cannot follow themselves.
+    FollowSelf,
+    /// 400: The image is too small or too large in pixels.
+    ImageDimensions,
+    /// 401: The request is not authenticated, e.g. it carries no bearer token.
+    Unauthenticated,
+    /// 401: The bearer token is invalid or expired.
+    TokenInvalid,
+    /// 401: The username or the password is wrong.
+    InvalidCredentials,
+    /// 403: The caller may not do this.
+    Forbidden,
+    /// 403: The action requires staff privileges.
+    StaffRequired,
+    /// 403: Only the author of the content may do this.
+    NotAuthor,
+    /// 403: Only the owner of the club may do this.
+    NotOwner,
+    /// 403: Only the officers of the club may do this.
+    NotOfficer,
+    /// 403: Only the members of the club may do this.
+    NotMember,
+    /// 403: The target member holds a rank as high as the caller's.
+    RankTooLow,
+    /// 403: The caller is banned from the club.
+    ClubBanned,
+    /// 403: The topic is locked.
+    TopicLocked,
+    /// 403: The staff marked the review as a spoiler, so its author cannot clear the flag.
+    SpoilerLocked,
+    /// 403: The section of the profile is hidden from the caller.
+    ProfilePrivate,
+    /// 403: Only adults may opt into explicit content.
+    ExplicitContentRestricted,
+    /// 404: The resource does not exist.
+    NotFound,
+    /// 404: The resource has no trash.
+    TrashDisabled,
+    /// 404: The resource has no edit history.
+    HistoryNotTracked,
+    /// 409: The request conflicts with the current state of the resource.
+    Conflict,
+    /// 409: A value that must be unique is already taken.
+    Duplicate,
+    /// 409: The username or the email is already taken.
+    AccountTaken,
+    /// 409: The caller already reviewed the entry.
+    ReviewDuplicate,
+    /// 409: The entry is already on the caller's list.
+    ListEntryDuplicate,
+    /// 409: The fan work already has a chapter with this number.
+    ChapterDuplicate,
+    /// 409: The caller already reported the content.
+    ReportDuplicate,
+    /// 409: The caller already likes the fan work.
+    LikeDuplicate,
+    /// 409: The caller already follows the user.
+    FollowDuplicate,
+    /// 409: The user is banned from the club.
+    MemberBanned,
+    /// 409: A request to join the club is already pending.
+    JoinRequestPending,
+    /// 409: The owner must hand the club over first.
+    OwnerMustTransfer,
+    /// 409: The caller or the user is already a member of the club.
+    AlreadyMember,
+    /// 409: The resource is still referenced by other documents.
+    StillReferenced,
+    /// 413: The request body is too large.
+    PayloadTooLarge,
+    /// 413: The image is larger than allowed.
+    ImageTooLarge,
+    /// 415: The request body has an unsupported format.
+    UnsupportedMediaType,
+    /// 415: The image has an unsupported format or cannot be decoded.
+    ImageUnsupported,
+    /// 422: The request body breaks validation rules. The invalid fields are listed.
+    ValidationFailed,
+    /// 422: The body references a document that does not exist.
+    ReferenceMissing,
+    /// 422: The replied comment does not exist.
+    ReplyMissing,
+    /// 422: The reply would be nested too deep.
+    ReplyTooDeep,
+    /// 500: An unexpected error occurred.
+    InternalError,
+    /// 500: The database failed.
+    DatabaseError,
+}
+
+impl ErrorCode {
+    /// The HTTP status the code is sent with.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::BadRequest
+            | ErrorCode::InvalidId
+            | ErrorCode::InvalidEntryType
+            | ErrorCode::EmptyContent
+            | ErrorCode::WrongWorkKind
+            | ErrorCode::RatingRequired
+            | ErrorCode::FollowSelf
+            | ErrorCode::ImageDimensions => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthenticated | ErrorCode::TokenInvalid | ErrorCode::InvalidCredentials => {
+                StatusCode::UNAUTHORIZED
+            }
+            ErrorCode::Forbidden
+            | ErrorCode::StaffRequired
+            | ErrorCode::NotAuthor
+            | ErrorCode::NotOwner
+            | ErrorCode::NotOfficer
+            | ErrorCode::NotMember
+            | ErrorCode::RankTooLow
+            | ErrorCode::ClubBanned
+            | ErrorCode::TopicLocked
+            | ErrorCode::SpoilerLocked
+            | ErrorCode::ProfilePrivate
+            | ErrorCode::ExplicitContentRestricted => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound | ErrorCode::TrashDisabled | ErrorCode::HistoryNotTracked => {
+                StatusCode::NOT_FOUND
+            }
+            ErrorCode::Conflict
+            | ErrorCode::Duplicate
+            | ErrorCode::AccountTaken
+            | ErrorCode::ReviewDuplicate
+            | ErrorCode::ListEntryDuplicate
+            | ErrorCode::ChapterDuplicate
+            | ErrorCode::ReportDuplicate
+            | ErrorCode::LikeDuplicate
+            | ErrorCode::FollowDuplicate
+            | ErrorCode::MemberBanned
+            | ErrorCode::JoinRequestPending
+            | ErrorCode::OwnerMustTransfer
+            | ErrorCode::AlreadyMember
+            | ErrorCode::StillReferenced => StatusCode::CONFLICT,
+            ErrorCode::PayloadTooLarge | ErrorCode::ImageTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::UnsupportedMediaType | ErrorCode::ImageUnsupported => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            ErrorCode::ValidationFailed
+            | ErrorCode::ReferenceMissing
+            | ErrorCode::ReplyMissing
+            | ErrorCode::ReplyTooDeep => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::InternalError | ErrorCode::DatabaseError => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// The generic code of an HTTP status, for errors raised without a specific code.
+    /// Unknown statuses fall back to `InternalError`.
+    pub fn for_status(status: u16) -> Self {
+        match status {
+            400 => ErrorCode::BadRequest,
+            401 => ErrorCode::Unauthenticated,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            409 => ErrorCode::Conflict,
+            413 => ErrorCode::PayloadTooLarge,
+            415 => ErrorCode::UnsupportedMediaType,
+            422 => ErrorCode::ValidationFailed,
+            _ => ErrorCode::InternalError,
+        }
+    }
+}
+
+/// Serializable error object with error code, status code and message.
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SerializableError {
-    error: String,
+    code: ErrorCode,
     status: u16,
+    message: String,
+    /// The messages of the invalid fields, by path, on validation errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<BTreeMap<String, Vec<String>>>,
+    /// The ID of the request, also sent in the `X-Request-Id` header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
 impl SerializableError {
-    pub fn new(error: String, status: u16) -> Self {
-        SerializableError { error, status }
+    pub fn new(code: ErrorCode, message: String) -> Self {
+        SerializableError {
+            code,
+            status: code.status().as_u16(),
+            message,
+            fields: None,
+            request_id: None,
+        }
+    }
+
+    /// Lists the invalid fields of a validation error.
+    pub fn with_fields(mut self, fields: BTreeMap<String, Vec<String>>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    /// Sets the ID of the request the error answers.
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
     }
 }
 
 impl fmt::Display for SerializableError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, r#"{{"error": "{}", "status": {}}}"#, self.error, self.status)
+        write!(f, r#"{{"code": "{:?}", "status": {}, "message": "{}"}}"#, self.code, self.status, self.message)
     }
 }
@@ -0,0 +1,543 @@
+use chrono::Weekday;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::RefOr;
+use utoipa::{PartialSchema, ToSchema};
+
+/// Declares an enum stored and served as a snake_case code, and read from either that code or
+/// the string Jikan uses for it. `Display` gives back the Jikan string.
+macro_rules! jikan_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $code:literal, $jikan:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)+
+        }
+
+        impl $name {
+            /// Every value, in declaration order.
+            pub const ALL: &'static [$name] = &[$($name::$variant,)+];
+
+            /// The code the value is stored and served as.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+
+            /// The string Jikan uses for the value.
+            pub fn jikan(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $jikan,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.jikan())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let s = s.trim();
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|v| {
+                        s.eq_ignore_ascii_case(v.code())
+                            || s.eq_ignore_ascii_case(v.jikan())
+                            || v.jikan()
+                                .split_once(" - ")
+                                .is_some_and(|(short, _)| s.eq_ignore_ascii_case(short))
+                    })
+                    .ok_or_else(|| format!("Unknown {} \"{}\"", stringify!($name), s))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.code())
+            }
+        }
+
+        impl PartialSchema for $name {
+            fn schema() -> RefOr<Schema> {
+                codes(Self::ALL.iter().map(|v| v.code()))
+            }
+        }
+
+        impl ToSchema for $name {}
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+jikan_enum! {
+    /// The age rating of an anime.
+    AgeRating {
+        G => "g", "G - All Ages",
+        Pg => "pg", "PG - Children",
+        Pg13 => "pg13", "PG-13 - Teens 13 or older",
+        R17 => "r17", "R - 17+ (violence & profanity)",
+        RPlus => "r_plus", "R+ - Mild Nudity",
+        Rx => "rx", "Rx - Hentai",
+    }
+}
+
+impl AgeRating {
+    /// The minimum age of the audience.
+    pub fn min_age(&self) -> u8 {
+        match self {
+            AgeRating::G | AgeRating::Pg => 0,
+            AgeRating::Pg13 => 13,
+            AgeRating::R17 | AgeRating::RPlus => 17,
+            AgeRating::Rx => 18,
+        }
+    }
+}
+
+jikan_enum! {
+    /// The airing status of an anime.
+    AnimeStatus {
+        FinishedAiring => "finished_airing", "Finished Airing",
+        CurrentlyAiring => "currently_airing", "Currently Airing",
+        NotYetAired => "not_yet_aired", "Not yet aired",
+    }
+}
+
+jikan_enum! {
+    /// The format of an anime.
+    AnimeType {
+        Tv => "tv", "TV",
+        Movie => "movie", "Movie",
+        Ova => "ova", "OVA",
+        Special => "special", "Special",
+        Ona => "ona", "ONA",
+        Music => "music", "Music",
+        Cm => "cm", "CM",
+        Pv => "pv", "PV",
+        TvSpecial => "tv_special", "TV Special",
+    }
+}
+
+jikan_enum! {
+    /// The material an anime is adapted from.
+    AnimeSource {
+        Original => "original", "Original",
+        Manga => "manga", "Manga",
+        FourKomaManga => "4_koma_manga", "4-koma manga",
+        WebManga => "web_manga", "Web manga",
+        DigitalManga => "digital_manga", "Digital manga",
+        Novel => "novel", "Novel",
+        LightNovel => "light_novel", "Light novel",
+        VisualNovel => "visual_novel", "Visual novel",
+        WebNovel => "web_novel", "Web novel",
+        Game => "game", "Game",
+        CardGame => "card_game", "Card game",
+        Book => "book", "Book",
+        PictureBook => "picture_book", "Picture book",
+        Radio => "radio", "Radio",
+        Music => "music", "Music",
+        MixedMedia => "mixed_media", "Mixed media",
+        Other => "other", "Other",
+        Unknown => "unknown", "Unknown",
+    }
+}
+
+jikan_enum! {
+    /// The publishing status of a manga.
+    MangaStatus {
+        Finished => "finished", "Finished",
+        Publishing => "publishing", "Publishing",
+        OnHiatus => "on_hiatus", "On Hiatus",
+        Discontinued => "discontinued", "Discontinued",
+        NotYetPublished => "not_yet_published", "Not yet published",
+    }
+}
+
+jikan_enum! {
+    /// The format of a manga.
+    MangaType {
+        Manga => "manga", "Manga",
+        Novel => "novel", "Novel",
+        LightNovel => "light_novel", "Light Novel",
+        OneShot => "one_shot", "One-shot",
+        Doujinshi => "doujinshi", "Doujinshi",
+        Manhua => "manhua", "Manhua",
+        Manhwa => "manhwa", "Manhwa",
+        Oel => "oel", "OEL",
+    }
+}
+
+/// The Jikan wording of a missing duration.
+const UNKNOWN: &str = "Unknown";
+
+/// The length of an episode, stored and served in seconds.
+///
+/// It is read from a number of seconds or from the Jikan wording, e.g. `24 min per ep` or
+/// `1 hr 55 min`. `Display` gives the length alone, e.g. `24 min`, and `jikan` the wording of a
+/// given anime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(transparent)]
+pub struct EpisodeDuration(pub u32);
+
+impl fmt::Display for EpisodeDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            return f.write_str(UNKNOWN);
+        }
+
+        let (hours, minutes, seconds) = (self.0 / 3600, self.0 % 3600 / 60, self.0 % 60);
+        let mut parts = Vec::new();
+        if hours > 0 {
+            parts.push(format!("{} hr", hours));
+        }
+        if minutes > 0 {
+            parts.push(format!("{} min", minutes));
+        }
+        if seconds > 0 {
+            parts.push(format!("{} sec", seconds));
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+impl EpisodeDuration {
+    /// The string Jikan uses for the duration of an anime, which is per episode unless the anime
+    /// has a single one.
+    ///
+    /// # Parameters
+    /// - `episodes`: The number of episodes of the anime, `None` if unknown.
+    pub fn jikan(&self, episodes: Option<u32>) -> String {
+        match self.0 == 0 || episodes == Some(1) {
+            true => self.to_string(),
+            false => format!("{} per ep", self),
+        }
+    }
+}
+
+impl FromStr for EpisodeDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let mut seconds = None;
+        for pair in words.windows(2) {
+            let Ok(amount) = pair[0].parse::<u32>() else {
+                continue;
+            };
+            let unit = match pair[1].trim_end_matches('.') {
+                "hr" | "hrs" | "hour" | "hours" => 3600,
+                "min" | "mins" | "minute" | "minutes" => 60,
+                "sec" | "secs" | "second" | "seconds" => 1,
+                _ => continue,
+            };
+            seconds = Some(seconds.unwrap_or(0) + amount * unit);
+        }
+        seconds
+            .map(EpisodeDuration)
+            .ok_or_else(|| format!("Unknown duration \"{}\"", s))
+    }
+}
+
+/// A duration as stored by this API or as worded by Jikan.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredDuration {
+    Seconds(u32),
+    Jikan(String),
+}
+
+impl<'de> Deserialize<'de> for EpisodeDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match StoredDuration::deserialize(deserializer)? {
+            StoredDuration::Seconds(seconds) => Ok(EpisodeDuration(seconds)),
+            StoredDuration::Jikan(s) => s.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Deserializes an optional duration, reading the Jikan `Unknown` and other durations without
+/// any unit as `None`.
+pub fn deserialize_option_duration<'de, D>(
+    deserializer: D,
+) -> Result<Option<EpisodeDuration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<StoredDuration>::deserialize(deserializer)? {
+        Some(StoredDuration::Seconds(seconds)) => Some(EpisodeDuration(seconds)),
+        Some(StoredDuration::Jikan(s)) => s.parse().ok(),
+        None => None,
+    })
+}
+
+/// The day of the week an anime airs on, stored and served as its lowercase name.
+///
+/// It is read from the name or from the plural Jikan uses, e.g. `Saturdays`. `Display` gives the
+/// capitalized name, e.g. `Saturday`, and `jikan` the plural.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BroadcastDay(pub Weekday);
+
+impl fmt::Display for BroadcastDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&capitalize(self.name()))
+    }
+}
+
+impl BroadcastDay {
+    /// Every day, from Monday.
+    pub const ALL: [BroadcastDay; 7] = [
+        BroadcastDay(Weekday::Mon),
+        BroadcastDay(Weekday::Tue),
+        BroadcastDay(Weekday::Wed),
+        BroadcastDay(Weekday::Thu),
+        BroadcastDay(Weekday::Fri),
+        BroadcastDay(Weekday::Sat),
+        BroadcastDay(Weekday::Sun),
+    ];
+
+    /// The string Jikan uses for the day, e.g. `Saturdays`.
+    pub fn jikan(&self) -> String {
+        format!("{}s", self)
+    }
+
+    /// The lowercase name of the day, which it is stored and served as.
+    pub fn name(&self) -> &'static str {
+        match self.0 {
+            Weekday::Mon => "monday",
+            Weekday::Tue => "tuesday",
+            Weekday::Wed => "wednesday",
+            Weekday::Thu => "thursday",
+            Weekday::Fri => "friday",
+            Weekday::Sat => "saturday",
+            Weekday::Sun => "sunday",
+        }
+    }
+}
+
+impl FromStr for BroadcastDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let day = s.trim();
+        day.strip_suffix('s')
+            .unwrap_or(day)
+            .parse::<Weekday>()
+            .map(BroadcastDay)
+            .map_err(|_| format!("Unknown day \"{}\"", s))
+    }
+}
+
+impl Serialize for BroadcastDay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl PartialSchema for BroadcastDay {
+    fn schema() -> RefOr<Schema> {
+        codes(Self::ALL.iter().map(|day| day.name()))
+    }
+}
+
+impl ToSchema for BroadcastDay {}
+
+impl<'de> Deserialize<'de> for BroadcastDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The schema of a string holding one of the given codes.
+fn codes<'a>(codes: impl IntoIterator<Item = &'a str>) -> RefOr<Schema> {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .enum_values(Some(codes))
+        .into()
+}
+
+/// Uppercases the first letter of a word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        deserialize_option_duration, AgeRating, AnimeSource, AnimeStatus, AnimeType, BroadcastDay,
+        EpisodeDuration, MangaStatus, MangaType,
+    };
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::collections::HashSet;
+    use std::fmt::{Debug, Display};
+    use std::str::FromStr;
+
+    /// Checks that every value is read from its code and its Jikan string, is displayed as the
+    /// latter and served as the former.
+    fn round_trips<T>(all: &[T], code: fn(&T) -> &'static str, jikan: fn(&T) -> &'static str)
+    where
+        T: Copy + Debug + PartialEq + Display + FromStr + Serialize + DeserializeOwned,
+        T::Err: Debug,
+    {
+        for value in all {
+            assert_eq!(code(value).parse::<T>().unwrap(), *value);
+            assert_eq!(jikan(value).parse::<T>().unwrap(), *value);
+            assert_eq!(jikan(value).to_uppercase().parse::<T>().unwrap(), *value);
+            assert_eq!(format!(" {} ", jikan(value)).parse::<T>().unwrap(), *value);
+            assert_eq!(value.to_string(), jikan(value));
+            assert_eq!(serde_json::to_value(value).unwrap(), json!(code(value)));
+            assert_eq!(
+                serde_json::from_value::<T>(json!(code(value))).unwrap(),
+                *value
+            );
+            assert_eq!(
+                serde_json::from_value::<T>(json!(jikan(value))).unwrap(),
+                *value
+            );
+        }
+        assert_eq!(
+            all.iter().map(code).collect::<HashSet<_>>().len(),
+            all.len()
+        );
+        assert_eq!(
+            all.iter().map(jikan).collect::<HashSet<_>>().len(),
+            all.len()
+        );
+    }
+
+    #[test]
+    fn every_value_is_read_from_its_code_and_its_jikan_string() {
+        round_trips(AgeRating::ALL, AgeRating::code, AgeRating::jikan);
+        round_trips(AnimeStatus::ALL, AnimeStatus::code, AnimeStatus::jikan);
+        round_trips(AnimeType::ALL, AnimeType::code, AnimeType::jikan);
+        round_trips(AnimeSource::ALL, AnimeSource::code, AnimeSource::jikan);
+        round_trips(MangaStatus::ALL, MangaStatus::code, MangaStatus::jikan);
+        round_trips(MangaType::ALL, MangaType::code, MangaType::jikan);
+    }
+
+    #[test]
+    fn age_ratings_are_read_from_their_short_form() {
+        let short = ["G", "PG", "PG-13", "R", "R+", "Rx"];
+        for (rating, short) in AgeRating::ALL.iter().zip(short) {
+            assert_eq!(short.parse::<AgeRating>().unwrap(), *rating);
+        }
+    }
+
+    #[test]
+    fn unknown_values_are_refused() {
+        assert!("Sequel".parse::<AnimeSource>().is_err());
+        assert!("".parse::<AnimeType>().is_err());
+        assert!("PG-15".parse::<AgeRating>().is_err());
+        assert!(serde_json::from_value::<MangaStatus>(json!("Cancelled")).is_err());
+    }
+
+    #[test]
+    fn durations_per_episode_are_worded_per_episode() {
+        let duration = "24 min per ep".parse::<EpisodeDuration>().unwrap();
+        assert_eq!(duration, EpisodeDuration(24 * 60));
+        assert_eq!(duration.to_string(), "24 min");
+        assert_eq!(duration.jikan(Some(12)), "24 min per ep");
+        assert_eq!(duration.jikan(None), "24 min per ep");
+
+        let duration = "1 hr per ep".parse::<EpisodeDuration>().unwrap();
+        assert_eq!(duration.jikan(Some(2)), "1 hr per ep");
+        let duration = "23 sec per ep".parse::<EpisodeDuration>().unwrap();
+        assert_eq!(duration.jikan(Some(26)), "23 sec per ep");
+    }
+
+    #[test]
+    fn durations_of_a_single_episode_are_worded_as_a_total() {
+        let duration = "1 hr 55 min".parse::<EpisodeDuration>().unwrap();
+        assert_eq!(duration, EpisodeDuration(3600 + 55 * 60));
+        assert_eq!(duration.to_string(), "1 hr 55 min");
+        assert_eq!(duration.jikan(Some(1)), "1 hr 55 min");
+
+        let duration = "2 hr 1 min 5 sec".parse::<EpisodeDuration>().unwrap();
+        assert_eq!(duration, EpisodeDuration(7265));
+        assert_eq!(duration.jikan(Some(1)), "2 hr 1 min 5 sec");
+    }
+
+    #[test]
+    fn unknown_durations_are_worded_as_jikan_does() {
+        assert!("Unknown".parse::<EpisodeDuration>().is_err());
+        assert_eq!(EpisodeDuration(0).to_string(), "Unknown");
+        assert_eq!(EpisodeDuration(0).jikan(Some(12)), "Unknown");
+    }
+
+    #[test]
+    fn durations_are_stored_in_seconds() {
+        #[derive(Deserialize)]
+        struct Anime {
+            #[serde(default, deserialize_with = "deserialize_option_duration")]
+            duration: Option<EpisodeDuration>,
+        }
+        let duration = |value| {
+            serde_json::from_value::<Anime>(json!({ "duration": value }))
+                .unwrap()
+                .duration
+        };
+
+        assert_eq!(
+            serde_json::to_value(EpisodeDuration(1440)).unwrap(),
+            json!(1440)
+        );
+        assert_eq!(duration(json!(1440)), Some(EpisodeDuration(1440)));
+        assert_eq!(
+            duration(json!("24 min per ep")),
+            Some(EpisodeDuration(1440))
+        );
+        assert_eq!(duration(json!("Unknown")), None);
+        assert_eq!(duration(json!(null)), None);
+        assert!(serde_json::from_value::<EpisodeDuration>(json!("Unknown")).is_err());
+    }
+
+    #[test]
+    fn every_day_is_read_from_its_name_and_its_jikan_plural() {
+        let days = [
+            ("monday", "Monday", "Mondays"),
+            ("tuesday", "Tuesday", "Tuesdays"),
+            ("wednesday", "Wednesday", "Wednesdays"),
+            ("thursday", "Thursday", "Thursdays"),
+            ("friday", "Friday", "Fridays"),
+            ("saturday", "Saturday", "Saturdays"),
+            ("sunday", "Sunday", "Sundays"),
+        ];
+        for (day, (name, display, jikan)) in BroadcastDay::ALL.iter().zip(days) {
+            assert_eq!(name.parse::<BroadcastDay>().unwrap(), *day);
+            assert_eq!(display.parse::<BroadcastDay>().unwrap(), *day);
+            assert_eq!(jikan.parse::<BroadcastDay>().unwrap(), *day);
+            assert_eq!(day.name(), name);
+            assert_eq!(day.to_string(), display);
+            assert_eq!(day.jikan(), jikan);
+            assert_eq!(serde_json::to_value(day).unwrap(), json!(name));
+            assert_eq!(
+                serde_json::from_value::<BroadcastDay>(json!(jikan)).unwrap(),
+                *day
+            );
+        }
+        assert!("Unknown".parse::<BroadcastDay>().is_err());
+        assert!("Other".parse::<BroadcastDay>().is_err());
+    }
+}
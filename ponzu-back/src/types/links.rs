@@ -1,21 +1,60 @@
-use serde::{Deserialize, Serialize};
+use crate::types::validation::url_or_empty;
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
 /// External links for the title.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Validate, ToSchema)]
 pub struct ExternalLink {
     pub name: String,
+    #[validate(custom(function = "url_or_empty"))]
     pub url: String,
 }
 
 /// Images for the title in different formats.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
 pub struct Images {
     pub jpg: ImageUrls,
     pub webp: ImageUrls,
 }
 
+impl Images {
+    /// Images using the same URL for every format and size.
+    pub fn uniform(url: String) -> Self {
+        let urls = ImageUrls {
+            image_url: url.clone(),
+            small_image_url: url.clone(),
+            large_image_url: url,
+        };
+        Self {
+            jpg: urls.clone(),
+            webp: urls,
+        }
+    }
+}
+
+/// Deserializes optional images, accepting the single URL stored before images were uploaded.
+pub fn deserialize_option_images<'de, D>(deserializer: D) -> Result<Option<Images>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredImages {
+        Url(String),
+        Images(Images),
+    }
+
+    Ok(match Option::<StoredImages>::deserialize(deserializer)? {
+        Some(StoredImages::Url(url)) => Some(Images::uniform(url)),
+        Some(StoredImages::Images(images)) => Some(images),
+        None => None,
+    })
+}
+
 /// Image URLs for the title.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
 pub struct ImageUrls {
     pub image_url: String,
     pub small_image_url: String,
@@ -23,7 +62,7 @@ pub struct ImageUrls {
 }
 
 /// Trailer for the title.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Trailer {
     pub youtube_id: String,
     pub url: String,
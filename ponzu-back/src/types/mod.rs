@@ -1,5 +1,11 @@
+pub mod app_config;
 pub mod app_error;
 pub mod app_state;
+pub mod auth;
 pub mod error_response;
+pub mod jikan;
 pub mod links;
+pub mod openapi;
+pub mod request_id;
 pub mod title_meta;
+pub mod validation;
@@ -0,0 +1,27 @@
+use utoipa::openapi::schema::{KnownFormat, ObjectBuilder, Schema, SchemaFormat, Type};
+use utoipa::openapi::RefOr;
+use utoipa::{PartialSchema, ToSchema};
+
+/// An ID as served by the API, in the MongoDB Extended JSON form of an ObjectId.
+#[derive(ToSchema)]
+#[schema(as = ObjectId, example = json!({ "$oid": "65f1c0de2a4e5b7f0c9d1a23" }))]
+#[allow(dead_code)]
+pub struct ObjectIdJson {
+    /// The hexadecimal form of the ID.
+    #[schema(rename = "$oid")]
+    oid: String,
+}
+
+/// The raw bytes of a file, such as an uploaded or served image.
+pub struct Binary;
+
+impl PartialSchema for Binary {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Binary)))
+            .into()
+    }
+}
+
+impl ToSchema for Binary {}
@@ -0,0 +1,63 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use mongodb::bson::oid::ObjectId;
+
+/// The header carrying the ID of a request, read from the request and echoed in the response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The longest request ID accepted from a client.
+const MAX_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The ID of the current request, or `None` outside of a request.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Gives every request an ID, sent back in the `X-Request-Id` header and in the error bodies.
+///
+/// The ID sent by the client, such as a proxy, is kept when it is at most 64 visible ASCII
+/// characters, so that the logs of both sides can be matched. Otherwise a new one is generated.
+pub async fn assign_request_id(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| {
+            !v.is_empty() && v.len() <= MAX_LENGTH && v.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| ObjectId::new().to_hex());
+    let header = HeaderValue::from_str(&id).ok();
+    if let Some(value) = &header {
+        // Also set on the request, for the logger
+        req.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value.clone());
+    }
+
+    // The errors of the inner middlewares are turned into responses within the scope, so that
+    // their bodies carry the ID as well.
+    let http_req = req.request().clone();
+    let mut res = REQUEST_ID
+        .scope(id.clone(), async move {
+            match next.call(req).await {
+                Ok(res) => res.map_into_boxed_body(),
+                Err(e) => ServiceResponse::from_err(e, http_req).map_into_boxed_body(),
+            }
+        })
+        .await;
+    if let Some(value) = header {
+        res.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+    Ok(res)
+}
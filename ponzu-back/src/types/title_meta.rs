@@ -1,28 +1,29 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Commonly-used types for title metadata with type, name, and URL fields.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct MalEntity {
     pub r#type: String,
     pub name: String,
 }
 
 /// Title object with type and title fields.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Title {
     pub r#type: String,
     pub title: String,
 }
 
 /// Theme object representing openings and endings.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Theme {
     pub openings: Vec<String>,
     pub endings: Vec<String>,
 }
 
 /// Relation object with relation and entry fields.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Relation {
     pub relation: String,
     pub entry: Vec<MalEntity>,
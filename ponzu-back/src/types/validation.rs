@@ -0,0 +1,198 @@
+use crate::types::app_error::AppError;
+use actix_web::dev::Payload;
+use actix_web::error::JsonPayloadError;
+use actix_web::web::Json;
+use actix_web::{FromRequest, HttpRequest};
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+use validator::{Validate, ValidateUrl, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+/// The messages of the invalid fields of a request body, by path, e.g. `aired.from` or
+/// `external[0].url`.
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+/// A JSON request body checked against the rules declared on its type with `#[validate]`.
+///
+/// Bodies breaking a rule, or holding a value of the wrong type such as an unknown enum
+/// variant, are refused with a 422 listing the invalid fields. Malformed JSON is refused
+/// with a 400.
+#[derive(Debug)]
+pub struct ValidJson<T>(pub T);
+
+impl<T> ValidJson<T> {
+    /// Unwraps the validated body.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ValidJson<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validate + 'static> FromRequest for ValidJson<T> {
+    type Error = AppError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = Json::<T>::from_request(req, payload);
+        async move {
+            let body = json
+                .await
+                .map_err(|e| match e.as_error::<JsonPayloadError>() {
+                    Some(JsonPayloadError::Deserialize(de)) if de.is_data() => {
+                        AppError::Validation(FieldErrors::from([(
+                            "body".to_string(),
+                            vec![de.to_string()],
+                        )]))
+                    }
+                    _ => {
+                        let status = e.as_response_error().status_code().as_u16();
+                        AppError::from((e.to_string(), status))
+                    }
+                })?
+                .into_inner();
+            body.validate()
+                .map_err(|e| AppError::Validation(field_errors(&e)))?;
+            Ok(ValidJson(body))
+        }
+        .boxed_local()
+    }
+}
+
+/// Flattens the errors of a body into messages by field path.
+pub fn field_errors(errors: &ValidationErrors) -> FieldErrors {
+    let mut fields = FieldErrors::new();
+    collect(errors, "", &mut fields);
+    fields
+}
+
+/// Collects the messages of nested errors under a path prefix.
+///
+/// The errors of a whole struct, raised by cross-field rules, are reported under the path of
+/// the struct, or under `body` for the request body itself.
+fn collect(errors: &ValidationErrors, prefix: &str, fields: &mut FieldErrors) {
+    for (field, kind) in errors.errors() {
+        let path = match (prefix, field.as_ref()) {
+            ("", "__all__") => "body".to_string(),
+            (prefix, "__all__") => prefix.to_string(),
+            ("", field) => field.to_string(),
+            (prefix, field) => format!("{}.{}", prefix, field),
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => fields
+                .entry(path)
+                .or_default()
+                .extend(errors.iter().map(describe)),
+            ValidationErrorsKind::Struct(errors) => collect(errors, &path, fields),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect(errors, &format!("{}[{}]", path, index), fields);
+                }
+            }
+        }
+    }
+}
+
+/// Words the message of a broken rule.
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).map(|v| v.to_string());
+    match (error.code.as_ref(), param("min"), param("max")) {
+        ("length", _, _) if error.params.contains_key("equal") => {
+            format!(
+                "must have a length of {}",
+                param("equal").unwrap_or_default()
+            )
+        }
+        ("length", Some(min), Some(max)) => {
+            format!("must have a length between {} and {}", min, max)
+        }
+        ("length", Some(min), None) => format!("must have a length of at least {}", min),
+        ("length", None, Some(max)) => format!("must have a length of at most {}", max),
+        ("range", Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+        ("range", Some(min), None) => format!("must be at least {}", min),
+        ("range", None, Some(max)) => format!("must be at most {}", max),
+        ("email", _, _) => "must be a valid email address".to_string(),
+        ("url", _, _) => "must be a valid URL".to_string(),
+        (code, _, _) => format!("is invalid ({})", code),
+    }
+}
+
+/// Builds the error of a custom rule.
+fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+/// Checks that a value names a kind of entry, `anime` or `manga`.
+pub fn entry_type(value: &str) -> Result<(), ValidationError> {
+    match value {
+        "anime" | "manga" => Ok(()),
+        _ => Err(invalid("entry_type", "must be anime or manga")),
+    }
+}
+
+/// Checks that a value is the hexadecimal form of an ObjectId.
+pub fn object_id(value: &str) -> Result<(), ValidationError> {
+    ObjectId::parse_str(value)
+        .map(|_| ())
+        .map_err(|_| invalid("object_id", "must be a valid ID"))
+}
+
+/// Checks that a value is a URL, or empty as Jikan leaves the unknown ones.
+pub fn url_or_empty(value: &str) -> Result<(), ValidationError> {
+    match value.is_empty() || value.validate_url() {
+        true => Ok(()),
+        false => Err(invalid("url", "must be a valid URL")),
+    }
+}
+
+/// Checks that a username only holds letters, digits, dashes and underscores.
+pub fn username_charset(value: &str) -> Result<(), ValidationError> {
+    match value
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        true => Ok(()),
+        false => Err(invalid(
+            "username",
+            "may only contain letters, digits, - and _",
+        )),
+    }
+}
+
+/// Checks that a date is not in the future.
+pub fn past(value: &DateTime) -> Result<(), ValidationError> {
+    match *value <= DateTime::now() {
+        true => Ok(()),
+        false => Err(invalid("past", "must not be in the future")),
+    }
+}
+
+/// Checks that a period does not end before it starts.
+pub fn period(from: Option<DateTime>, to: Option<DateTime>) -> Result<(), ValidationError> {
+    match (from, to) {
+        (Some(from), Some(to)) if from > to => {
+            Err(invalid("period", "from must not be later than to"))
+        }
+        _ => Ok(()),
+    }
+}
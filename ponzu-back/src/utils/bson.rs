@@ -1,9 +1,12 @@
+use crate::types::app_error::AppError;
+use crate::types::error_response::ErrorCode;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::serde_helpers::{
     deserialize_hex_string_from_object_id, serialize_hex_string_as_object_id,
 };
+use mongodb::bson::Bson;
 use serde::ser::Error;
-use serde::Serializer;
+use serde::{Deserialize, Serializer};
 use std::str::FromStr;
 
 /// Serializes an `Option<String>` as an `ObjectId` in hexadecimal format.
@@ -64,6 +67,41 @@ where
     }
 }
 
+/// Deserializes an optional RFC 3339 string into an `Option<mongodb::bson::DateTime>`.
+///
+/// # Parameters
+/// - `deserializer`: The deserializer to use.
+///
+/// # Returns
+/// A `Result` containing the `Option<mongodb::bson::DateTime>` if successful, or an `Error` if the string is not a valid date.
+pub fn deserialize_option_bson_datetime_from_rfc3339_string<'de, D>(
+    deserializer: D,
+) -> Result<Option<mongodb::bson::DateTime>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| mongodb::bson::DateTime::parse_rfc3339_str(s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Deserializes a BSON value that may be missing, telling a `null` value apart from a missing one.
+///
+/// Use along with `#[serde(default)]`: a missing field is `None`, and a `null` one is
+/// `Some(Bson::Null)`.
+///
+/// # Parameters
+/// - `deserializer`: The deserializer to use.
+///
+/// # Returns
+/// A `Result` containing the value, wrapped in `Some`, if successful.
+pub fn deserialize_present_bson<'de, D>(deserializer: D) -> Result<Option<Bson>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Bson::deserialize(deserializer).map(Some)
+}
+
 /// Converts a string to a MongoDB `ObjectId`.
 ///
 /// # Parameters
@@ -80,3 +118,14 @@ pub fn get_object_id(id: &str) -> Result<ObjectId, mongodb::error::Error> {
         ))),
     }
 }
+
+/// Parses a string received from a client into a MongoDB `ObjectId`.
+///
+/// # Parameters
+/// - `id`: The string representation of the `_id` field.
+///
+/// # Returns
+/// A `Result` containing the `ObjectId` if successful, or an `AppError` with status 400 otherwise.
+pub fn parse_object_id(id: &str) -> Result<ObjectId, AppError> {
+    get_object_id(id).map_err(|_| AppError::from((ErrorCode::InvalidId, "Ill-formed MongoId")))
+}
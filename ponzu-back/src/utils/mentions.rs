@@ -0,0 +1,87 @@
+/// Extracts the usernames mentioned in a text, e.g. `thanks @rin_t!`.
+///
+/// A mention starts with `@` at the start of the text or after a character that cannot be
+/// part of a username, so e-mail addresses are not mistaken for mentions. Usernames are made
+/// of letters, digits, `_`, `-` and `.`, without a trailing `.`.
+///
+/// # Parameters
+/// - `text`: The text to scan.
+///
+/// # Returns
+/// The distinct usernames, in order of first mention.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let is_name = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+
+    let mut mentions = Vec::<String>::new();
+    let mut previous = None;
+    for (i, c) in text.char_indices() {
+        if c == '@' && !previous.is_some_and(is_name) {
+            let name = text[i + 1..]
+                .split(|c: char| !is_name(c))
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('.');
+            if !name.is_empty() && !mentions.iter().any(|m| m == name) {
+                mentions.push(name.to_string());
+            }
+        }
+        previous = Some(c);
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_mentions;
+
+    #[test]
+    fn mentions_are_found_anywhere_in_the_text() {
+        assert_eq!(parse_mentions("@rin_t thanks!"), vec!["rin_t"]);
+        assert_eq!(
+            parse_mentions("thanks @rin_t and @kei-2"),
+            vec!["rin_t", "kei-2"]
+        );
+        assert_eq!(
+            parse_mentions("(@rin) said:@kei\n@mio"),
+            vec!["rin", "kei", "mio"]
+        );
+    }
+
+    #[test]
+    fn text_without_mentions_has_none() {
+        assert!(parse_mentions("").is_empty());
+        assert!(parse_mentions("no one here").is_empty());
+        assert!(parse_mentions("a lone @ sign, and @!").is_empty());
+    }
+
+    #[test]
+    fn e_mail_addresses_are_not_mentions() {
+        assert!(parse_mentions("write to rin@example.com").is_empty());
+        assert!(parse_mentions("a.b@c_d").is_empty());
+    }
+
+    #[test]
+    fn trailing_dots_end_the_sentence_not_the_username() {
+        assert_eq!(parse_mentions("ask @rin.t."), vec!["rin.t"]);
+        assert_eq!(parse_mentions("ask @rin..."), vec!["rin"]);
+        assert!(parse_mentions("@...").is_empty());
+    }
+
+    #[test]
+    fn each_username_is_reported_once_in_order_of_first_mention() {
+        assert_eq!(parse_mentions("@kei @rin @kei, @rin"), vec!["kei", "rin"]);
+        // Usernames are compared as written
+        assert_eq!(parse_mentions("@Rin @rin"), vec!["Rin", "rin"]);
+    }
+
+    #[test]
+    fn repeated_at_signs_mention_the_name_after_the_last() {
+        assert_eq!(parse_mentions("@@rin"), vec!["rin"]);
+    }
+
+    #[test]
+    fn non_ascii_usernames_are_supported() {
+        assert_eq!(parse_mentions("よろしく @りん!"), vec!["りん"]);
+        assert_eq!(parse_mentions("merci @zoé."), vec!["zoé"]);
+    }
+}
@@ -1 +1,7 @@
 pub mod bson;
+pub mod images;
+pub mod mentions;
+pub mod password;
+pub mod spoiler;
+#[cfg(test)]
+pub mod testing;
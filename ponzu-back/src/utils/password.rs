@@ -0,0 +1,37 @@
+use crate::types::app_error::AppError;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+
+/// Hashes a password with Argon2 and a random salt.
+///
+/// # Parameters
+/// - `password`: The plain text password.
+///
+/// # Returns
+/// The password hash in PHC string format.
+pub fn hash_password(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::from(e.to_string()))
+}
+
+/// Verifies a password against a stored hash.
+///
+/// # Parameters
+/// - `password`: The plain text password.
+/// - `hash`: The stored password hash in PHC string format.
+///
+/// # Returns
+/// `true` if the password matches the hash.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,117 @@
+/// The delimiter of inline spoilers, e.g. `the butler ||did it||`.
+const DELIMITER: &str = "||";
+
+/// Splits a text on its inline spoiler markup.
+///
+/// An unmatched trailing delimiter is kept as plain text.
+///
+/// # Parameters
+/// - `text`: The text to split.
+///
+/// # Returns
+/// The non-empty segments of the text, each paired with whether it is a spoiler.
+pub fn split_spoilers(text: &str) -> Vec<(bool, String)> {
+    let mut parts = text
+        .split(DELIMITER)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if parts.len() % 2 == 0 {
+        let unmatched = parts.pop().unwrap_or_default();
+        if let Some(last) = parts.last_mut() {
+            last.push_str(DELIMITER);
+            last.push_str(&unmatched);
+        }
+    }
+
+    parts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, part)| !part.is_empty())
+        .map(|(i, part)| (i % 2 == 1, part))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_spoilers;
+
+    fn plain(text: &str) -> (bool, String) {
+        (false, text.to_string())
+    }
+
+    fn spoiler(text: &str) -> (bool, String) {
+        (true, text.to_string())
+    }
+
+    #[test]
+    fn text_without_markup_is_a_single_plain_segment() {
+        assert_eq!(split_spoilers("no secrets"), vec![plain("no secrets")]);
+        assert!(split_spoilers("").is_empty());
+    }
+
+    #[test]
+    fn spoilers_are_split_from_the_surrounding_text() {
+        assert_eq!(
+            split_spoilers("the butler ||did it|| all along"),
+            vec![plain("the butler "), spoiler("did it"), plain(" all along")]
+        );
+        assert_eq!(split_spoilers("||all of it||"), vec![spoiler("all of it")]);
+    }
+
+    #[test]
+    fn unmatched_opening_delimiter_is_kept_as_text() {
+        assert_eq!(
+            split_spoilers("wait ||for it"),
+            vec![plain("wait ||for it")]
+        );
+        assert_eq!(
+            split_spoilers("||one|| and ||two"),
+            vec![spoiler("one"), plain(" and ||two")]
+        );
+    }
+
+    #[test]
+    fn unmatched_closing_delimiter_is_kept_as_text() {
+        assert_eq!(
+            split_spoilers("it ends|| here"),
+            vec![plain("it ends|| here")]
+        );
+        assert_eq!(split_spoilers("trailing||"), vec![plain("trailing||")]);
+        assert_eq!(split_spoilers("||"), vec![plain("||")]);
+    }
+
+    #[test]
+    fn empty_spoilers_are_dropped() {
+        assert_eq!(split_spoilers("a |||| b"), vec![plain("a "), plain(" b")]);
+        assert!(split_spoilers("||||").is_empty());
+    }
+
+    #[test]
+    fn adjacent_spoilers_stay_separate() {
+        assert_eq!(
+            split_spoilers("||first||||second||"),
+            vec![spoiler("first"), spoiler("second")]
+        );
+    }
+
+    #[test]
+    fn nested_delimiters_alternate_instead_of_nesting() {
+        assert_eq!(
+            split_spoilers("||outer ||inner|| outer||"),
+            vec![spoiler("outer "), plain("inner"), spoiler(" outer")]
+        );
+    }
+
+    #[test]
+    fn other_markup_inside_a_spoiler_is_kept_verbatim() {
+        assert_eq!(
+            split_spoilers("see ||**Episode 12** | the end||"),
+            vec![plain("see "), spoiler("**Episode 12** | the end")]
+        );
+    }
+
+    #[test]
+    fn a_third_pipe_belongs_to_the_text() {
+        assert_eq!(split_spoilers("|||x|||"), vec![spoiler("|x"), plain("|")]);
+    }
+}
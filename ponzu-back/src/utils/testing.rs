@@ -0,0 +1,27 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::{Client, Database};
+
+/// The variable holding the URL of the MongoDB server the database tests run against.
+const DATABASE_URL: &str = "PONZU_TEST_DATABASE_URL";
+
+/// Connects to a new, empty database on the test server.
+///
+/// Each call gets a database of its own, so tests can run in parallel. Drop it at the end of the
+/// test with `Database::drop`.
+///
+/// # Returns
+/// The database, or `None` when `PONZU_TEST_DATABASE_URL` is unset, in which case the test should
+/// return early.
+pub async fn test_database() -> Option<Database> {
+    let Some(url) = std::env::var(DATABASE_URL)
+        .ok()
+        .filter(|url| !url.is_empty())
+    else {
+        eprintln!("{} is unset, skipping a database test", DATABASE_URL);
+        return None;
+    };
+    let client = Client::with_uri_str(url)
+        .await
+        .expect("Failed to connect to the test database");
+    Some(client.database(&format!("ponzu_test_{}", ObjectId::new())))
+}